@@ -0,0 +1,93 @@
+//! Search-path benchmarks over a synthetic 50k-document index.
+//!
+//! Run with `cargo bench`. Compare against a baseline with
+//! `cargo bench -- --save-baseline before` / `--baseline before`.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+
+use drozosearch::config::Config;
+use drozosearch::index::fixtures;
+use drozosearch::index::reader::SearchEngine;
+use drozosearch::index::writer::IndexWriter;
+
+const DOCS: usize = 50_000;
+
+/// How often the background writer in `search_while_committing` commits
+const COMMIT_EVERY: Duration = Duration::from_millis(50);
+
+fn bench_search(c: &mut Criterion) {
+    let index = fixtures::synthetic_index(DOCS).expect("failed to build fixture index");
+    let engine = SearchEngine::new(index, &Config::default()).expect("failed to open reader");
+
+    let queries = [
+        ("short_prefix", "ma"),
+        ("common_term", "config"),
+        ("two_terms", "payment service"),
+        ("exact_name", "readme_120.md"),
+        ("size_filtered", "report size>1mb"),
+        ("filter_only", "size<10kb"),
+    ];
+
+    let mut group = c.benchmark_group("search");
+    for (name, query) in queries {
+        // Clear the result cache so every iteration does the full search
+        group.bench_function(name, |b| {
+            b.iter(|| {
+                engine.clear_cache();
+                engine.search(black_box(query), 200)
+            })
+        });
+    }
+    group.bench_function("cached_repeat", |b| {
+        b.iter(|| engine.search(black_box("payment service"), 200))
+    });
+    group.finish();
+}
+
+/// Searches while another writer keeps committing, as during a first pass
+/// or a busy watcher: every search checks for a new commit first
+fn bench_search_while_committing(c: &mut Criterion) {
+    let index = fixtures::synthetic_index(DOCS).expect("failed to build fixture index");
+    let engine =
+        SearchEngine::new(index.clone(), &Config::default()).expect("failed to open reader");
+
+    let stop = Arc::new(AtomicBool::new(false));
+    let committer = {
+        let stop = Arc::clone(&stop);
+        thread::spawn(move || {
+            let mut writer =
+                IndexWriter::new(&index, 50_000_000, 10_000, None).expect("failed to open writer");
+            let mut n = 0;
+            while !stop.load(Ordering::Relaxed) {
+                let path = format!("/home/bench/incoming/payment_{}.txt", n);
+                fixtures::add_files(&mut writer, &[(path.as_str(), "payment service notes")])
+                    .expect("failed to add file");
+                writer.commit().expect("failed to commit");
+                n += 1;
+                thread::sleep(COMMIT_EVERY);
+            }
+        })
+    };
+
+    let queries = ["p", "pa", "pay", "paym", "payme", "paymen", "payment"];
+    let mut next = 0;
+    c.bench_function("search_while_committing", |b| {
+        b.iter(|| {
+            let query = queries[next % queries.len()];
+            next += 1;
+            engine.clear_cache();
+            engine.search(black_box(query), 200)
+        })
+    });
+
+    stop.store(true, Ordering::Relaxed);
+    committer.join().expect("committer panicked");
+}
+
+criterion_group!(benches, bench_search, bench_search_while_committing);
+criterion_main!(benches);
@@ -0,0 +1,210 @@
+use std::io;
+use std::net::Ipv4Addr;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::thread;
+
+use tantivy::Index;
+use tiny_http::{Header, Method, Response, Server};
+
+use crate::config::Config;
+use crate::index::reader::SearchEngine;
+
+/// Results returned when the request doesn't give a `limit`
+const DEFAULT_LIMIT: usize = 50;
+
+/// Largest `limit` a request may ask for
+const MAX_LIMIT: usize = 1000;
+
+/// Changes the app passes on to the API's search engine, so the API ranks
+/// and filters results the way the app does
+pub enum ApiUpdate {
+    /// Settings changed
+    Configure(Box<Config>),
+    /// A result was opened and saved to the open history file
+    Opened,
+}
+
+/// Serve searches over HTTP on `127.0.0.1:config.api_port`:
+///
+/// `GET /search?q=<query>&limit=<n>` answers with the results as a JSON
+/// array, the same as [`SearchEngine::search_json`].
+///
+/// There is no authentication: any program on this machine can search the
+/// index, so the port must never be exposed to the network. Binding happens
+/// here so a taken port is reported; requests are then answered one at a
+/// time on a background thread with its own search engine over `index`.
+/// Send that engine the app's settings changes through the returned sender.
+pub fn start(index: Index, config: &Config) -> io::Result<Sender<ApiUpdate>> {
+    let server = Server::http((Ipv4Addr::LOCALHOST, config.api_port)).map_err(io::Error::other)?;
+    let mut engine = SearchEngine::new(index, config).map_err(io::Error::other)?;
+    let (updates_tx, updates) = mpsc::channel();
+    thread::spawn(move || serve(&server, &mut engine, &updates));
+    Ok(updates_tx)
+}
+
+fn serve(server: &Server, engine: &mut SearchEngine, updates: &Receiver<ApiUpdate>) {
+    for request in server.incoming_requests() {
+        for update in updates.try_iter() {
+            match update {
+                ApiUpdate::Configure(config) => engine.configure(&config),
+                ApiUpdate::Opened => engine.reload_open_history(),
+            }
+        }
+        let (status, content_type, body) = answer(request.method(), request.url(), engine);
+        let content_type = format!("{}; charset=utf-8", content_type);
+        let response = Response::from_string(body)
+            .with_status_code(status)
+            .with_header(Header::from_bytes("Content-Type", content_type).expect("valid header"));
+        if let Err(e) = request.respond(response) {
+            eprintln!("drozosearch: API request failed ({})", e);
+        }
+    }
+}
+
+/// Status, content type and body for a request of `url` (path and query)
+fn answer(method: &Method, url: &str, engine: &SearchEngine) -> (u16, &'static str, String) {
+    let (path, query) = url.split_once('?').unwrap_or((url, ""));
+    if *method != Method::Get {
+        return (405, "text/plain", "only GET is supported\n".into());
+    }
+    if path != "/search" {
+        return (404, "text/plain", "try GET /search?q=...\n".into());
+    }
+
+    let mut q = None;
+    let mut limit = DEFAULT_LIMIT;
+    for (key, value) in query.split('&').filter_map(|pair| pair.split_once('=')) {
+        match key {
+            "q" => q = Some(url_decode(value)),
+            "limit" => match value.parse::<usize>() {
+                Ok(n) if n > 0 => limit = n.min(MAX_LIMIT),
+                _ => return (400, "text/plain", "limit must be a positive number\n".into()),
+            },
+            _ => {}
+        }
+    }
+    let Some(q) = q else {
+        return (400, "text/plain", "missing q parameter\n".into());
+    };
+    (200, "application/json", engine.search_json(&q, limit))
+}
+
+/// Decode a query string value: `+` is a space and `%XX` a byte. Malformed
+/// escapes are kept as they are.
+fn url_decode(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'+' => decoded.push(b' '),
+            b'%' => {
+                let byte = value
+                    .get(i + 1..i + 3)
+                    .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+                if let Some(byte) = byte {
+                    decoded.push(byte);
+                    i += 2;
+                } else {
+                    decoded.push(b'%');
+                }
+            }
+            b => decoded.push(b),
+        }
+        i += 1;
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{Read, Write};
+    use std::net::TcpStream;
+
+    use super::*;
+    use crate::index::fixtures;
+
+    fn test_config(history: &tempfile::TempDir) -> Config {
+        Config {
+            root_dirs: vec!["/r".into()],
+            open_history_path: history.path().join("open_history.tsv"),
+            ..Config::default()
+        }
+    }
+
+    /// Serve an index of two files on a free port; returns the address and
+    /// the sender of updates for its engine
+    fn server() -> (std::net::SocketAddr, Sender<ApiUpdate>, tempfile::TempDir) {
+        let history = tempfile::tempdir().unwrap();
+        let index = fixtures::index_of(&[("/r/report.pdf", ""), ("/r/notes.txt", "report draft")]).unwrap();
+        let mut engine = SearchEngine::new(index, &test_config(&history)).unwrap();
+        let server = Server::http((Ipv4Addr::LOCALHOST, 0)).unwrap();
+        let addr = server.server_addr().to_ip().unwrap();
+        let (updates_tx, updates) = mpsc::channel();
+        thread::spawn(move || serve(&server, &mut engine, &updates));
+        (addr, updates_tx, history)
+    }
+
+    /// Send a raw request; returns the status code and body
+    fn request(addr: std::net::SocketAddr, method: &str, target: &str) -> (u16, String) {
+        let mut stream = TcpStream::connect(addr).unwrap();
+        write!(stream, "{} {} HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n", method, target).unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        let status = response[9..12].parse().unwrap();
+        let body = response.split_once("\r\n\r\n").unwrap().1.to_string();
+        (status, body)
+    }
+
+    #[test]
+    fn search_answers_with_json() {
+        let (addr, _updates, _history) = server();
+        let (status, body) = request(addr, "GET", "/search?q=report&limit=5");
+        assert_eq!(status, 200);
+        let results: Vec<serde_json::Value> = serde_json::from_str(&body).unwrap();
+        assert_eq!(results.len(), 2);
+
+        let (status, body) = request(addr, "GET", "/search?limit=1&q=report+draft");
+        assert_eq!(status, 200);
+        let results: Vec<serde_json::Value> = serde_json::from_str(&body).unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0]["file_path"], "/r/notes.txt");
+    }
+
+    #[test]
+    fn settings_changed_in_the_app_apply_to_later_requests() {
+        let (addr, updates, history) = server();
+        let config = Config {
+            names_only: true,
+            ..test_config(&history)
+        };
+        updates.send(ApiUpdate::Configure(Box::new(config))).unwrap();
+        let (_, body) = request(addr, "GET", "/search?q=report");
+        let results: Vec<serde_json::Value> = serde_json::from_str(&body).unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0]["file_path"], "/r/report.pdf");
+    }
+
+    #[test]
+    fn bad_requests_are_refused() {
+        let (addr, _updates, _history) = server();
+        assert_eq!(request(addr, "GET", "/search").0, 400);
+        assert_eq!(request(addr, "GET", "/search?q=x&limit=0").0, 400);
+        assert_eq!(request(addr, "GET", "/search?q=x&limit=many").0, 400);
+        assert_eq!(request(addr, "GET", "/").0, 404);
+        assert_eq!(request(addr, "POST", "/search?q=x").0, 405);
+    }
+
+    #[test]
+    fn query_values_are_decoded() {
+        assert_eq!(url_decode("report+draft"), "report draft");
+        assert_eq!(url_decode("ext%3Ars%20main"), "ext:rs main");
+        assert_eq!(url_decode("caf%C3%A9"), "café");
+        // Malformed escapes stay as they are; stray bytes don't panic
+        assert_eq!(url_decode("100%"), "100%");
+        assert_eq!(url_decode("%zz%4"), "%zz%4");
+        assert_eq!(url_decode("%FF"), "\u{FFFD}");
+        assert_eq!(url_decode("%e9%"), "\u{FFFD}%");
+        assert_eq!(url_decode("ż%"), "ż%");
+    }
+}
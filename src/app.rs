@@ -1,85 +1,260 @@
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::path::PathBuf;
 use std::sync::mpsc::{self, Receiver, Sender};
 use std::thread;
 use std::time::Instant;
 
 use eframe::egui;
+use global_hotkey::GlobalHotKeyEvent;
+use serde::{Deserialize, Serialize};
 use tantivy::Index;
 use tray_icon::menu::{Menu, MenuEvent, MenuItem, PredefinedMenuItem};
 use tray_icon::{TrayIconBuilder, TrayIconEvent};
 
-use crate::config::Config;
-use crate::index::reader::SearchEngine;
-use crate::index::schema;
-use crate::indexer::coordinator;
-use crate::types::*;
+use drozosearch::api::{self, ApiUpdate};
+use drozosearch::config::{CloseAction, Config, DirectoryOrder, RankWeights, SavedSearch, Theme};
+use drozosearch::index::{integrity, schema};
+use drozosearch::index::query::{regex_pattern, NameRegex};
+use drozosearch::index::reader::{SearchEngine, SearchPage};
+use drozosearch::index::stats::{self, IndexHealth};
+use drozosearch::indexer::coordinator::{self, IndexerCommand};
+use drozosearch::indexer::progress::{progress_fraction, UiProgress};
+use drozosearch::indexer::volumes;
+use drozosearch::opener;
+use drozosearch::trash;
+use drozosearch::types::*;
+
+use crate::export::{self, ExportOutcome};
+use crate::git::{GitStatus, GitStatusCache};
+use crate::history;
+use crate::hotkey;
+use crate::theme;
+use crate::thumbnail::{self, ThumbnailCache};
+
+/// Opening more selected files than this at once asks for confirmation first
+const OPEN_MANY_CONFIRM_THRESHOLD: usize = 10;
 
 pub struct DrozoSearchApp {
+    config: Config,
     query: String,
     last_query_sent: String,
     last_keystroke: Instant,
+    // Recent searches, most recent first; Up in the empty search box recalls them
+    search_history: Vec<String>,
+    // Which of them the search box holds while stepping back with Up
+    history_pos: Option<usize>,
+    // The recent searches dropdown was under the pointer last frame, so it
+    // stays up while being clicked even though the search box lost focus
+    history_menu_hovered: bool,
+    // Results as shown, in `sort_column` order
     results: Vec<SearchResult>,
+    // Results in the order the search engine ranked them
+    ranked_results: Vec<SearchResult>,
+    // The query they answer; behind `query` while a search is under way
+    results_query: String,
+    // Documents matching the query (an estimate), and whether "Load more"
+    // can fetch another page or is fetching one
+    total_hits: usize,
+    has_more: bool,
+    loading_more: bool,
+    sort_column: SortColumn,
+    sort_ascending: bool,
     selected_index: Option<usize>,
+    // Rows picked with Ctrl/Cmd+click or Shift+click; empty unless more than
+    // the cursor row is selected
+    multi_selected: BTreeSet<usize>,
+    // Row a Shift+click range starts from; the cursor row if unset
+    selection_anchor: Option<usize>,
+    // Files waiting for confirmation before they are all opened
+    pending_open: Option<Vec<(PathBuf, bool)>>,
     first_frame: bool,
     scroll_to_selected: bool,
+    // Height of each row in `results` as last drawn, or estimated if it
+    // hasn't been yet; places the rows without laying out the ones off screen
+    row_heights: Vec<f32>,
     context_menu_index: Option<usize>,
 
-    search_tx: Sender<String>,
-    results_rx: Receiver<Vec<SearchResult>>,
+    search_tx: Sender<SearchCommand>,
+    results_rx: Receiver<SearchReply>,
     progress_rx: Receiver<IndexProgress>,
 
     files_indexed: u64,
     estimated_total: u64,
+    files_per_sec: f64,
+    eta_seconds: Option<u64>,
     index_status: IndexStatus,
+    // Highest progress fraction reported this run; the bar never moves back
+    progress_target: f32,
 
     logo_texture: Option<egui::TextureHandle>,
-
-    // Tray icon (must stay alive)
+    git_status: Option<GitStatusCache>,
+    thumbnails: ThumbnailCache,
+
+    // Indexer inputs held back until the user picks a folder to index
+    pending_indexer: Option<(Index, Sender<IndexProgress>)>,
+    // Re-runs indexing after the indexed folders change; None until started
+    indexer_tx: Option<Sender<IndexerCommand>>,
+    // Keeps other copies of the app from indexing the same index
+    _index_lock: Option<integrity::IndexLock>,
+    // Another copy of the app holds the index: search only, never index
+    index_read_only: bool,
+    root_input: String,
+    // Answer from a native folder dialog that is still open
+    folder_pick_rx: Option<Receiver<Option<PathBuf>>>,
+
+    // Glob typed into the "copy matching paths" action
+    copy_glob: String,
+    // CSV export whose save dialog or write is still running
+    export_rx: Option<Receiver<ExportOutcome>>,
+
+    // Handle for reading index statistics; indexing and search own their own
+    index: Index,
+    // Statistics shown in settings, and a collection still running
+    index_health: Option<Result<IndexHealth, String>>,
+    index_health_rx: Option<Receiver<Result<IndexHealth, String>>>,
+
+    // Files waiting for confirmation before they're deleted for good
+    pending_delete: Option<Vec<PathBuf>>,
+    // Finished deletions, sent from the threads doing them
+    deletes_tx: Sender<trash::DeleteOutcome>,
+    deletes_rx: Receiver<trash::DeleteOutcome>,
+
+    // "Wipe the index and start over?" confirmation
+    rebuild_prompt_open: bool,
+
+    // Files the last pass couldn't read, listed in a window
+    failures_open: bool,
+
+    // Name being typed for "Save this search"
+    save_search_name: Option<String>,
+
+    // First-close "hide or quit?" prompt
+    close_prompt_open: bool,
+    close_prompt_remember: bool,
+    quit_requested: bool,
+
+    // Sticky "search only in this folder" restriction
+    search_scope: Option<PathBuf>,
+    // "Find duplicates" mode: results are groups of files with identical contents
+    find_duplicates: bool,
+
+    // One-off message shown under the status row until dismissed
+    notice: Option<String>,
+    // Why the current query can't be searched (e.g. a broken `/regex/`);
+    // shown instead of "No results" until the query changes
+    query_error: Option<String>,
+
+    // Settings window
+    settings_open: bool,
+    // Indexed folders as edited in settings, applied on save
+    roots_draft: Option<Vec<PathBuf>>,
+    root_availability: Vec<(std::path::PathBuf, bool)>,
+    root_availability_checked: Option<Instant>,
+
+    // Tray icon (must stay alive); None if the tray couldn't be created
     _tray_icon: Option<tray_icon::TrayIcon>,
+    tray_error: Option<String>,
+    // The global "bring up drozoSearch" shortcut (must stay alive)
+    hotkey: Option<hotkey::Registration>,
+    hotkey_error: Option<String>,
     tray_show_id: tray_icon::menu::MenuId,
+    tray_rescan_id: tray_icon::menu::MenuId,
     tray_quit_id: tray_icon::menu::MenuId,
     window_visible: bool,
 }
 
+/// Room below the last result row for the "Load more results" button
+const LOAD_MORE_HEIGHT: f32 = 40.0;
+
+/// A query unchanged this long after the last keystroke goes into the search history
+const HISTORY_SETTLE: std::time::Duration = std::time::Duration::from_secs(2);
+/// How many recent searches the dropdown lists
+const RECENT_SEARCHES_SHOWN: usize = 8;
+/// How often the global shortcut is checked for presses
+const HOTKEY_POLL: std::time::Duration = std::time::Duration::from_millis(250);
+
 impl DrozoSearchApp {
     pub fn new(cc: &eframe::CreationContext<'_>) -> Self {
-        // Dark theme
-        let mut visuals = egui::Visuals::dark();
-        visuals.window_shadow = egui::epaint::Shadow::NONE;
-        visuals.widgets.noninteractive.bg_fill = egui::Color32::from_gray(22);
-        visuals.widgets.inactive.bg_fill = egui::Color32::from_gray(32);
-        visuals.widgets.hovered.bg_fill = egui::Color32::from_gray(42);
-        visuals.widgets.active.bg_fill = egui::Color32::from_gray(50);
-        visuals.selection.bg_fill = egui::Color32::from_rgb(35, 75, 130);
-        visuals.extreme_bg_color = egui::Color32::from_gray(16);
-        cc.egui_ctx.set_visuals(visuals);
-
-        let mut style = (*cc.egui_ctx.style()).clone();
-        style.spacing.item_spacing = egui::vec2(6.0, 1.0);
-        cc.egui_ctx.set_style(style);
-
-        let config = Config::default();
+        let config = Config::load();
+        theme::apply_theme(&cc.egui_ctx, config.theme);
+        let view: ViewState = cc
+            .storage
+            .and_then(|storage| eframe::get_value(storage, ViewState::KEY))
+            .unwrap_or_default();
         std::fs::create_dir_all(&config.index_path).expect("Failed to create index directory");
 
-        let tantivy_schema = schema::build_schema();
-        // Open existing index or create a new one
-        let index = Index::open_in_dir(&config.index_path).unwrap_or_else(|_| {
-            Index::create_in_dir(&config.index_path, tantivy_schema.clone())
-                .expect("Failed to create tantivy index")
-        });
+        // A second copy of the app searches the index the first one keeps up
+        // to date instead of indexing (or repairing) it at the same time
+        let (index_lock, read_only) = match integrity::lock(&config.index_path) {
+            Ok(Some(lock)) => (Some(lock), false),
+            Ok(None) => (None, true),
+            Err(e) => {
+                eprintln!("drozosearch: can't lock the index ({}), opening it anyway", e);
+                (None, false)
+            }
+        };
+
+        // Open existing index or create a new one, rebuilding it if it's corrupt
+        let (index, index_notice) = if read_only {
+            let index = integrity::open_read_only(&config.index_path).unwrap_or_else(|e| {
+                eprintln!("drozosearch: can't open the other instance's index ({})", e);
+                // Nothing to search, but the window still says why
+                let index = Index::create_in_ram(schema::build_schema());
+                schema::register_tokenizers(&index);
+                index
+            });
+            (index, None)
+        } else {
+            integrity::open_or_repair(&config.index_path).expect("Failed to open tantivy index")
+        };
 
-        let (search_tx, search_rx) = mpsc::channel::<String>();
-        let (results_tx, results_rx) = mpsc::channel::<Vec<SearchResult>>();
+        let (search_tx, search_rx) = mpsc::channel::<SearchCommand>();
+        let (results_tx, results_rx) = mpsc::channel::<SearchReply>();
         let (progress_tx, progress_rx) = mpsc::channel::<IndexProgress>();
+        let (deletes_tx, deletes_rx) = mpsc::channel();
+
+        // Local HTTP API for other tools, if enabled
+        let mut notice = index_notice.map(str::to_string);
+        let mut api_tx = None;
+        if config.api_port != 0 {
+            match api::start(index.clone(), &config) {
+                Ok(tx) => api_tx = Some(tx),
+                Err(e) => {
+                    eprintln!("drozosearch: can't serve the API on port {} ({})", config.api_port, e);
+                    notice = Some(format!("Search API not started on port {}: {}", config.api_port, e));
+                }
+            }
+        }
 
         let search_index = index.clone();
+        let stats_index = index.clone();
+        let search_config = config.clone();
         let search_ctx = cc.egui_ctx.clone();
         thread::spawn(move || {
-            search_thread(search_index, search_rx, results_tx, search_ctx);
+            search_thread(search_index, search_config, search_rx, results_tx, api_tx, search_ctx);
         });
 
-        // Always run incremental indexing — it will skip unchanged files
-        let _indexer_handle =
-            coordinator::start_indexing(index, config, progress_tx, cc.egui_ctx.clone());
+        // Always run incremental indexing — it will skip unchanged files.
+        // With no folder configured, wait until the user picks one.
+        let (pending_indexer, indexer_tx) = if read_only {
+            (None, None)
+        } else if config.root_dirs.is_empty() {
+            (Some((index, progress_tx)), None)
+        } else {
+            let indexer_tx = coordinator::start_indexing(
+                index,
+                config.clone(),
+                UiProgress::new(progress_tx, cc.egui_ctx.clone()),
+            );
+            (None, Some(indexer_tx))
+        };
+        let index_status = if read_only {
+            IndexStatus::Error(integrity::HELD_ELSEWHERE.to_string())
+        } else if pending_indexer.is_some() {
+            IndexStatus::NoRoots
+        } else {
+            IndexStatus::Starting
+        };
 
         // Load logo texture
         let logo_texture = {
@@ -97,12 +272,15 @@ impl DrozoSearchApp {
 
         // ── Build tray icon ──
         let show_item = MenuItem::new("Show drozoSearch", true, None);
+        let rescan_item = MenuItem::new("Re-index now", true, None);
         let quit_item = MenuItem::new("Quit", true, None);
         let show_id = show_item.id().clone();
+        let rescan_id = rescan_item.id().clone();
         let quit_id = quit_item.id().clone();
 
         let tray_menu = Menu::new();
         let _ = tray_menu.append(&show_item);
+        let _ = tray_menu.append(&rescan_item);
         let _ = tray_menu.append(&PredefinedMenuItem::separator());
         let _ = tray_menu.append(&quit_item);
 
@@ -120,63 +298,327 @@ impl DrozoSearchApp {
                 .with_tooltip("drozoSearch")
                 .with_icon(icon)
                 .build()
-                .ok()
+        };
+        // Without a tray (e.g. Linux with no StatusNotifier host) a hidden
+        // window could never be brought back, so closing has to quit instead
+        let (tray_icon, tray_error) = match tray_icon {
+            Ok(tray) => (Some(tray), None),
+            Err(e) => {
+                eprintln!("drozosearch: system tray unavailable ({}), closing will quit", e);
+                (None, Some(e.to_string()))
+            }
+        };
+
+        let (hotkey, hotkey_error) = if config.global_hotkey.trim().is_empty() {
+            (None, None)
+        } else {
+            match hotkey::register(&config.global_hotkey) {
+                Ok(registration) => (Some(registration), None),
+                Err(e) => {
+                    eprintln!("drozosearch: global shortcut {} unavailable ({})", config.global_hotkey, e);
+                    (None, Some(e))
+                }
+            }
         };
 
         DrozoSearchApp {
             query: String::new(),
             last_query_sent: String::new(),
             last_keystroke: Instant::now(),
+            search_history: history::load(),
+            history_pos: None,
+            history_menu_hovered: false,
             results: Vec::new(),
+            ranked_results: Vec::new(),
+            results_query: String::new(),
+            total_hits: 0,
+            has_more: false,
+            loading_more: false,
+            sort_column: view.sort_column,
+            sort_ascending: view.sort_ascending,
             selected_index: None,
+            multi_selected: BTreeSet::new(),
+            selection_anchor: None,
+            pending_open: None,
+            search_scope: None,
+            find_duplicates: false,
+            pending_delete: None,
+            deletes_tx,
+            deletes_rx,
+            rebuild_prompt_open: false,
+            failures_open: false,
+            save_search_name: None,
+            close_prompt_open: false,
+            close_prompt_remember: false,
+            quit_requested: false,
+            copy_glob: String::new(),
+            export_rx: None,
+            index: stats_index,
+            index_health: None,
+            index_health_rx: None,
+            pending_indexer,
+            indexer_tx,
+            _index_lock: index_lock,
+            index_read_only: read_only,
+            root_input: String::new(),
+            folder_pick_rx: None,
             first_frame: true,
             scroll_to_selected: false,
+            row_heights: Vec::new(),
             context_menu_index: None,
             search_tx,
             results_rx,
             progress_rx,
             files_indexed: 0,
             estimated_total: 0,
-            index_status: IndexStatus::Starting,
+            files_per_sec: 0.0,
+            eta_seconds: None,
+            progress_target: 0.0,
+            index_status,
             logo_texture,
+            git_status: config
+                .show_git_status
+                .then(|| GitStatusCache::new(cc.egui_ctx.clone())),
+            thumbnails: ThumbnailCache::new(cc.egui_ctx.clone()),
+            notice,
+            query_error: None,
+            settings_open: view.settings_open,
+            roots_draft: None,
+            root_availability: Vec::new(),
+            root_availability_checked: None,
+            config,
             _tray_icon: tray_icon,
+            tray_error,
+            hotkey,
+            hotkey_error,
             tray_show_id: show_id,
+            tray_rescan_id: rescan_id,
             tray_quit_id: quit_id,
             window_visible: true,
         }
     }
 }
 
+/// View state carried over between runs, next to the window geometry eframe
+/// keeps. Unlike `config.toml` this isn't meant for editing.
+#[derive(Serialize, Deserialize)]
+#[serde(default)]
+struct ViewState {
+    sort_column: SortColumn,
+    sort_ascending: bool,
+    settings_open: bool,
+}
+
+impl ViewState {
+    const KEY: &'static str = "view";
+}
+
+impl Default for ViewState {
+    fn default() -> Self {
+        ViewState {
+            sort_column: SortColumn::Relevance,
+            sort_ascending: true,
+            settings_open: false,
+        }
+    }
+}
+
+/// Column the result list is sorted by. `Relevance` is the search engine's
+/// composite-score order.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SortColumn {
+    Relevance,
+    Name,
+    Location,
+    Type,
+    Size,
+    Modified,
+}
+
+impl SortColumn {
+    fn compare(&self, a: &SearchResult, b: &SearchResult) -> std::cmp::Ordering {
+        match self {
+            SortColumn::Relevance => std::cmp::Ordering::Equal,
+            SortColumn::Name => a.file_name.to_lowercase().cmp(&b.file_name.to_lowercase()),
+            SortColumn::Location => a.file_path.parent().cmp(&b.file_path.parent()),
+            SortColumn::Type => a.match_type.to_string().cmp(&b.match_type.to_string()),
+            SortColumn::Size => a.file_size.cmp(&b.file_size),
+            SortColumn::Modified => a.modified.cmp(&b.modified),
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            SortColumn::Relevance => "Relevance",
+            SortColumn::Name => "Name",
+            SortColumn::Location => "Location",
+            SortColumn::Type => "Type",
+            SortColumn::Size => "Size",
+            SortColumn::Modified => "Modified",
+        }
+    }
+}
+
+/// Orders offered by the sort selector above the results, as the column
+/// and direction the headers would set for them
+const SORT_CHOICES: [(&str, SortColumn, bool); 5] = [
+    ("Relevance", SortColumn::Relevance, true),
+    ("Newest", SortColumn::Modified, false),
+    ("Oldest", SortColumn::Modified, true),
+    ("Largest", SortColumn::Size, false),
+    ("Name", SortColumn::Name, true),
+];
+
+/// Messages from the UI to the search thread
+enum SearchCommand {
+    Query(String),
+    /// Settings changed; applies to subsequent queries
+    Configure(Box<Config>),
+    /// Restrict subsequent queries to a folder (None clears it)
+    Scope(Option<PathBuf>),
+    /// Answer subsequent queries with groups of duplicate files, or go back
+    /// to ranked results
+    Duplicates(bool),
+    /// A result was opened; feeds the open-history ranking boost
+    Opened(PathBuf),
+    /// Send the next page of the last query's results
+    LoadMore,
+}
+
+/// Results from the search thread
+struct SearchReply {
+    /// The query the page answers
+    query: String,
+    page: SearchPage,
+    /// The page continues the results shown ("Load more") instead of
+    /// replacing them
+    appended: bool,
+}
+
+/// Results fetched per search, and per "Load more"
+const PAGE_SIZE: usize = 200;
+
+/// Answers queries with its own engine. Settings changes and opens are
+/// passed on to the HTTP API's engine, if it's running.
 fn search_thread(
     index: Index,
-    rx: Receiver<String>,
-    tx: Sender<Vec<SearchResult>>,
+    config: Config,
+    rx: Receiver<SearchCommand>,
+    tx: Sender<SearchReply>,
+    api: Option<Sender<ApiUpdate>>,
     ctx: egui::Context,
 ) {
-    let engine = SearchEngine::new(index);
+    let mut engine = match SearchEngine::new(index, &config) {
+        Ok(engine) => engine,
+        Err(e) => {
+            eprintln!("drozosearch: failed to open index reader ({}), search disabled", e);
+            return;
+        }
+    };
+    let mut duplicates = false;
+    // The query "Load more" continues, and how many of its results were sent
+    let mut current = String::new();
+    let mut loaded = 0;
     loop {
-        let mut query = match rx.recv() {
-            Ok(q) => q,
+        let first = match rx.recv() {
+            Ok(cmd) => cmd,
             Err(_) => return,
         };
-        while let Ok(newer) = rx.try_recv() {
-            query = newer;
+        // Drain the backlog: apply every config change, keep only the newest query
+        let mut query = None;
+        let mut more = false;
+        for cmd in std::iter::once(first).chain(rx.try_iter()) {
+            match cmd {
+                SearchCommand::Query(q) => query = Some(q),
+                SearchCommand::LoadMore => more = true,
+                SearchCommand::Configure(config) => {
+                    engine.configure(&config);
+                    if let Some(api) = &api {
+                        let _ = api.send(ApiUpdate::Configure(config));
+                    }
+                }
+                SearchCommand::Scope(dir) => engine.set_scope(dir.as_deref()),
+                SearchCommand::Duplicates(on) => duplicates = on,
+                SearchCommand::Opened(path) => {
+                    // Saved to the history file, which the API's engine re-reads
+                    engine.record_open(&path);
+                    if let Some(api) = &api {
+                        let _ = api.send(ApiUpdate::Opened);
+                    }
+                }
+            }
         }
-        let results = engine.search(&query, 200);
-        let _ = tx.send(results);
+        // A new query makes "Load more" for the previous one moot
+        let reply = if let Some(query) = query {
+            let page = if duplicates {
+                let results = engine.duplicates(&query, PAGE_SIZE);
+                SearchPage {
+                    total_hits: results.len(),
+                    results,
+                    has_more: false,
+                }
+            } else {
+                engine.search_page(&query, 0, PAGE_SIZE)
+            };
+            current = query;
+            loaded = page.results.len();
+            SearchReply { query: current.clone(), page, appended: false }
+        } else if more && !duplicates {
+            let page = engine.search_page(&current, loaded, PAGE_SIZE);
+            loaded += page.results.len();
+            SearchReply { query: current.clone(), page, appended: true }
+        } else {
+            continue;
+        };
+        let _ = tx.send(reply);
         ctx.request_repaint();
     }
 }
 
 impl eframe::App for DrozoSearchApp {
     fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        // The OS preference may have flipped under Theme::System
+        theme::sync(ctx);
+
         // ── Handle window close → hide to tray ──
-        if ctx.input(|i| i.viewport().close_requested()) {
-            ctx.send_viewport_cmd(egui::ViewportCommand::CancelClose);
-            ctx.send_viewport_cmd(egui::ViewportCommand::Visible(false));
-            self.window_visible = false;
-            #[cfg(target_os = "macos")]
-            macos_hide_app();
+        let has_tray = self.tray_error.is_none();
+        if ctx.input(|i| i.viewport().close_requested()) && !self.quit_requested && has_tray {
+            if self.config.ask_on_close {
+                // Ask first; the prompt hides or quits once the user decides
+                ctx.send_viewport_cmd(egui::ViewportCommand::CancelClose);
+                self.close_prompt_open = true;
+            } else if self.config.close_action == CloseAction::HideToTray {
+                ctx.send_viewport_cmd(egui::ViewportCommand::CancelClose);
+                self.hide_to_tray(ctx);
+            }
+        }
+
+        // Ctrl/Cmd+Shift+H switches between hiding to the tray and quitting on close
+        if has_tray
+            && ctx.input_mut(|i| {
+                i.consume_key(egui::Modifiers::COMMAND | egui::Modifiers::SHIFT, egui::Key::H)
+            })
+        {
+            self.config.close_action = match self.config.close_action {
+                CloseAction::HideToTray => CloseAction::Quit,
+                CloseAction::Quit => CloseAction::HideToTray,
+            };
+            self.config.ask_on_close = false;
+            self.notice = Some(match self.config.save() {
+                Ok(()) => format!(
+                    "Closing the window now: {}",
+                    self.config.close_action.label().to_lowercase()
+                ),
+                Err(e) => format!("Couldn't save settings: {}", e),
+            });
+        }
+
+        // Ctrl/Cmd+Shift+F switches between searching names only and everything
+        if ctx.input_mut(|i| {
+            i.consume_key(egui::Modifiers::COMMAND | egui::Modifiers::SHIFT, egui::Key::F)
+        }) {
+            self.set_names_only(!self.config.names_only);
         }
 
         // ── Poll tray events ──
@@ -189,72 +631,218 @@ impl eframe::App for DrozoSearchApp {
                     #[cfg(target_os = "macos")]
                     macos_hide_app();
                 } else {
-                    ctx.send_viewport_cmd(egui::ViewportCommand::Visible(true));
-                    ctx.send_viewport_cmd(egui::ViewportCommand::Focus);
-                    self.window_visible = true;
-                    #[cfg(target_os = "macos")]
-                    macos_show_app();
+                    self.show_window(ctx);
                 }
             }
         }
         if let Ok(event) = MenuEvent::receiver().try_recv() {
             if event.id() == &self.tray_show_id {
-                ctx.send_viewport_cmd(egui::ViewportCommand::Visible(true));
-                ctx.send_viewport_cmd(egui::ViewportCommand::Focus);
-                self.window_visible = true;
-                #[cfg(target_os = "macos")]
-                macos_show_app();
+                self.show_window(ctx);
+            } else if event.id() == &self.tray_rescan_id {
+                self.rescan_index();
             } else if event.id() == &self.tray_quit_id {
-                std::process::exit(0);
+                self.quit_requested = true;
+                ctx.send_viewport_cmd(egui::ViewportCommand::Close);
+            }
+        }
+        // Global shortcut: bring the window up with the search box focused
+        if let Some(hotkey) = &self.hotkey {
+            if GlobalHotKeyEvent::receiver().try_iter().any(|event| hotkey.is_pressed(&event)) {
+                self.show_window(ctx);
+                self.first_frame = true;
             }
+            // Presses don't wake the UI up by themselves
+            ctx.request_repaint_after(HOTKEY_POLL);
         }
 
         // ── Poll channels ──
-        while let Ok(results) = self.results_rx.try_recv() {
-            self.results = results;
+        while let Ok(reply) = self.results_rx.try_recv() {
+            self.total_hits = reply.page.total_hits;
+            self.has_more = reply.page.has_more;
+            self.loading_more = false;
+            if reply.appended {
+                let selected = self
+                    .selected_index
+                    .and_then(|i| self.results.get(i))
+                    .map(|r| r.file_path.clone());
+                self.ranked_results.extend(reply.page.results);
+                self.apply_sort();
+                // New rows sorted in between shift the indices of the old ones
+                if let Some(path) = selected {
+                    self.selected_index = self.results.iter().position(|r| r.file_path == path);
+                }
+                if self.sort_column != SortColumn::Relevance {
+                    self.clear_multi_selection();
+                }
+            } else {
+                self.ranked_results = reply.page.results;
+                self.results_query = reply.query;
+                self.clear_multi_selection();
+                self.apply_sort();
+            }
         }
+        self.poll_export();
+        self.poll_deletes();
+        self.poll_index_health();
+        let was_building = index_is_building(&self.index_status);
         while let Ok(progress) = self.progress_rx.try_recv() {
             self.files_indexed = progress.files_indexed;
             self.estimated_total = progress.estimated_total;
+            self.files_per_sec = progress.files_per_sec;
+            self.eta_seconds = progress.eta_seconds;
             self.index_status = progress.status;
+            self.progress_target = match self.index_status {
+                IndexStatus::Indexing => {
+                    progress_fraction(self.progress_target, self.files_indexed, self.estimated_total)
+                }
+                IndexStatus::Committing => self.progress_target,
+                _ => 0.0,
+            };
+        }
+        // Results shown during the scan came from a partial index; search again
+        // now that it's complete
+        if was_building
+            && matches!(self.index_status, IndexStatus::Ready(_) | IndexStatus::Watching(_))
+            && self.is_searchable(&self.last_query_sent)
+        {
+            let _ = self
+                .search_tx
+                .send(SearchCommand::Query(self.last_query_sent.clone()));
+        }
+
+        // ── Hidden in the tray: skip layout and animations entirely ──
+        // Indexer progress still wakes us up, so the state above stays current
+        // and the UI is up to date as soon as the window is shown again.
+        if !self.window_visible && self.config.idle_when_hidden {
+            return;
         }
 
         // ── Debounced search ──
         if self.query != self.last_query_sent
             && self.last_keystroke.elapsed().as_millis() >= 150
         {
-            let _ = self.search_tx.send(self.query.clone());
-            self.last_query_sent = self.query.clone();
+            self.send_query();
         }
         if self.query != self.last_query_sent {
             ctx.request_repaint_after(std::time::Duration::from_millis(50));
         }
+        // A search left standing for a moment goes into the history
+        if self.history_pos.is_none()
+            && self.query == self.last_query_sent
+            && !self.query.trim().is_empty()
+            && self.query_error.is_none()
+            && self.search_history.first() != Some(&self.query)
+        {
+            let idle = self.last_keystroke.elapsed();
+            if idle >= HISTORY_SETTLE {
+                history::remember(&mut self.search_history, &self.query);
+                if let Err(e) = history::save(&self.search_history) {
+                    eprintln!("drozosearch: couldn't save search history ({})", e);
+                }
+            } else {
+                ctx.request_repaint_after(HISTORY_SETTLE - idle);
+            }
+        }
 
         // ── Keyboard navigation ──
-        let down = ctx.input(|i| i.key_pressed(egui::Key::ArrowDown));
-        let up = ctx.input(|i| i.key_pressed(egui::Key::ArrowUp));
-        let enter = ctx.input(|i| i.key_pressed(egui::Key::Enter));
-        let escape = ctx.input(|i| i.key_pressed(egui::Key::Escape));
-
+        // The save search prompt has the keyboard while it's up
+        let navigating = self.save_search_name.is_none();
+        let pressed = |key| navigating && ctx.input(|i| i.key_pressed(key));
+        let down = pressed(egui::Key::ArrowDown);
+        let up = pressed(egui::Key::ArrowUp);
+        let enter = pressed(egui::Key::Enter);
+        let escape = pressed(egui::Key::Escape);
+
+        // Up with the text cursor at the start of the search box steps back
+        // through recent searches, when the box is empty or already holds one;
+        // anywhere else it moves through the results
+        if self
+            .history_pos
+            .is_some_and(|pos| self.search_history.get(pos) != Some(&self.query))
+        {
+            self.history_pos = None;
+        }
+        let recall = up
+            && (self.query.is_empty() || self.history_pos.is_some())
+            && search_cursor_at_start(ctx);
+        if recall {
+            let older = self.history_pos.map_or(0, |pos| pos + 1);
+            if let Some(query) = self.search_history.get(older) {
+                self.query = query.clone();
+                self.history_pos = Some(older);
+                self.last_keystroke = Instant::now();
+                self.selected_index = None;
+            }
+        }
+        if down {
+            self.history_pos = None;
+        }
         if escape {
             self.query.clear();
             self.results.clear();
+            self.ranked_results.clear();
             self.selected_index = None;
+            self.clear_multi_selection();
         }
+        // Arrow keys move a single cursor, dropping any multi-selection
         if down && !self.results.is_empty() {
             let max = self.results.len().saturating_sub(1);
             self.selected_index = Some(self.selected_index.map_or(0, |i| (i + 1).min(max)));
+            self.clear_multi_selection();
             self.scroll_to_selected = true;
         }
-        if up && !self.results.is_empty() {
+        if up && !recall && !self.results.is_empty() {
             self.selected_index = Some(self.selected_index.map_or(0, |i| i.saturating_sub(1)));
+            self.clear_multi_selection();
             self.scroll_to_selected = true;
         }
+        // Enter opens the cursor row (the last one clicked), even with several
+        // selected. With no cursor, Enter in the search box opens the top
+        // result, but only once the results are those of the query as typed.
         if enter {
-            if let Some(idx) = self.selected_index {
-                if let Some(result) = self.results.get(idx) {
-                    let _ = open::that(&result.file_path);
-                }
+            let row = self.selected_index.or_else(|| {
+                let typed = self.results_query == self.query
+                    && ctx.memory(|m| m.has_focus(search_box_id()));
+                typed.then_some(0)
+            });
+            if let Some(result) = row.and_then(|idx| self.results.get(idx)) {
+                open_result(&self.config, &self.search_tx, &result.file_path, result.is_dir);
+            }
+        }
+        // Space previews the cursor row with Quick Look, as in Finder. While a
+        // text field (e.g. the search box) has the keyboard it types a space.
+        #[cfg(target_os = "macos")]
+        if pressed(egui::Key::Space) && !ctx.wants_keyboard_input() {
+            if let Some(result) = self.selected_index.and_then(|i| self.results.get(i)) {
+                quick_look(&result.file_path);
+            }
+        }
+        // Ctrl/Cmd+C copies the selected paths, Ctrl/Cmd+Shift+C just the names.
+        // Text being copied from the query (or any other field) goes first.
+        let copy_shift = ctx.input(|i| {
+            i.events
+                .iter()
+                .find_map(|e| matches!(e, egui::Event::Copy).then_some(i.modifiers.shift))
+        });
+        if let Some(names_only) = copy_shift.filter(|_| !self.copying_text(ctx)) {
+            let rows: Vec<usize> = if self.multi_selected.is_empty() {
+                self.selected_index.into_iter().collect()
+            } else {
+                self.multi_selected.iter().copied().collect()
+            };
+            let lines: Vec<String> = rows
+                .iter()
+                .filter_map(|&i| self.results.get(i))
+                .map(|r| {
+                    if names_only {
+                        r.file_name.clone()
+                    } else {
+                        r.file_path.to_string_lossy().to_string()
+                    }
+                })
+                .collect();
+            if !lines.is_empty() {
+                ctx.copy_text(lines.join("\n"));
             }
         }
 
@@ -265,7 +853,7 @@ impl eframe::App for DrozoSearchApp {
             .frame(
                 egui::Frame::NONE
                     .inner_margin(egui::Margin::symmetric(16, 10))
-                    .fill(egui::Color32::from_gray(26)),
+                    .fill(theme::gray(26)),
             )
             .show(ctx, |ui| {
                 // Search row
@@ -280,16 +868,26 @@ impl eframe::App for DrozoSearchApp {
                     egui::Frame::NONE
                         .inner_margin(egui::Margin::symmetric(8, 6))
                         .corner_radius(egui::CornerRadius::same(6))
-                        .fill(egui::Color32::from_gray(16))
-                        .stroke(egui::Stroke::new(1.0, egui::Color32::from_gray(50)))
+                        .fill(theme::gray(16))
+                        .stroke(egui::Stroke::new(1.0, theme::gray(50)))
                         .show(ui, |ui| {
-                            ui.set_width(ui.available_width());
+                            // Leave room for the save, duplicates and settings buttons
+                            ui.set_width(ui.available_width() - 104.0);
+                            if self.config.names_only {
+                                self.names_only_chip(ui);
+                            }
+                            if self.search_scope.is_some() {
+                                self.scope_chip(ui);
+                            }
+                            let hint = if self.config.names_only {
+                                "  Search file names..."
+                            } else {
+                                "  Search files, content, metadata..."
+                            };
                             let response = ui.add(
                                 egui::TextEdit::singleline(&mut self.query)
-                                    .hint_text(
-                                        egui::RichText::new("  Search files, content, metadata...")
-                                            .color(egui::Color32::from_gray(70)),
-                                    )
+                                    .id(search_box_id())
+                                    .hint_text(egui::RichText::new(hint).color(theme::gray(70)))
                                     .desired_width(ui.available_width())
                                     .frame(false)
                                     .font(egui::FontId::proportional(16.0)),
@@ -299,53 +897,126 @@ impl eframe::App for DrozoSearchApp {
                                 self.last_keystroke = Instant::now();
                                 self.selected_index = None;
                             }
+                            let recalling = self.query.is_empty() || self.history_pos.is_some();
+                            if (response.has_focus() || self.history_menu_hovered)
+                                && recalling
+                                && !self.search_history.is_empty()
+                            {
+                                self.recent_searches_menu(ctx, response.rect);
+                            } else {
+                                self.history_menu_hovered = false;
+                            }
                             if self.first_frame {
                                 response.request_focus();
                                 self.first_frame = false;
                             }
                         });
+
+                    let saved = self.config.saved_searches.iter().any(|s| s.query == self.query);
+                    let (star, star_color) = if saved {
+                        ("★", theme::rgb(255, 200, 60))
+                    } else {
+                        ("☆", theme::gray(160))
+                    };
+                    let save = ui.add_enabled(
+                        !self.query.trim().is_empty(),
+                        egui::Button::new(egui::RichText::new(star).size(16.0).color(star_color))
+                            .frame(false)
+                            .min_size(egui::vec2(28.0, 28.0)),
+                    );
+                    if save.on_hover_text("Save this search").clicked() {
+                        let name = self
+                            .config
+                            .saved_searches
+                            .iter()
+                            .find(|s| s.query == self.query)
+                            .map_or_else(|| self.query.clone(), |s| s.name.clone());
+                        self.save_search_name = Some(name);
+                    }
+
+                    let dupes_color = if self.find_duplicates {
+                        theme::rgb(90, 160, 255)
+                    } else {
+                        theme::gray(160)
+                    };
+                    let dupes = ui.add(
+                        egui::Button::new(egui::RichText::new("⧉").size(16.0).color(dupes_color))
+                            .frame(false)
+                            .min_size(egui::vec2(28.0, 28.0)),
+                    );
+                    if dupes
+                        .on_hover_text("Find duplicates: group files with identical contents")
+                        .clicked()
+                    {
+                        self.set_find_duplicates(!self.find_duplicates);
+                    }
+
+                    let gear = ui.add(
+                        egui::Button::new(egui::RichText::new("⚙").size(16.0))
+                            .frame(false)
+                            .min_size(egui::vec2(28.0, 28.0)),
+                    );
+                    if gear.on_hover_text("Settings").clicked() {
+                        self.settings_open = !self.settings_open;
+                    }
                 });
 
                 ui.add_space(6.0);
 
                 // Status row
                 ui.horizontal(|ui| {
+                    // Ease towards the reported progress instead of jumping
+                    let progress_frac = ctx.animate_value_with_time(
+                        egui::Id::new("index_progress"),
+                        self.progress_target,
+                        0.5,
+                    );
+
                     // Status dot + text
                     let (dot_color, status_str, is_active) = match &self.index_status {
                         IndexStatus::Counting => (
-                            egui::Color32::from_rgb(150, 130, 255),
+                            theme::rgb(150, 130, 255),
                             format!("Scanning... found {} files", format_count(self.estimated_total)),
                             true,
                         ),
                         IndexStatus::Starting => (
-                            egui::Color32::from_rgb(255, 220, 50),
+                            theme::rgb(255, 220, 50),
                             format!("Preparing to index {} files...", format_count(self.estimated_total)),
                             true,
                         ),
                         IndexStatus::Indexing => {
-                            let pct = if self.estimated_total > 0 {
-                                (self.files_indexed as f64 / self.estimated_total as f64 * 100.0).min(100.0)
-                            } else {
-                                0.0
+                            let pct = progress_frac * 100.0;
+                            // A dash until the indexer has a steady enough rate
+                            let (speed, eta) = match self.eta_seconds {
+                                Some(eta) => (
+                                    format_count(self.files_per_sec.round() as u64),
+                                    format!("~{}", format_eta(eta)),
+                                ),
+                                None => ("—".into(), "—".into()),
                             };
                             (
-                                egui::Color32::from_rgb(255, 150, 30),
+                                theme::rgb(255, 150, 30),
                                 format!(
-                                    "Indexing  {} / {}  ({:.0}%)",
+                                    "Indexing  {} / {}  ({:.0}%)  ·  {} files/s  ·  {} left",
                                     format_count(self.files_indexed),
                                     format_count(self.estimated_total),
                                     pct,
+                                    speed,
+                                    eta,
                                 ),
                                 true,
                             )
                         }
                         IndexStatus::Committing => (
-                            egui::Color32::from_rgb(255, 220, 50),
+                            theme::rgb(255, 220, 50),
                             "Saving index...".into(),
                             true,
                         ),
-                        IndexStatus::Ready(ref stats) => {
+                        IndexStatus::Ready(ref stats) | IndexStatus::Watching(ref stats) => {
                             let mut text = format!("{} files indexed", format_count(self.files_indexed));
+                            if matches!(self.index_status, IndexStatus::Watching(_)) {
+                                text.push_str(" · watching for changes");
+                            }
                             if let Some(s) = stats {
                                 let mut parts = Vec::new();
                                 if s.added > 0 {
@@ -357,27 +1028,37 @@ impl eframe::App for DrozoSearchApp {
                                 if s.deleted > 0 {
                                     parts.push(format!("-{} removed", s.deleted));
                                 }
+                                if s.failed > 0 {
+                                    parts.push(format!("{} unreadable", s.failed));
+                                }
                                 if !parts.is_empty() {
                                     text.push_str(&format!("  ({})", parts.join(", ")));
                                 }
                             }
                             (
-                                egui::Color32::from_rgb(60, 200, 80),
+                                theme::rgb(60, 200, 80),
                                 text,
                                 false,
                             )
                         }
                         IndexStatus::Error(e) => (
-                            egui::Color32::from_rgb(255, 80, 80),
+                            theme::rgb(255, 80, 80),
                             format!("Error: {}", e),
                             false,
                         ),
+                        IndexStatus::NoRoots => (
+                            theme::gray(110),
+                            "No folder to index yet".into(),
+                            false,
+                        ),
                     };
 
                     // Animated dot
                     let (rect, _) =
                         ui.allocate_exact_size(egui::vec2(10.0, 10.0), egui::Sense::hover());
-                    let pulse = if is_active {
+                    // Only animate while the window can actually be seen
+                    let animate = is_active && self.window_visible;
+                    let pulse = if animate {
                         let t = ui.input(|i| i.time) as f32;
                         0.5 + 0.5 * (t * 3.0).sin()
                     } else {
@@ -392,15 +1073,21 @@ impl eframe::App for DrozoSearchApp {
                     );
                     ui.painter().circle_filled(rect.center(), 4.0, pulsing_color);
 
-                    if is_active {
+                    if animate {
                         ctx.request_repaint();
                     }
 
                     ui.label(
                         egui::RichText::new(status_str)
                             .size(11.0)
-                            .color(egui::Color32::from_gray(120)),
+                            .color(theme::gray(120)),
                     );
+                    if last_pass_stats(&self.index_status).is_some_and(|s| s.failed > 0) {
+                        let view = ui.link(egui::RichText::new("View skipped files").size(11.0));
+                        if view.clicked() {
+                            self.failures_open = true;
+                        }
+                    }
 
                     // Progress bar during indexing (real percentage)
                     if matches!(self.index_status, IndexStatus::Indexing) && self.estimated_total > 0 {
@@ -413,10 +1100,9 @@ impl eframe::App for DrozoSearchApp {
                         ui.painter().rect_filled(
                             bar_rect,
                             egui::CornerRadius::same(3),
-                            egui::Color32::from_gray(40),
+                            theme::gray(40),
                         );
                         // Fill based on real progress
-                        let progress_frac = (self.files_indexed as f32 / self.estimated_total as f32).min(1.0);
                         let fill_width = bar_rect.width() * progress_frac;
                         if fill_width > 0.0 {
                             let fill_rect = egui::Rect::from_min_size(
@@ -426,7 +1112,7 @@ impl eframe::App for DrozoSearchApp {
                             ui.painter().rect_filled(
                                 fill_rect,
                                 egui::CornerRadius::same(3),
-                                egui::Color32::from_rgb(90, 160, 255),
+                                theme::rgb(90, 160, 255),
                             );
                         }
                     }
@@ -441,7 +1127,7 @@ impl eframe::App for DrozoSearchApp {
                         ui.painter().rect_filled(
                             bar_rect,
                             egui::CornerRadius::same(2),
-                            egui::Color32::from_gray(35),
+                            theme::gray(35),
                         );
                         let t = ui.input(|i| i.time) as f32;
                         let pos = ((t * 1.5).sin() * 0.5 + 0.5) * 0.7;
@@ -452,21 +1138,74 @@ impl eframe::App for DrozoSearchApp {
                         ui.painter().rect_filled(
                             fill_rect.intersect(bar_rect),
                             egui::CornerRadius::same(2),
-                            egui::Color32::from_rgb(150, 130, 255),
+                            theme::rgb(150, 130, 255),
                         );
                     }
 
-                    // Result count on the right
+                    // Sort order and result count on the right
                     ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
-                        if !self.results.is_empty() {
+                        self.sort_selector(ui);
+                        if !self.ranked_results.is_empty() {
+                            if !self.results.is_empty() {
+                                ui.menu_button(egui::RichText::new("⋯").size(12.0), |ui| {
+                                    let exporting = self.export_rx.is_some();
+                                    let export = ui.add_enabled(!exporting, egui::Button::new("Export results…"));
+                                    if export.on_hover_text("Save the results as shown to a CSV file").clicked() {
+                                        self.export_rx = Some(export::export_csv(ctx, self.results.clone()));
+                                        ui.close_menu();
+                                    }
+                                    ui.separator();
+                                    self.copy_matching_menu(ui, ctx);
+                                });
+                            }
+                            let count = if self.find_duplicates {
+                                let groups = self
+                                    .results
+                                    .iter()
+                                    .map(|r| (&r.content_hash, r.file_size))
+                                    .collect::<HashSet<_>>()
+                                    .len();
+                                format!("{} duplicates in {} groups", self.results.len(), groups)
+                            } else if self.total_hits > self.results.len() {
+                                format!(
+                                    "{} of ~{} results",
+                                    self.results.len(),
+                                    format_count(self.total_hits as u64)
+                                )
+                            } else {
+                                format!("{} results", self.results.len())
+                            };
+                            ui.label(egui::RichText::new(count).size(11.0).color(theme::gray(100)));
+                            self.match_type_chips(ui);
+                        } else if !self.query.trim().is_empty()
+                            && !self.is_searchable(&self.query)
+                        {
                             ui.label(
-                                egui::RichText::new(format!("{} results", self.results.len()))
+                                egui::RichText::new("keep typing…")
                                     .size(11.0)
-                                    .color(egui::Color32::from_gray(100)),
+                                    .italics()
+                                    .color(theme::gray(80)),
                             );
                         }
                     });
                 });
+
+                // One-off notice (e.g. index repaired), click to dismiss
+                if let Some(notice) = &self.notice {
+                    ui.add_space(4.0);
+                    let resp = ui
+                        .add(
+                            egui::Label::new(
+                                egui::RichText::new(format!("{}  (click to dismiss)", notice))
+                                    .size(11.0)
+                                    .color(theme::rgb(255, 190, 60)),
+                            )
+                            .sense(egui::Sense::click()),
+                        );
+                    if resp.clicked() {
+                        self.notice = None;
+                    }
+                }
             });
 
         // ═══════════════════════════════════════
@@ -476,7 +1215,7 @@ impl eframe::App for DrozoSearchApp {
             .frame(
                 egui::Frame::NONE
                     .inner_margin(egui::Margin::symmetric(16, 4))
-                    .fill(egui::Color32::from_gray(22)),
+                    .fill(theme::gray(22)),
             )
             .show(ctx, |ui| {
                 ui.horizontal(|ui| {
@@ -484,35 +1223,46 @@ impl eframe::App for DrozoSearchApp {
                         ui.label(
                             egui::RichText::new("|")
                                 .size(10.0)
-                                .color(egui::Color32::from_gray(40)),
+                                .color(theme::gray(40)),
                         );
                     };
                     let hint = |ui: &mut egui::Ui, text: &str| {
                         ui.label(
                             egui::RichText::new(text)
                                 .size(10.0)
-                                .color(egui::Color32::from_gray(70)),
+                                .color(theme::gray(70)),
                         );
                     };
-                    hint(ui, "Click open");
+                    if self.config.double_click_to_open {
+                        hint(ui, "Click select");
+                        sep(ui);
+                        hint(ui, "Double-click open");
+                        sep(ui);
+                        hint(ui, "Alt+Double-click open with...");
+                    } else {
+                        hint(ui, "Click open");
+                        sep(ui);
+                        hint(ui, "Alt+Click open with...");
+                    }
                     sep(ui);
-                    hint(ui, "Shift+Click open with...");
+                    hint(ui, "Ctrl/Shift+Click select several");
                     sep(ui);
                     hint(ui, "Up/Down navigate");
                     sep(ui);
                     hint(ui, "Enter open");
                     sep(ui);
+                    hint(ui, "Ctrl/Cmd+C copy path");
+                    sep(ui);
                     hint(ui, "ESC clear");
 
                     ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
                         if let Some(idx) = self.selected_index {
                             if let Some(result) = self.results.get(idx) {
-                                let path_display = result.file_path.to_string_lossy();
-                                let display = truncate_path(&path_display, 80);
-                                ui.label(
-                                    egui::RichText::new(display)
-                                        .size(10.0)
-                                        .color(egui::Color32::from_gray(90)),
+                                breadcrumbs(
+                                    ui,
+                                    &result.file_path,
+                                    result.is_dir,
+                                    self.config.show_full_paths,
                                 );
                             }
                         }
@@ -520,6 +1270,10 @@ impl eframe::App for DrozoSearchApp {
                 });
             });
 
+        if !self.config.saved_searches.is_empty() {
+            self.saved_searches_panel(ctx);
+        }
+
         // ═══════════════════════════════════════
         // ── CENTRAL PANEL: Results ──
         // ═══════════════════════════════════════
@@ -527,11 +1281,11 @@ impl eframe::App for DrozoSearchApp {
             .frame(
                 egui::Frame::NONE
                     .inner_margin(egui::Margin::same(0))
-                    .fill(egui::Color32::from_gray(18)),
+                    .fill(theme::gray(18)),
             )
             .show(ctx, |ui| {
                 // Empty state
-                if self.query.is_empty() {
+                if self.query.is_empty() && !self.find_duplicates {
                     ui.add_space(ui.available_height() / 3.0);
                     ui.vertical_centered(|ui| {
                         // Logo + title
@@ -544,34 +1298,38 @@ impl eframe::App for DrozoSearchApp {
                             egui::RichText::new("drozoSearch")
                                 .size(36.0)
                                 .strong()
-                                .color(egui::Color32::from_gray(50)),
+                                .color(theme::gray(50)),
                         );
                         ui.add_space(8.0);
                         ui.label(
                             egui::RichText::new("Search files, content & metadata instantly")
                                 .size(14.0)
-                                .color(egui::Color32::from_gray(65)),
+                                .color(theme::gray(65)),
                         );
                         ui.add_space(24.0);
+                        if self.pending_indexer.is_some() {
+                            self.first_root_prompt(ui, ctx);
+                            return;
+                        }
                         ui.horizontal(|ui| {
                             ui.add_space(ui.available_width() / 2.0 - 120.0);
                             for (key, desc) in [("name:", "file names"), ("ext:", "extensions"), ("size>1mb", "by size")] {
                                 egui::Frame::NONE
                                     .inner_margin(egui::Margin::symmetric(8, 3))
                                     .corner_radius(egui::CornerRadius::same(4))
-                                    .fill(egui::Color32::from_gray(28))
+                                    .fill(theme::gray(28))
                                     .show(ui, |ui| {
                                         ui.horizontal(|ui| {
                                             ui.label(
                                                 egui::RichText::new(key)
                                                     .size(11.0)
                                                     .strong()
-                                                    .color(egui::Color32::from_rgb(90, 160, 255)),
+                                                    .color(theme::rgb(90, 160, 255)),
                                             );
                                             ui.label(
                                                 egui::RichText::new(desc)
                                                     .size(11.0)
-                                                    .color(egui::Color32::from_gray(80)),
+                                                    .color(theme::gray(80)),
                                             );
                                         });
                                     });
@@ -581,258 +1339,1896 @@ impl eframe::App for DrozoSearchApp {
                     return;
                 }
 
+                // Too short to search yet — the status row says "keep typing"
+                if !self.is_searchable(&self.query) {
+                    return;
+                }
+
+                let building = index_is_building(&self.index_status);
+
                 if self.results.is_empty() {
                     ui.add_space(ui.available_height() / 3.0);
                     ui.vertical_centered(|ui| {
+                        if let Some(error) = &self.query_error {
+                            ui.label(
+                                egui::RichText::new(error)
+                                    .size(16.0)
+                                    .color(theme::rgb(255, 190, 60)),
+                            );
+                            return;
+                        }
                         ui.label(
                             egui::RichText::new("No results")
                                 .size(20.0)
-                                .color(egui::Color32::from_gray(60)),
+                                .color(theme::gray(60)),
                         );
                         ui.add_space(4.0);
+                        let hint = if !self.ranked_results.is_empty() {
+                            "Every match is of a type hidden by the NAME / CONTENT / META toggles"
+                        } else if building {
+                            "Still indexing: matches may appear once the scan finishes"
+                        } else {
+                            "Try a different search term"
+                        };
                         ui.label(
-                            egui::RichText::new("Try a different search term")
+                            egui::RichText::new(hint)
                                 .size(12.0)
-                                .color(egui::Color32::from_gray(50)),
+                                .color(theme::gray(50)),
                         );
                     });
                     return;
                 }
 
+                // ── Freshness banner ──
+                if building {
+                    egui::Frame::NONE
+                        .inner_margin(egui::Margin::symmetric(16, 4))
+                        .fill(theme::rgb(40, 34, 20))
+                        .show(ui, |ui| {
+                            ui.set_width(ui.available_width());
+                            ui.label(
+                                egui::RichText::new("Results may be incomplete — still indexing")
+                                    .size(11.0)
+                                    .color(theme::rgb(230, 180, 80)),
+                            );
+                        });
+                }
+
                 // ── Column headers ──
                 egui::Frame::NONE
                     .inner_margin(egui::Margin::symmetric(16, 5))
-                    .fill(egui::Color32::from_gray(24))
+                    .fill(theme::gray(24))
                     .show(ui, |ui| {
                         let widths = compute_column_widths(ui.available_width());
+                        let sort = |column| {
+                            (self.sort_column == column).then_some(self.sort_ascending)
+                        };
+                        let mut clicked = None;
                         ui.horizontal(|ui| {
-                            header_label(ui, "Name", widths.name);
-                            header_label(ui, "Location", widths.path);
-                            header_label(ui, "Type", widths.match_type);
-                            header_label_right(ui, "Size", widths.size);
-                            header_label_right(ui, "Modified", widths.modified);
+                            let columns = [
+                                (SortColumn::Name, "Name", widths.name, false),
+                                (SortColumn::Location, "Location", widths.path, false),
+                                (SortColumn::Type, "Type", widths.match_type, false),
+                                (SortColumn::Size, "Size", widths.size, true),
+                                (SortColumn::Modified, "Modified", widths.modified, true),
+                            ];
+                            for (column, text, width, right) in columns {
+                                let header = if right {
+                                    header_label_right(ui, text, width, sort(column))
+                                } else {
+                                    header_label(ui, text, width, sort(column))
+                                };
+                                if header.clicked() {
+                                    clicked = Some(column);
+                                }
+                            }
                         });
+                        if let Some(column) = clicked {
+                            self.toggle_sort(column);
+                        }
                     });
 
                 // Thin separator line
                 let sep_rect = ui.allocate_space(egui::vec2(ui.available_width(), 1.0)).1;
                 ui.painter()
-                    .rect_filled(sep_rect, egui::CornerRadius::ZERO, egui::Color32::from_gray(35));
+                    .rect_filled(sep_rect, egui::CornerRadius::ZERO, theme::gray(35));
 
                 // ── Results scroll area ──
+                let mut open_selected_requested = false;
+                let mut scope_requested = None;
+                let mut trash_requested: Option<Vec<PathBuf>> = None;
+                let mut delete_requested: Option<Vec<PathBuf>> = None;
+                let mut load_more_requested = false;
+                if self.row_heights.len() != self.results.len() {
+                    self.row_heights = self.results.iter().map(estimated_row_height).collect();
+                }
                 egui::ScrollArea::vertical()
                     .auto_shrink([false; 2])
-                    .show(ui, |ui| {
+                    .show_viewport(ui, |ui, viewport| {
                         let widths = compute_column_widths(ui.available_width() - 32.0);
 
-                        for (i, result) in self.results.iter().enumerate() {
-                            let is_selected = self.selected_index == Some(i);
+                        // Only rows in view are laid out. They're placed by the heights
+                        // measured when last drawn, estimated for rows not drawn yet
+                        let rows_height: f32 = self.row_heights.iter().sum();
+                        let total = rows_height + if self.has_more { LOAD_MORE_HEIGHT } else { 0.0 };
+                        ui.set_height(total);
+                        let content = ui.max_rect();
+                        let mut first = 0;
+                        let mut first_top = 0.0;
+                        while first < self.results.len() && first_top + self.row_heights[first] < viewport.min.y {
+                            first_top += self.row_heights[first];
+                            first += 1;
+                        }
 
-                            let bg = if is_selected {
-                                egui::Color32::from_rgb(25, 55, 100)
-                            } else if i % 2 == 0 {
-                                egui::Color32::from_gray(19)
-                            } else {
-                                egui::Color32::from_gray(16)
-                            };
+                        // The selected row may not be laid out, so scroll to where it would be
+                        if let Some(sel) = self.selected_index.filter(|&i| i < self.results.len()) {
+                            if self.scroll_to_selected {
+                                let top: f32 = self.row_heights[..sel].iter().sum();
+                                let rect = egui::Rect::from_min_size(
+                                    egui::pos2(content.left(), content.top() + top),
+                                    egui::vec2(content.width(), self.row_heights[sel]),
+                                );
+                                ui.scroll_to_rect(rect, Some(egui::Align::Center));
+                            }
+                        }
 
-                            let hover_bg = if is_selected {
-                                egui::Color32::from_rgb(30, 65, 115)
-                            } else {
-                                egui::Color32::from_gray(28)
-                            };
+                        let rows_rect = egui::Rect::from_x_y_ranges(
+                            content.x_range(),
+                            content.top() + first_top..=content.top() + total,
+                        );
+                        ui.allocate_new_ui(egui::UiBuilder::new().max_rect(rows_rect), |ui| {
+                            // Keep widget IDs consistent as rows scroll in and out
+                            ui.skip_ahead_auto_ids(first);
+                            let mut y = first_top;
+                            let mut drawn_to = first;
+                            for i in first..self.results.len() {
+                                if y > viewport.max.y {
+                                    break;
+                                }
+                                let result = &self.results[i];
+                                let row_top = ui.cursor().top();
+
+                                // A gap before each group of duplicates
+                                let new_group = i > 0 && {
+                                    let prev = &self.results[i - 1];
+                                    (&prev.content_hash, prev.file_size) != (&result.content_hash, result.file_size)
+                                };
+                                if self.find_duplicates && new_group {
+                                    ui.add_space(8.0);
+                                }
 
-                            let row_frame = egui::Frame::NONE
-                                .inner_margin(egui::Margin::symmetric(16, 4))
-                                .fill(bg);
+                                let is_selected =
+                                    self.selected_index == Some(i) || self.multi_selected.contains(&i);
 
-                            let row_resp = row_frame
-                                .show(ui, |ui| {
-                                    ui.horizontal(|ui| {
-                                        // ── Name column ──
-                                        ui.allocate_ui(egui::vec2(widths.name, 20.0), |ui| {
-                                            ui.horizontal(|ui| {
-                                                let (icon, icon_color) = file_icon(result);
-                                                ui.label(
-                                                    egui::RichText::new(icon)
-                                                        .size(13.0)
-                                                        .strong()
-                                                        .color(icon_color),
-                                                );
-                                                ui.label(
-                                                    egui::RichText::new(&result.file_name)
-                                                        .size(13.0)
-                                                        .color(if is_selected {
-                                                            egui::Color32::WHITE
-                                                        } else {
-                                                            egui::Color32::from_gray(220)
-                                                        }),
-                                                );
-                                            });
-                                        });
+                                let bg = if is_selected {
+                                    theme::rgb(25, 55, 100)
+                                } else if i % 2 == 0 {
+                                    theme::gray(19)
+                                } else {
+                                    theme::gray(16)
+                                };
 
-                                        // ── Path column ──
-                                        ui.allocate_ui(egui::vec2(widths.path, 20.0), |ui| {
-                                            let path_str = result
-                                                .file_path
-                                                .parent()
-                                                .map(|p| {
-                                                    let s = p.to_string_lossy().to_string();
-                                                    // Shorten home dir
-                                                    if let Some(home) = dirs::home_dir() {
-                                                        let home_str = home.to_string_lossy().to_string();
-                                                        if s.starts_with(&home_str) {
-                                                            return format!("~{}", &s[home_str.len()..]);
-                                                        }
-                                                    }
-                                                    s
-                                                })
-                                                .unwrap_or_default();
-                                            let display_path = truncate_path(&path_str, 55);
-                                            ui.label(
-                                                egui::RichText::new(display_path)
-                                                    .size(11.0)
-                                                    .color(egui::Color32::from_gray(95)),
-                                            );
-                                        });
+                                let hover_bg = if is_selected {
+                                    theme::rgb(30, 65, 115)
+                                } else {
+                                    theme::gray(28)
+                                };
 
-                                        // ── Match type badge ──
-                                        ui.allocate_ui(egui::vec2(widths.match_type, 20.0), |ui| {
-                                            let (label, badge_bg, badge_fg) = match result.match_type {
-                                                MatchType::FileName => (
-                                                    "NAME",
-                                                    egui::Color32::from_rgb(25, 60, 30),
-                                                    egui::Color32::from_rgb(90, 210, 90),
-                                                ),
-                                                MatchType::Content => (
-                                                    "CONTENT",
-                                                    egui::Color32::from_rgb(20, 40, 70),
-                                                    egui::Color32::from_rgb(90, 155, 255),
-                                                ),
-                                                MatchType::Metadata => (
-                                                    "META",
-                                                    egui::Color32::from_rgb(60, 45, 15),
-                                                    egui::Color32::from_rgb(255, 190, 60),
-                                                ),
-                                            };
-                                            egui::Frame::NONE
-                                                .inner_margin(egui::Margin::symmetric(6, 1))
-                                                .corner_radius(egui::CornerRadius::same(3))
-                                                .fill(badge_bg)
-                                                .show(ui, |ui| {
+                                let row_frame = egui::Frame::NONE
+                                    .inner_margin(egui::Margin::symmetric(16, 4))
+                                    .fill(bg);
+
+                                let row_resp = row_frame
+                                    .show(ui, |ui| {
+                                        ui.horizontal(|ui| {
+                                            // ── Name column ──
+                                            ui.allocate_ui(egui::vec2(widths.name, 20.0), |ui| {
+                                                ui.horizontal(|ui| {
+                                                    // Images get a thumbnail, decoded lazily for rows on screen
+                                                    let thumbnail = (!result.is_dir
+                                                        && thumbnail::is_thumbnailable(&result.file_path)
+                                                        && ui.is_rect_visible(ui.max_rect()))
+                                                    .then(|| self.thumbnails.thumbnail(ctx, &result.file_path))
+                                                    .flatten();
+                                                    let icon_resp = if let Some(tex) = &thumbnail {
+                                                        ui.image(egui::load::SizedTexture::new(
+                                                            tex.id(),
+                                                            thumbnail_size(tex.size_vec2()),
+                                                        ))
+                                                    } else {
+                                                        let (icon, icon_color) = file_icon(result);
+                                                        ui.label(
+                                                            egui::RichText::new(icon)
+                                                                .size(13.0)
+                                                                .strong()
+                                                                .color(icon_color),
+                                                        )
+                                                    };
+                                                    if let Some(mime) = &result.mime_type {
+                                                        icon_resp.on_hover_text(mime);
+                                                    }
                                                     ui.label(
-                                                        egui::RichText::new(label)
-                                                            .size(9.0)
-                                                            .strong()
-                                                            .color(badge_fg),
+                                                        egui::RichText::new(&result.file_name)
+                                                            .size(13.0)
+                                                            .color(if is_selected {
+                                                                theme::gray(255)
+                                                            } else {
+                                                                theme::gray(220)
+                                                            }),
                                                     );
+
+                                                    // Git badge, looked up lazily for rows on screen
+                                                    let wants_badge =
+                                                        !result.is_dir && ui.is_rect_visible(ui.max_rect());
+                                                    if let Some(git) = self.git_status.as_mut() {
+                                                        if let Some(status) = wants_badge
+                                                            .then(|| git.status(&result.file_path, result.modified))
+                                                            .flatten()
+                                                        {
+                                                            ui.label(
+                                                                egui::RichText::new(status.badge())
+                                                                    .size(10.0)
+                                                                    .strong()
+                                                                    .color(git_badge_color(status)),
+                                                            );
+                                                        }
+                                                    }
                                                 });
-                                        });
+                                            });
 
-                                        // ── Size column ──
-                                        ui.allocate_ui(egui::vec2(widths.size, 20.0), |ui| {
-                                            ui.with_layout(
-                                                egui::Layout::right_to_left(egui::Align::Center),
-                                                |ui| {
+                                            // ── Path column ──
+                                            ui.allocate_ui(egui::vec2(widths.path, 20.0), |ui| {
+                                                let full = self.config.show_full_paths;
+                                                let path_str = result
+                                                    .file_path
+                                                    .parent()
+                                                    .map(|p| {
+                                                        let s = p.to_string_lossy().to_string();
+                                                        if full {
+                                                            return s;
+                                                        }
+                                                        // Shorten home dir
+                                                        if let Some(home) = dirs::home_dir() {
+                                                            let home_str = home.to_string_lossy().to_string();
+                                                            if s.starts_with(&home_str) {
+                                                                return format!("~{}", &s[home_str.len()..]);
+                                                            }
+                                                        }
+                                                        s
+                                                    })
+                                                    .unwrap_or_default();
+                                                let path_label = if full {
+                                                    // Wrap rather than truncate so every character stays visible
+                                                    ui.add(
+                                                        egui::Label::new(
+                                                            egui::RichText::new(path_str)
+                                                                .size(11.0)
+                                                                .color(theme::gray(95)),
+                                                        )
+                                                        .wrap(),
+                                                    )
+                                                } else {
+                                                    let display_path = truncate_path(&path_str, 55);
                                                     ui.label(
-                                                        egui::RichText::new(format_size(
-                                                            result.file_size,
-                                                        ))
-                                                        .size(11.0)
-                                                        .color(egui::Color32::from_gray(110)),
-                                                    );
-                                                },
-                                            );
-                                        });
+                                                        egui::RichText::new(display_path)
+                                                            .size(11.0)
+                                                            .color(theme::gray(95)),
+                                                    )
+                                                };
+                                                if let Some(owner) = owner_label(result) {
+                                                    path_label.on_hover_text(owner);
+                                                }
+                                            });
 
-                                        // ── Modified column ──
-                                        ui.allocate_ui(egui::vec2(widths.modified, 20.0), |ui| {
-                                            ui.with_layout(
-                                                egui::Layout::right_to_left(egui::Align::Center),
-                                                |ui| {
-                                                    ui.label(
-                                                        egui::RichText::new(format_time_ago(
-                                                            result.modified,
-                                                        ))
-                                                        .size(11.0)
-                                                        .color(egui::Color32::from_gray(110)),
-                                                    );
-                                                },
-                                            );
-                                        });
-                                    });
-                                })
-                                .response;
-
-                            // Hover highlight
-                            let interact = row_resp.interact(egui::Sense::click());
-                            if interact.hovered() && !is_selected {
-                                let painter = ui.painter();
-                                painter.rect_filled(
-                                    row_resp.rect,
-                                    egui::CornerRadius::ZERO,
-                                    hover_bg,
-                                );
-                            }
+                                            // ── Match type badge ──
+                                            ui.allocate_ui(egui::vec2(widths.match_type, 20.0), |ui| {
+                                                let (label, badge_bg, badge_fg) = match result.match_type {
+                                                    MatchType::FileName => (
+                                                        "NAME",
+                                                        theme::rgb(25, 60, 30),
+                                                        theme::rgb(90, 210, 90),
+                                                    ),
+                                                    MatchType::Content => (
+                                                        "CONTENT",
+                                                        theme::rgb(20, 40, 70),
+                                                        theme::rgb(90, 155, 255),
+                                                    ),
+                                                    MatchType::Metadata => (
+                                                        "META",
+                                                        theme::rgb(60, 45, 15),
+                                                        theme::rgb(255, 190, 60),
+                                                    ),
+                                                };
+                                                egui::Frame::NONE
+                                                    .inner_margin(egui::Margin::symmetric(6, 1))
+                                                    .corner_radius(egui::CornerRadius::same(3))
+                                                    .fill(badge_bg)
+                                                    .show(ui, |ui| {
+                                                        ui.label(
+                                                            egui::RichText::new(label)
+                                                                .size(9.0)
+                                                                .strong()
+                                                                .color(badge_fg),
+                                                        );
+                                                    });
+                                            });
 
-                            // Click: open file; Shift+click: "Open With" chooser
-                            if interact.clicked() {
-                                let shift_held = ui.input(|i| i.modifiers.shift);
-                                if shift_held {
-                                    open_with_chooser(&result.file_path);
-                                } else {
-                                    let _ = open::that(&result.file_path);
+                                            // ── Size column ──
+                                            ui.allocate_ui(egui::vec2(widths.size, 20.0), |ui| {
+                                                ui.with_layout(
+                                                    egui::Layout::right_to_left(egui::Align::Center),
+                                                    |ui| {
+                                                        // Amber when only the start of the content is searchable
+                                                        let size_color = if result.content_truncated {
+                                                            theme::rgb(255, 190, 60)
+                                                        } else {
+                                                            theme::gray(110)
+                                                        };
+                                                        let size = ui.label(
+                                                            egui::RichText::new(format_size(
+                                                                result.file_size,
+                                                            ))
+                                                            .size(11.0)
+                                                            .color(size_color),
+                                                        );
+                                                        if let Some(media) = &result.media_summary {
+                                                            size.on_hover_text(media);
+                                                        } else if result.content_truncated {
+                                                            size.on_hover_text(format!(
+                                                                "Partially indexed: only the first {} of the content is searchable",
+                                                                format_size(self.config.max_file_size)
+                                                            ));
+                                                        }
+                                                    },
+                                                );
+                                            });
+
+                                            // ── Modified column ──
+                                            ui.allocate_ui(egui::vec2(widths.modified, 20.0), |ui| {
+                                                ui.with_layout(
+                                                    egui::Layout::right_to_left(egui::Align::Center),
+                                                    |ui| {
+                                                        ui.label(
+                                                            egui::RichText::new(format_time_ago(
+                                                                result.modified,
+                                                            ))
+                                                            .size(11.0)
+                                                            .color(theme::gray(110)),
+                                                        );
+                                                    },
+                                                );
+                                            });
+                                        });
+                                        // Media details (dimensions, duration, codec)
+                                        if let Some(media) = &result.media_summary {
+                                            ui.horizontal(|ui| {
+                                                ui.add_space(24.0);
+                                                ui.label(
+                                                    egui::RichText::new(media)
+                                                        .size(10.0)
+                                                        .color(theme::gray(90)),
+                                                );
+                                            });
+                                        }
+                                        // Content excerpt; selectable so a line can be copied
+                                        // without opening the file
+                                        if let Some(snippet) = &result.content_snippet {
+                                            ui.horizontal(|ui| {
+                                                ui.add_space(24.0);
+                                                ui.add(
+                                                    egui::Label::new(snippet_layout(
+                                                        snippet,
+                                                        &result.snippet_highlights,
+                                                    ))
+                                                    .selectable(true)
+                                                    .truncate(),
+                                                );
+                                            });
+                                        }
+                                    })
+                                    .response;
+
+                                // Hover highlight
+                                let interact = row_resp.interact(egui::Sense::click());
+                                if interact.hovered() && !is_selected {
+                                    let painter = ui.painter();
+                                    painter.rect_filled(
+                                        row_resp.rect,
+                                        egui::CornerRadius::ZERO,
+                                        hover_bg,
+                                    );
                                 }
-                                self.selected_index = Some(i);
-                            }
 
-                            // Right-click context menu
-                            interact.context_menu(|ui| {
-                                self.context_menu_index = Some(i);
-                                if ui.button("Open file").clicked() {
-                                    let _ = open::that(&result.file_path);
-                                    ui.close_menu();
+                                let (toggle_held, range_held, chooser_held) = ui.input(|i| {
+                                    (i.modifiers.command, i.modifiers.shift, i.modifiers.alt)
+                                });
+                                let selecting = toggle_held || range_held;
+                                if range_held && interact.clicked() {
+                                    // Shift+click: select every row from the anchor to this one
+                                    let anchor = self.selection_anchor.or(self.selected_index).unwrap_or(i);
+                                    self.multi_selected = (anchor.min(i)..=anchor.max(i)).collect();
+                                    self.selection_anchor = Some(anchor);
+                                    self.selected_index = Some(i);
+                                } else if toggle_held && interact.clicked() {
+                                    // Ctrl/Cmd+click: add the row to (or drop it from) the selection
+                                    if self.multi_selected.is_empty() {
+                                        self.multi_selected.extend(self.selected_index);
+                                    }
+                                    if !self.multi_selected.remove(&i) {
+                                        self.multi_selected.insert(i);
+                                    }
+                                    self.selection_anchor = Some(i);
+                                    self.selected_index = Some(i);
                                 }
-                                if ui.button("Open containing folder").clicked() {
-                                    if let Some(parent) = result.file_path.parent() {
-                                        let _ = open::that(parent);
+
+                                // Click (or double-click, if configured): open file;
+                                // with Alt held: "Open With" chooser
+                                let open_requested = !selecting
+                                    && if self.config.double_click_to_open {
+                                        interact.double_clicked()
+                                    } else {
+                                        interact.clicked()
+                                    };
+                                if open_requested {
+                                    if chooser_held {
+                                        open_with_chooser(&result.file_path);
+                                        let _ = self
+                                            .search_tx
+                                            .send(SearchCommand::Opened(result.file_path.clone()));
+                                    } else {
+                                        open_result(
+                                            &self.config,
+                                            &self.search_tx,
+                                            &result.file_path,
+                                            result.is_dir,
+                                        );
                                     }
-                                    ui.close_menu();
                                 }
-                                ui.separator();
-                                if ui.button("Copy full path").clicked() {
-                                    ctx.copy_text(result.file_path.to_string_lossy().to_string());
-                                    ui.close_menu();
+                                if !selecting && (interact.clicked() || open_requested) {
+                                    self.selected_index = Some(i);
+                                    self.multi_selected.clear();
+                                    self.selection_anchor = Some(i);
                                 }
-                                if ui.button("Copy file name").clicked() {
-                                    ctx.copy_text(result.file_name.clone());
-                                    ui.close_menu();
+
+                                // Right-click context menu
+                                interact.context_menu(|ui| {
+                                    self.context_menu_index = Some(i);
+                                    let selected_count = self.multi_selected.len();
+                                    if selected_count > 1 && self.multi_selected.contains(&i) {
+                                        // Bulk actions on the whole selection
+                                        if ui.button(format!("Open {} selected", selected_count)).clicked() {
+                                            open_selected_requested = true;
+                                            ui.close_menu();
+                                        }
+                                        let selected_paths = || -> Vec<PathBuf> {
+                                            self.multi_selected
+                                                .iter()
+                                                .filter_map(|&i| self.results.get(i))
+                                                .map(|r| r.file_path.clone())
+                                                .collect()
+                                        };
+                                        if ui.button(format!("Copy {} paths", selected_count)).clicked() {
+                                            let paths: Vec<String> = selected_paths()
+                                                .iter()
+                                                .map(|p| p.to_string_lossy().to_string())
+                                                .collect();
+                                            ctx.copy_text(paths.join("\n"));
+                                            ui.close_menu();
+                                        }
+                                        if ui.button(format!("Copy {} files", selected_count)).clicked() {
+                                            copy_files_to_clipboard(selected_paths());
+                                            ui.close_menu();
+                                        }
+                                        if ui.button(format!("Move {} to Trash", selected_count)).clicked() {
+                                            trash_requested = Some(selected_paths());
+                                            ui.close_menu();
+                                        }
+                                        if ui.button(format!("Delete {} permanently…", selected_count)).clicked() {
+                                            delete_requested = Some(selected_paths());
+                                            ui.close_menu();
+                                        }
+                                        ui.separator();
+                                    }
+                                    if ui.button("Open file").clicked() {
+                                        open_result(
+                                            &self.config,
+                                            &self.search_tx,
+                                            &result.file_path,
+                                            result.is_dir,
+                                        );
+                                        ui.close_menu();
+                                    }
+                                    if !result.is_dir && ui.button("Open with…").clicked() {
+                                        open_with_chooser(&result.file_path);
+                                        let _ = self
+                                            .search_tx
+                                            .send(SearchCommand::Opened(result.file_path.clone()));
+                                        ui.close_menu();
+                                    }
+                                    if self.config.open_commands.contains_key(&result.category())
+                                        && ui.button("Open with default").clicked()
+                                    {
+                                        let _ = open::that(&result.file_path);
+                                        let _ = self
+                                            .search_tx
+                                            .send(SearchCommand::Opened(result.file_path.clone()));
+                                        ui.close_menu();
+                                    }
+                                    if ui.button(REVEAL_LABEL).clicked() {
+                                        reveal_in_file_manager(&result.file_path);
+                                        ui.close_menu();
+                                    }
+                                    if ui.button("Open containing folder").clicked() {
+                                        if let Some(parent) = result.file_path.parent() {
+                                            let _ = open::that(parent);
+                                        }
+                                        ui.close_menu();
+                                    }
+                                    if ui.button("Open terminal here").clicked() {
+                                        let dir = if result.is_dir {
+                                            Some(result.file_path.as_path())
+                                        } else {
+                                            result.file_path.parent()
+                                        };
+                                        if let Some(dir) = dir {
+                                            open_terminal(&self.config, dir);
+                                        }
+                                        ui.close_menu();
+                                    }
+                                    if result.is_dir && ui.button("Open in new window").clicked() {
+                                        open_in_new_window(&result.file_path);
+                                        ui.close_menu();
+                                    }
+                                    if result.is_dir && ui.button("Search only in this folder").clicked() {
+                                        scope_requested = Some(result.file_path.clone());
+                                        ui.close_menu();
+                                    }
+                                    ui.separator();
+                                    let copy_file = ui.button(if result.is_dir { "Copy folder" } else { "Copy file" });
+                                    if copy_file.on_hover_text("To paste into another folder or app").clicked() {
+                                        copy_files_to_clipboard(vec![result.file_path.clone()]);
+                                        ui.close_menu();
+                                    }
+                                    if ui.button("Copy full path").clicked() {
+                                        ctx.copy_text(result.file_path.to_string_lossy().to_string());
+                                        ui.close_menu();
+                                    }
+                                    if ui.button("Copy file name").clicked() {
+                                        ctx.copy_text(result.file_name.clone());
+                                        ui.close_menu();
+                                    }
+                                    if ui.button("Copy folder path").clicked() {
+                                        if let Some(parent) = result.file_path.parent() {
+                                            ctx.copy_text(parent.to_string_lossy().to_string());
+                                        }
+                                        ui.close_menu();
+                                    }
+                                    ui.separator();
+                                    if ui.button("Move to Trash").clicked() {
+                                        trash_requested = Some(vec![result.file_path.clone()]);
+                                        ui.close_menu();
+                                    }
+                                    if ui.button("Delete permanently…").clicked() {
+                                        delete_requested = Some(vec![result.file_path.clone()]);
+                                        ui.close_menu();
+                                    }
+                                });
+
+                                // Tooltip
+                                if interact.hovered() {
+                                    interact.on_hover_text_at_pointer(
+                                        result.file_path.to_string_lossy().to_string(),
+                                    );
                                 }
-                            });
 
-                            // Scroll to selected item
-                            if self.scroll_to_selected && is_selected {
-                                ui.scroll_to_rect(row_resp.rect, Some(egui::Align::Center));
+                                self.row_heights[i] = ui.cursor().top() - row_top;
+                                y += self.row_heights[i];
+                                drawn_to = i + 1;
                             }
 
-                            // Tooltip
-                            if interact.hovered() {
-                                interact.on_hover_text_at_pointer(
-                                    result.file_path.to_string_lossy().to_string(),
-                                );
+                            if self.has_more && drawn_to == self.results.len() {
+                                ui.add_space(8.0);
+                                ui.vertical_centered(|ui| {
+                                    let label = if self.loading_more {
+                                        "Loading…"
+                                    } else {
+                                        "Load more results"
+                                    };
+                                    if ui.add_enabled(!self.loading_more, egui::Button::new(label)).clicked() {
+                                        load_more_requested = true;
+                                    }
+                                });
                             }
-                        }
+                        });
 
                         self.scroll_to_selected = false;
                     });
-            });
+
+                if open_selected_requested {
+                    self.open_selected();
+                }
+                if load_more_requested {
+                    self.loading_more = true;
+                    let _ = self.search_tx.send(SearchCommand::LoadMore);
+                }
+                if let Some(dir) = scope_requested {
+                    self.set_search_scope(Some(dir));
+                }
+                if let Some(paths) = trash_requested {
+                    self.delete_result_files(ctx, paths, false);
+                }
+                if delete_requested.is_some() {
+                    self.pending_delete = delete_requested;
+                }
+            });
+
+        if self.settings_open {
+            self.show_settings(ctx);
+        }
+        if self.pending_open.is_some() {
+            self.show_open_confirmation(ctx);
+        }
+        if self.pending_delete.is_some() {
+            self.show_delete_confirmation(ctx);
+        }
+        if self.rebuild_prompt_open {
+            self.show_rebuild_confirmation(ctx);
+        }
+        if self.failures_open {
+            self.show_failures(ctx);
+        }
+        if self.save_search_name.is_some() {
+            self.show_save_search_prompt(ctx);
+        }
+        if self.close_prompt_open {
+            self.show_close_prompt(ctx);
+        }
+    }
+
+    /// Window geometry is saved by eframe itself
+    fn save(&mut self, storage: &mut dyn eframe::Storage) {
+        let view = ViewState {
+            sort_column: self.sort_column,
+            sort_ascending: self.sort_ascending,
+            settings_open: self.settings_open,
+        };
+        eframe::set_value(storage, ViewState::KEY, &view);
+    }
+}
+
+impl DrozoSearchApp {
+    fn show_window(&mut self, ctx: &egui::Context) {
+        ctx.send_viewport_cmd(egui::ViewportCommand::Visible(true));
+        ctx.send_viewport_cmd(egui::ViewportCommand::Focus);
+        self.window_visible = true;
+        #[cfg(target_os = "macos")]
+        macos_show_app();
+    }
+
+    fn hide_to_tray(&mut self, ctx: &egui::Context) {
+        ctx.send_viewport_cmd(egui::ViewportCommand::Visible(false));
+        self.window_visible = false;
+        #[cfg(target_os = "macos")]
+        macos_hide_app();
+    }
+
+    /// "Hide to tray or quit?" prompt shown on close until a choice is remembered
+    fn show_close_prompt(&mut self, ctx: &egui::Context) {
+        let mut choice = None;
+        egui::Window::new("Close drozoSearch")
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::vec2(0.0, 0.0))
+            .show(ctx, |ui| {
+                ui.label("Keep drozoSearch running in the tray, or quit?");
+                ui.add_space(6.0);
+                ui.checkbox(&mut self.close_prompt_remember, "Remember my choice");
+                ui.add_space(6.0);
+                ui.horizontal(|ui| {
+                    for action in CloseAction::ALL {
+                        if ui.button(action.label()).clicked() {
+                            choice = Some(action);
+                        }
+                    }
+                    if ui.button("Cancel").clicked() {
+                        self.close_prompt_open = false;
+                    }
+                });
+            });
+
+        let Some(action) = choice else {
+            return;
+        };
+        self.close_prompt_open = false;
+        if self.close_prompt_remember {
+            self.config.close_action = action;
+            self.config.ask_on_close = false;
+        }
+        match action {
+            CloseAction::HideToTray => self.hide_to_tray(ctx),
+            CloseAction::Quit => {
+                self.quit_requested = true;
+                ctx.send_viewport_cmd(egui::ViewportCommand::Close);
+            }
+        }
+    }
+
+    /// Clicking a header sorts by it ascending, then descending, then goes
+    /// back to relevance order
+    fn toggle_sort(&mut self, column: SortColumn) {
+        if self.sort_column != column {
+            self.set_sort(column, true);
+        } else if self.sort_ascending {
+            self.set_sort(column, false);
+        } else {
+            self.set_sort(SortColumn::Relevance, true);
+        }
+    }
+
+    /// Sort the results by `column`, for the headers and the sort selector
+    /// alike. The cursor stays on the same file.
+    fn set_sort(&mut self, column: SortColumn, ascending: bool) {
+        self.sort_column = column;
+        self.sort_ascending = ascending;
+
+        let selected = self
+            .selected_index
+            .and_then(|i| self.results.get(i))
+            .map(|r| r.file_path.clone());
+        self.apply_sort();
+        if let Some(path) = selected {
+            self.selected_index = self.results.iter().position(|r| r.file_path == path);
+            self.scroll_to_selected = true;
+        }
+        // Row indices moved, so a multi-selection no longer points at the same files
+        self.clear_multi_selection();
+    }
+
+    /// Rebuild `results` from the ranked results in the current sort order.
+    /// The sort is stable, so ties keep their relevance order. Duplicate
+    /// groups stay together and in order; files are sorted within them.
+    fn apply_sort(&mut self) {
+        let shown = self.config.match_types;
+        self.results = self
+            .ranked_results
+            .iter()
+            .filter(|r| shown.shows(&r.match_type))
+            .cloned()
+            .collect();
+        // Rows moved, so their measured heights no longer line up
+        self.row_heights.clear();
+        let mut group_order = HashMap::new();
+        if self.find_duplicates {
+            for (i, r) in self.ranked_results.iter().enumerate() {
+                group_order.entry((r.content_hash.clone(), r.file_size)).or_insert(i);
+            }
+        }
+        let group = |r: &SearchResult| group_order.get(&(r.content_hash.clone(), r.file_size)).copied();
+        let column = self.sort_column;
+        if self.sort_ascending {
+            self.results
+                .sort_by(|a, b| group(a).cmp(&group(b)).then_with(|| column.compare(a, b)));
+        } else {
+            self.results
+                .sort_by(|a, b| group(a).cmp(&group(b)).then_with(|| column.compare(b, a)));
+        }
+    }
+
+    /// Dropdown of recent searches under the search box; clicking one runs it
+    fn recent_searches_menu(&mut self, ctx: &egui::Context, search_box: egui::Rect) {
+        let mut picked = None;
+        let area = egui::Area::new(egui::Id::new("recent_searches"))
+            .order(egui::Order::Foreground)
+            .fixed_pos(search_box.left_bottom() + egui::vec2(-8.0, 10.0))
+            .show(ctx, |ui| {
+                egui::Frame::popup(ui.style()).show(ui, |ui| {
+                    ui.set_width(search_box.width());
+                    ui.label(egui::RichText::new("Recent searches").size(10.0).color(theme::gray(110)));
+                    for (i, query) in self.search_history.iter().take(RECENT_SEARCHES_SHOWN).enumerate() {
+                        let entry = ui.selectable_label(
+                            self.history_pos == Some(i),
+                            egui::RichText::new(query).size(13.0),
+                        );
+                        if entry.clicked() {
+                            picked = Some(query.clone());
+                        }
+                    }
+                });
+            });
+        self.history_menu_hovered = area.response.contains_pointer();
+        if let Some(query) = picked {
+            self.query = query;
+            self.history_pos = None;
+            self.history_menu_hovered = false;
+            self.last_keystroke = Instant::now();
+            self.selected_index = None;
+            ctx.memory_mut(|m| m.request_focus(search_box_id()));
+        }
+    }
+
+    /// Compact sort order picker at the right of the status row. An order
+    /// set from the headers that isn't one of the choices shows as e.g.
+    /// `Location ▼`.
+    fn sort_selector(&mut self, ui: &mut egui::Ui) {
+        let current = (self.sort_column, self.sort_ascending);
+        let selected_text = SORT_CHOICES
+            .iter()
+            .find(|(_, column, ascending)| (*column, *ascending) == current)
+            .map_or_else(
+                || format!("{} {}", self.sort_column.label(), if self.sort_ascending { "▲" } else { "▼" }),
+                |(label, ..)| label.to_string(),
+            );
+        let mut picked = None;
+        egui::ComboBox::from_id_salt("sort_selector")
+            .selected_text(egui::RichText::new(selected_text).size(11.0).color(theme::gray(140)))
+            .width(90.0)
+            .show_ui(ui, |ui| {
+                for (label, column, ascending) in SORT_CHOICES {
+                    if ui.selectable_label((column, ascending) == current, label).clicked() {
+                        picked = Some((column, ascending));
+                    }
+                }
+            })
+            .response
+            .on_hover_text("Sort results");
+        if let Some((column, ascending)) = picked.filter(|&p| p != current) {
+            self.set_sort(column, ascending);
+        }
+    }
+
+    /// NAME / CONTENT / META toggles with how many results each has; a
+    /// hidden type's results are filtered out without searching again
+    fn match_type_chips(&mut self, ui: &mut egui::Ui) {
+        // Indexed as [metadata, content, name], the order the chips are added in
+        let mut counts = [0usize; 3];
+        for result in &self.ranked_results {
+            let slot = match result.match_type {
+                MatchType::Metadata => 0,
+                MatchType::Content => 1,
+                MatchType::FileName => 2,
+            };
+            counts[slot] += 1;
+        }
+        let mut changed = false;
+        let filter = &mut self.config.match_types;
+        // Laid out right to left, so META ends up last
+        let chips = [
+            ("META", &mut filter.metadata, counts[0]),
+            ("CONTENT", &mut filter.content, counts[1]),
+            ("NAME", &mut filter.name, counts[2]),
+        ];
+        for (label, shown, n) in chips {
+            let color = if *shown { theme::gray(200) } else { theme::gray(80) };
+            let chip = ui.selectable_label(
+                *shown,
+                egui::RichText::new(format!("{} {}", label, n)).size(10.0).color(color),
+            );
+            if chip.on_hover_text("Show or hide these matches").clicked() {
+                *shown = !*shown;
+                changed = true;
+            }
+        }
+        if !changed {
+            return;
+        }
+        if let Err(e) = self.config.save() {
+            self.notice = Some(format!("Couldn't save settings: {}", e));
+        }
+        let selected = self
+            .selected_index
+            .and_then(|i| self.results.get(i))
+            .map(|r| r.file_path.clone());
+        self.apply_sort();
+        self.selected_index =
+            selected.and_then(|path| self.results.iter().position(|r| r.file_path == path));
+        self.clear_multi_selection();
+    }
+
+    /// Drop the multi-selection, leaving just the cursor row
+    fn clear_multi_selection(&mut self) {
+        self.multi_selected.clear();
+        self.selection_anchor = None;
+    }
+
+    /// Open every selected row, asking first if there are a lot of them
+    fn open_selected(&mut self) {
+        let targets: Vec<(PathBuf, bool)> = self
+            .multi_selected
+            .iter()
+            .filter_map(|&i| self.results.get(i))
+            .map(|r| (r.file_path.clone(), r.is_dir))
+            .collect();
+        if targets.len() > OPEN_MANY_CONFIRM_THRESHOLD {
+            self.pending_open = Some(targets);
+        } else {
+            open_many(&self.config, &self.search_tx, targets);
+        }
+    }
+
+    fn show_open_confirmation(&mut self, ctx: &egui::Context) {
+        let count = self.pending_open.as_ref().map_or(0, Vec::len);
+        let mut decided = None;
+        egui::Window::new("Open files")
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::vec2(0.0, 0.0))
+            .show(ctx, |ui| {
+                ui.label(format!("Open {} files at once?", count));
+                ui.add_space(6.0);
+                ui.horizontal(|ui| {
+                    if ui.button("Open all").clicked() {
+                        decided = Some(true);
+                    }
+                    if ui.button("Cancel").clicked() {
+                        decided = Some(false);
+                    }
+                });
+            });
+        match decided {
+            Some(true) => {
+                if let Some(targets) = self.pending_open.take() {
+                    open_many(&self.config, &self.search_tx, targets);
+                }
+            }
+            Some(false) => self.pending_open = None,
+            None => {}
+        }
+    }
+
+    fn show_delete_confirmation(&mut self, ctx: &egui::Context) {
+        let what = match self.pending_delete.as_deref() {
+            Some([path]) => path
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_default(),
+            Some(paths) => format!("{} files", paths.len()),
+            None => String::new(),
+        };
+        let mut decided = None;
+        egui::Window::new("Delete permanently")
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::vec2(0.0, 0.0))
+            .show(ctx, |ui| {
+                ui.label(format!("Delete {} for good? This can't be undone.", what));
+                ui.add_space(6.0);
+                ui.horizontal(|ui| {
+                    if ui.button("Delete").clicked() {
+                        decided = Some(true);
+                    }
+                    if ui.button("Cancel").clicked() {
+                        decided = Some(false);
+                    }
+                });
+            });
+        match decided {
+            Some(true) => {
+                if let Some(paths) = self.pending_delete.take() {
+                    self.delete_result_files(ctx, paths, true);
+                }
+            }
+            Some(false) => self.pending_delete = None,
+            None => {}
+        }
+    }
+
+    /// Trash (or permanently delete) result files on a background thread;
+    /// see [`poll_deletes`](Self::poll_deletes) for what happens after.
+    fn delete_result_files(&mut self, ctx: &egui::Context, paths: Vec<PathBuf>, permanently: bool) {
+        let tx = self.deletes_tx.clone();
+        let ctx = ctx.clone();
+        thread::spawn(move || {
+            let _ = tx.send(trash::delete_all(paths, permanently));
+            ctx.request_repaint();
+        });
+    }
+
+    /// Deleted results go away and the indexer is told to drop them;
+    /// failures such as missing permissions show up as a notice.
+    fn poll_deletes(&mut self) {
+        while let Ok(outcome) = self.deletes_rx.try_recv() {
+            self.apply_deletion(outcome);
+        }
+    }
+
+    fn apply_deletion(&mut self, outcome: trash::DeleteOutcome) {
+        let trash::DeleteOutcome { permanently, deleted, failures } = outcome;
+        if let Some((path, e)) = failures.first() {
+            let action = if permanently { "delete" } else { "move to Trash" };
+            let more = match failures.len() {
+                1 => String::new(),
+                n => format!(" (and {} more)", n - 1),
+            };
+            self.notice = Some(format!("Couldn't {} {}: {}{}", action, path.display(), e, more));
+        }
+        if deleted.is_empty() {
+            return;
+        }
+
+        let selected = self
+            .selected_index
+            .and_then(|i| self.results.get(i))
+            .map(|r| r.file_path.clone());
+        // A deleted folder takes its contents with it
+        let gone = |r: &SearchResult| deleted.iter().any(|path| r.file_path.starts_with(path));
+        self.results.retain(|r| !gone(r));
+        self.ranked_results.retain(|r| !gone(r));
+        self.clear_multi_selection();
+        self.selected_index = selected.and_then(|p| self.results.iter().position(|r| r.file_path == p));
+
+        if let Some(indexer_tx) = &self.indexer_tx {
+            let _ = indexer_tx.send(IndexerCommand::FilesChanged(deleted));
+        }
+    }
+
+    fn show_rebuild_confirmation(&mut self, ctx: &egui::Context) {
+        let mut decided = None;
+        egui::Window::new("Rebuild index")
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::vec2(0.0, 0.0))
+            .show(ctx, |ui| {
+                ui.label("Delete the whole index and index every folder again?");
+                ui.label(
+                    egui::RichText::new("Searches find nothing until the first files are back in")
+                        .size(11.0)
+                        .color(theme::gray(120)),
+                );
+                ui.add_space(6.0);
+                ui.horizontal(|ui| {
+                    if ui.button("Rebuild").clicked() {
+                        decided = Some(true);
+                    }
+                    if ui.button("Cancel").clicked() {
+                        decided = Some(false);
+                    }
+                });
+            });
+        match decided {
+            Some(true) => {
+                self.rebuild_prompt_open = false;
+                self.rebuild_index();
+            }
+            Some(false) => self.rebuild_prompt_open = false,
+            None => {}
+        }
+    }
+
+    /// Files from the last pass whose content couldn't be read or that
+    /// couldn't be indexed, with the reason
+    fn show_failures(&mut self, ctx: &egui::Context) {
+        let mut open = self.failures_open;
+        let stats = last_pass_stats(&self.index_status);
+        egui::Window::new("Skipped files")
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(true)
+            .default_width(520.0)
+            .show(ctx, |ui| {
+                let Some(stats) = stats.filter(|s| s.failed > 0) else {
+                    ui.label("Every file of the last pass was read");
+                    return;
+                };
+                ui.label(
+                    egui::RichText::new("Their content isn't searchable, or they weren't indexed at all")
+                        .size(11.0)
+                        .color(theme::gray(120)),
+                );
+                let listed = stats.failures.len() as u64;
+                if stats.failed > listed {
+                    ui.label(
+                        egui::RichText::new(format!("Showing the first {} of {}", listed, stats.failed))
+                            .size(11.0)
+                            .color(theme::gray(120)),
+                    );
+                }
+                ui.add_space(4.0);
+                egui::ScrollArea::vertical().max_height(360.0).show(ui, |ui| {
+                    for (path, error) in &stats.failures {
+                        ui.label(
+                            egui::RichText::new(path.to_string_lossy()).size(12.0).color(theme::gray(200)),
+                        );
+                        ui.label(egui::RichText::new(error).size(11.0).color(theme::rgb(255, 120, 100)));
+                        ui.add_space(2.0);
+                    }
+                });
+            });
+        self.failures_open = open;
+    }
+
+    /// Have the indexer look for changes on disk now, e.g. after copying in
+    /// files somewhere the watcher doesn't reach. Nothing is queued while a
+    /// pass is already running.
+    fn rescan_index(&mut self) {
+        let Some(indexer_tx) = &self.indexer_tx else {
+            self.notice = Some(if self.index_read_only {
+                "The other drozoSearch instance keeps the index up to date".into()
+            } else {
+                "Choose a folder to index first".into()
+            });
+            return;
+        };
+        if matches!(
+            self.index_status,
+            IndexStatus::Starting | IndexStatus::Counting | IndexStatus::Indexing | IndexStatus::Committing
+        ) {
+            return;
+        }
+        let _ = indexer_tx.send(IndexerCommand::Rescan);
+        self.index_status = IndexStatus::Starting;
+        self.progress_target = 0.0;
+    }
+
+    /// Have the indexer wipe the index and start over
+    fn rebuild_index(&mut self) {
+        let Some(indexer_tx) = &self.indexer_tx else {
+            return;
+        };
+        let _ = indexer_tx.send(IndexerCommand::Rebuild);
+        self.index_status = IndexStatus::Starting;
+        self.files_indexed = 0;
+        self.estimated_total = 0;
+        self.files_per_sec = 0.0;
+        self.eta_seconds = None;
+        self.progress_target = 0.0;
+    }
+
+    fn show_settings(&mut self, ctx: &egui::Context) {
+        if let Some(dir) = self.poll_folder_pick() {
+            self.add_draft_root(dir);
+        }
+        let draft = self
+            .roots_draft
+            .get_or_insert_with(|| self.config.root_dirs.clone())
+            .clone();
+
+        // Volume availability touches the filesystem, so refresh it every few seconds
+        let stale = self
+            .root_availability_checked
+            .is_none_or(|t| t.elapsed() >= std::time::Duration::from_secs(3));
+        if stale {
+            self.root_availability = draft
+                .iter()
+                .map(|root| (root.clone(), volumes::is_available(root, &self.config.mounted_roots_path)))
+                .collect();
+            self.root_availability_checked = Some(Instant::now());
+        }
+        ctx.request_repaint_after(std::time::Duration::from_secs(3));
+
+        let mut open = self.settings_open;
+        let mut remove_root = None;
+        let mut add_typed = false;
+        let mut browse = false;
+        let mut save_roots = false;
+        egui::Window::new("Settings")
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(true)
+            .default_width(420.0)
+            .show(ctx, |ui| {
+                ui.label(
+                    egui::RichText::new("Indexed folders")
+                        .size(12.0)
+                        .strong()
+                        .color(theme::gray(180)),
+                );
+                ui.add_space(4.0);
+                for (i, (root, available)) in self.root_availability.iter().enumerate() {
+                    ui.horizontal(|ui| {
+                        let (rect, _) =
+                            ui.allocate_exact_size(egui::vec2(10.0, 10.0), egui::Sense::hover());
+                        let dot_color = if *available {
+                            theme::rgb(60, 200, 80)
+                        } else {
+                            theme::gray(90)
+                        };
+                        ui.painter().circle_filled(rect.center(), 4.0, dot_color);
+                        ui.label(
+                            egui::RichText::new(root.to_string_lossy())
+                                .size(12.0)
+                                .color(theme::gray(200)),
+                        );
+                        if !*available {
+                            ui.label(
+                                egui::RichText::new("offline, kept in index")
+                                    .size(10.0)
+                                    .color(theme::gray(100)),
+                            );
+                        }
+                        let remove = ui.add(egui::Button::new("✕").frame(false));
+                        if remove.on_hover_text("Stop indexing this folder").clicked() {
+                            remove_root = Some(i);
+                        }
+                    });
+                }
+                if draft.is_empty() {
+                    ui.label(
+                        egui::RichText::new("No folders; nothing will be indexed")
+                            .size(11.0)
+                            .color(theme::gray(100)),
+                    );
+                }
+                ui.add_space(4.0);
+                ui.horizontal(|ui| {
+                    let picking = self.folder_pick_rx.is_some();
+                    browse = ui
+                        .add_enabled(!picking, egui::Button::new("Add folder…"))
+                        .clicked();
+                    let input = ui.add(
+                        egui::TextEdit::singleline(&mut self.root_input)
+                            .hint_text("or type a path")
+                            .desired_width(200.0),
+                    );
+                    add_typed = input.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+                    add_typed |= ui.button("Add").clicked();
+                });
+                ui.add_space(4.0);
+                let changed = draft != self.config.root_dirs;
+                ui.horizontal(|ui| {
+                    save_roots = ui
+                        .add_enabled(changed, egui::Button::new("Save and re-index"))
+                        .clicked();
+                    if ui.add_enabled(changed, egui::Button::new("Revert")).clicked() {
+                        self.roots_draft = None;
+                        self.root_availability_checked = None;
+                    }
+                    let rebuild = ui
+                        .add_enabled(self.indexer_tx.is_some(), egui::Button::new("Rebuild index…"))
+                        .on_hover_text("Delete the index and index every folder again from scratch");
+                    if rebuild.clicked() {
+                        self.rebuild_prompt_open = true;
+                    }
+                });
+                egui::CollapsingHeader::new("Index statistics").show(ui, |ui| {
+                    self.show_index_health(ui);
+                });
+
+                ui.add_space(10.0);
+                ui.label(
+                    egui::RichText::new("Results")
+                        .size(12.0)
+                        .strong()
+                        .color(theme::gray(180)),
+                );
+                ui.add_space(4.0);
+                let before = self.config.directory_order;
+                egui::ComboBox::from_label("Directories")
+                    .selected_text(self.config.directory_order.label())
+                    .show_ui(ui, |ui| {
+                        for order in DirectoryOrder::ALL {
+                            ui.selectable_value(&mut self.config.directory_order, order, order.label());
+                        }
+                    });
+                if self.config.directory_order != before {
+                    self.apply_search_settings();
+                }
+                if ui
+                    .checkbox(&mut self.config.fuzzy_matching, "Tolerate typos in file names")
+                    .on_hover_text("When a search finds hardly anything, also show names one or two letters off")
+                    .changed()
+                {
+                    self.apply_search_settings();
+                }
+                let mut names_only = self.config.names_only;
+                if ui
+                    .checkbox(&mut names_only, "Search file names only")
+                    .on_hover_text("Skip contents and metadata for faster searches (Ctrl+Shift+F)")
+                    .changed()
+                {
+                    self.set_names_only(names_only);
+                }
+                ui.checkbox(&mut self.config.show_full_paths, "Show full paths");
+                egui::CollapsingHeader::new("Ranking").show(ui, |ui| {
+                    // Results re-rank while dragging; the config is saved on release
+                    let mut changed = false;
+                    let mut released = false;
+                    for (label, weight) in self.config.rank_weights.labeled_mut() {
+                        let slider = ui.add(egui::Slider::new(weight, 0.0..=10.0).text(label));
+                        changed |= slider.changed();
+                        released |= slider.drag_stopped() || slider.clicked() || slider.lost_focus();
+                    }
+                    let defaults = RankWeights::default();
+                    if ui
+                        .add_enabled(self.config.rank_weights != defaults, egui::Button::new("Reset to defaults"))
+                        .clicked()
+                    {
+                        self.config.rank_weights = defaults;
+                        changed = true;
+                        released = true;
+                    }
+                    if changed {
+                        self.apply_search_settings();
+                    }
+                    if released {
+                        if let Err(e) = self.config.save() {
+                            self.notice = Some(format!("Couldn't save settings: {}", e));
+                        }
+                    }
+                });
+
+                ui.add_space(10.0);
+                ui.label(
+                    egui::RichText::new("Window")
+                        .size(12.0)
+                        .strong()
+                        .color(theme::gray(180)),
+                );
+                ui.add_space(4.0);
+                let before = self.config.theme;
+                egui::ComboBox::from_label("Theme")
+                    .selected_text(self.config.theme.label())
+                    .show_ui(ui, |ui| {
+                        for theme in Theme::ALL {
+                            ui.selectable_value(&mut self.config.theme, theme, theme.label());
+                        }
+                    });
+                if self.config.theme != before {
+                    theme::apply_theme(ctx, self.config.theme);
+                    if let Err(e) = self.config.save() {
+                        self.notice = Some(format!("Couldn't save settings: {}", e));
+                    }
+                }
+                if let Some(error) = &self.tray_error {
+                    ui.label(
+                        egui::RichText::new("System tray unavailable: closing the window quits")
+                            .size(11.0)
+                            .color(theme::rgb(255, 190, 60)),
+                    )
+                    .on_hover_text(error);
+                } else {
+                    let before = self.config.close_action;
+                    egui::ComboBox::from_label("When closing the window")
+                        .selected_text(self.config.close_action.label())
+                        .show_ui(ui, |ui| {
+                            for action in CloseAction::ALL {
+                                ui.selectable_value(&mut self.config.close_action, action, action.label());
+                            }
+                        });
+                    if self.config.close_action != before {
+                        self.config.ask_on_close = false;
+                    }
+                }
+                if !self.config.global_hotkey.trim().is_empty() {
+                    ui.add_space(4.0);
+                    match &self.hotkey_error {
+                        Some(error) => {
+                            ui.label(
+                                egui::RichText::new(format!(
+                                    "Shortcut {} unavailable",
+                                    self.config.global_hotkey
+                                ))
+                                .size(11.0)
+                                .color(theme::rgb(255, 190, 60)),
+                            )
+                            .on_hover_text(error);
+                        }
+                        None => {
+                            ui.label(
+                                egui::RichText::new(format!(
+                                    "Press {} anywhere to bring up drozoSearch",
+                                    self.config.global_hotkey
+                                ))
+                                .size(11.0)
+                                .color(theme::gray(180)),
+                            );
+                        }
+                    }
+                }
+            });
+
+        if let Some(i) = remove_root {
+            if let Some(roots) = &mut self.roots_draft {
+                roots.remove(i);
+            }
+            self.root_availability_checked = None;
+        }
+        if add_typed {
+            let dir = PathBuf::from(self.root_input.trim());
+            if dir.is_dir() {
+                self.root_input.clear();
+                self.add_draft_root(dir);
+            } else {
+                self.notice = Some(format!("Not a folder: {}", dir.display()));
+            }
+        }
+        if browse {
+            self.folder_pick_rx = Some(pick_folder(ctx));
+        }
+        if save_roots {
+            if let Some(roots) = self.roots_draft.take() {
+                self.config.root_dirs = roots;
+                if let Err(e) = self.config.save() {
+                    self.notice = Some(format!("Couldn't save settings: {}", e));
+                }
+                self.reindex(ctx);
+            }
+        }
+        if !open {
+            // Closing the window discards unsaved folder edits
+            self.roots_draft = None;
+            self.root_availability_checked = None;
+            // Statistics are collected afresh the next time they're shown
+            self.index_health = None;
+        }
+        self.settings_open = open;
+    }
+
+    /// Add a folder to the settings draft. A folder already covered by an
+    /// existing root is refused so it isn't indexed twice; roots inside the
+    /// new folder are folded into it.
+    fn add_draft_root(&mut self, dir: PathBuf) {
+        let dir = std::fs::canonicalize(&dir).unwrap_or(dir);
+        let roots = self
+            .roots_draft
+            .get_or_insert_with(|| self.config.root_dirs.clone());
+        if let Some(parent) = roots.iter().find(|root| dir.starts_with(root)) {
+            self.notice = Some(format!(
+                "{} is already indexed as part of {}",
+                dir.display(),
+                parent.display()
+            ));
+            return;
+        }
+        roots.retain(|root| !root.starts_with(&dir));
+        roots.push(dir);
+        self.root_availability_checked = None;
+    }
+
+    /// Body of the "Index statistics" section; collects them on first show
+    fn show_index_health(&mut self, ui: &mut egui::Ui) {
+        if self.index_health.is_none() && self.index_health_rx.is_none() {
+            self.refresh_index_health(ui.ctx());
+        }
+        match &self.index_health {
+            None => {
+                ui.label(egui::RichText::new("Reading index…").size(11.0).color(theme::gray(120)));
+            }
+            Some(Err(e)) => {
+                ui.label(
+                    egui::RichText::new(format!("Couldn't read index statistics: {}", e))
+                        .size(11.0)
+                        .color(theme::rgb(255, 190, 60)),
+                );
+            }
+            Some(Ok(health)) => {
+                let mut rows = vec![
+                    ("Documents", format!("{} ({} folders)", health.docs, health.dirs)),
+                    ("Deleted, not yet merged", health.deleted_docs.to_string()),
+                    ("Segments", health.segments.to_string()),
+                    ("Size on disk", format_size(health.disk_bytes)),
+                    (
+                        "Last commit",
+                        health.last_commit.map_or("never".to_string(), format_time_ago),
+                    ),
+                ];
+                for (label, count) in &health.field_docs {
+                    rows.push((label, format!("{} documents", count)));
+                }
+                egui::Grid::new("index_health").num_columns(2).show(ui, |ui| {
+                    for (label, value) in rows {
+                        ui.label(egui::RichText::new(label).size(11.0).color(theme::gray(140)));
+                        ui.label(egui::RichText::new(value).size(11.0).color(theme::gray(200)));
+                        ui.end_row();
+                    }
+                });
+            }
+        }
+        if ui
+            .add_enabled(self.index_health_rx.is_none(), egui::Button::new("Refresh"))
+            .clicked()
+        {
+            self.refresh_index_health(ui.ctx());
+        }
+    }
+
+    /// Collect index statistics on a background thread
+    fn refresh_index_health(&mut self, ctx: &egui::Context) {
+        let (tx, rx) = mpsc::channel();
+        let index = self.index.clone();
+        let index_path = self.config.index_path.clone();
+        let ctx = ctx.clone();
+        thread::spawn(move || {
+            let health = stats::collect(&index, &index_path).map_err(|e| e.to_string());
+            let _ = tx.send(health);
+            ctx.request_repaint();
+        });
+        self.index_health_rx = Some(rx);
+    }
+
+    fn poll_index_health(&mut self) {
+        let Some(rx) = &self.index_health_rx else {
+            return;
+        };
+        match rx.try_recv() {
+            Ok(health) => {
+                self.index_health = Some(health);
+                self.index_health_rx = None;
+            }
+            Err(mpsc::TryRecvError::Empty) => {}
+            Err(mpsc::TryRecvError::Disconnected) => self.index_health_rx = None,
+        }
+    }
+
+    /// Report a finished CSV export
+    fn poll_export(&mut self) {
+        let Some(rx) = &self.export_rx else {
+            return;
+        };
+        match rx.try_recv() {
+            Ok(outcome) => {
+                self.export_rx = None;
+                match outcome {
+                    Some(Ok((path, count))) => {
+                        self.notice = Some(format!("Exported {} results to {}", count, path.display()));
+                    }
+                    Some(Err(e)) => self.notice = Some(e),
+                    None => {}
+                }
+            }
+            Err(mpsc::TryRecvError::Empty) => {}
+            Err(mpsc::TryRecvError::Disconnected) => self.export_rx = None,
+        }
+    }
+
+    /// Result of the folder dialog, once it has been answered
+    fn poll_folder_pick(&mut self) -> Option<PathBuf> {
+        let picked = self.folder_pick_rx.as_ref()?.try_recv();
+        match picked {
+            Ok(dir) => {
+                self.folder_pick_rx = None;
+                dir
+            }
+            Err(mpsc::TryRecvError::Empty) => None,
+            Err(mpsc::TryRecvError::Disconnected) => {
+                self.folder_pick_rx = None;
+                None
+            }
+        }
+    }
+
+    /// Index `config.root_dirs` again after they changed, starting the
+    /// indexer if no folder was configured before.
+    fn reindex(&mut self, ctx: &egui::Context) {
+        if self.index_read_only {
+            self.notice = Some("The other drozoSearch instance indexes these folders: change them there".into());
+            return;
+        }
+        self.root_availability_checked = None;
+        if let Some(indexer_tx) = &self.indexer_tx {
+            let _ = indexer_tx.send(IndexerCommand::Reindex(Box::new(self.config.clone())));
+        } else if self.config.root_dirs.is_empty() {
+            return;
+        } else if let Some((index, progress_tx)) = self.pending_indexer.take() {
+            self.indexer_tx = Some(coordinator::start_indexing(
+                index,
+                self.config.clone(),
+                UiProgress::new(progress_tx, ctx.clone()),
+            ));
+        }
+        self.index_status = IndexStatus::Starting;
+        self.progress_target = 0.0;
+    }
+
+    /// First-run prompt shown when there's no folder to index (e.g. the home
+    /// directory couldn't be resolved)
+    fn first_root_prompt(&mut self, ui: &mut egui::Ui, ctx: &egui::Context) {
+        ui.label(
+            egui::RichText::new("Choose a folder to index")
+                .size(13.0)
+                .color(theme::gray(140)),
+        );
+        ui.add_space(6.0);
+        let mut submit = false;
+        ui.horizontal(|ui| {
+            ui.add_space(ui.available_width() / 2.0 - 170.0);
+            let input = ui.add(
+                egui::TextEdit::singleline(&mut self.root_input)
+                    .hint_text("/path/to/folder")
+                    .desired_width(260.0),
+            );
+            submit = input.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+            submit |= ui.button("Index").clicked();
+        });
+
+        let dir = PathBuf::from(self.root_input.trim());
+        if submit && dir.is_dir() {
+            self.root_input.clear();
+            self.config.root_dirs = vec![dir];
+            if let Err(e) = self.config.save() {
+                self.notice = Some(format!("Couldn't save settings: {}", e));
+            }
+            self.reindex(ctx);
+        } else if submit {
+            self.notice = Some(format!("Not a folder: {}", dir.display()));
+        }
+    }
+
+    /// Advanced action: copy the paths of current results matching a glob
+    fn copy_matching_menu(&mut self, ui: &mut egui::Ui, ctx: &egui::Context) {
+        ui.label(
+            egui::RichText::new("Copy result paths matching a glob")
+                .size(11.0)
+                .color(theme::gray(150)),
+        );
+        ui.add(
+            egui::TextEdit::singleline(&mut self.copy_glob)
+                .hint_text("**/src/**/*.rs")
+                .desired_width(220.0),
+        );
+        match paths_matching_glob(&self.results, self.copy_glob.trim()) {
+            Ok(paths) => {
+                let button = egui::Button::new(format!("Copy matching ({})", paths.len()));
+                if ui.add_enabled(!paths.is_empty(), button).clicked() {
+                    ctx.copy_text(paths.join("\n"));
+                    self.notice = Some(format!("Copied {} paths", paths.len()));
+                    ui.close_menu();
+                }
+            }
+            Err(e) => {
+                ui.label(
+                    egui::RichText::new(e.kind().to_string())
+                        .size(11.0)
+                        .color(theme::rgb(255, 80, 80)),
+                );
+            }
+        }
+    }
+
+    /// Restrict searches to `dir` (or lift the restriction) and re-run the current query
+    fn set_search_scope(&mut self, dir: Option<PathBuf>) {
+        self.search_scope = dir.clone();
+        let _ = self.search_tx.send(SearchCommand::Scope(dir));
+        if self.is_searchable(&self.last_query_sent) {
+            let _ = self
+                .search_tx
+                .send(SearchCommand::Query(self.last_query_sent.clone()));
+        }
+    }
+
+    /// Whether a copy shortcut is meant for text: some other field has the
+    /// keyboard, or part of the query is selected in the search box
+    fn copying_text(&self, ctx: &egui::Context) -> bool {
+        let id = search_box_id();
+        if !ctx.memory(|m| m.has_focus(id)) {
+            return ctx.wants_keyboard_input();
+        }
+        egui::TextEdit::load_state(ctx, id)
+            .and_then(|state| state.cursor.char_range())
+            .is_some_and(|range| range.primary != range.secondary)
+    }
+
+    /// Whether a query should be sent to the search thread. Finding
+    /// duplicates works with no query at all.
+    fn is_searchable(&self, query: &str) -> bool {
+        self.find_duplicates || is_searchable(query, self.config.min_query_len)
+    }
+
+    /// Search for `query` now, or clear the results if it can't be searched
+    fn send_query(&mut self) {
+        self.query_error = regex_pattern(&self.query).and_then(|p| NameRegex::new(p).err());
+        if self.is_searchable(&self.query) && self.query_error.is_none() {
+            let _ = self.search_tx.send(SearchCommand::Query(self.query.clone()));
+        } else {
+            self.results.clear();
+            self.ranked_results.clear();
+        }
+        self.last_query_sent = self.query.clone();
+    }
+
+    /// Put a saved search in the search box and run it straight away
+    fn run_saved_search(&mut self, ctx: &egui::Context, query: String) {
+        self.query = query;
+        self.history_pos = None;
+        self.selected_index = None;
+        self.clear_multi_selection();
+        self.send_query();
+        ctx.memory_mut(|m| m.request_focus(search_box_id()));
+    }
+
+    /// Sidebar listing the saved searches; a click runs one
+    fn saved_searches_panel(&mut self, ctx: &egui::Context) {
+        let mut run = None;
+        let mut remove = None;
+        egui::SidePanel::left("saved_searches")
+            .resizable(true)
+            .default_width(170.0)
+            .frame(
+                egui::Frame::NONE
+                    .inner_margin(egui::Margin::symmetric(8, 10))
+                    .fill(theme::gray(22)),
+            )
+            .show(ctx, |ui| {
+                ui.label(
+                    egui::RichText::new("SAVED SEARCHES")
+                        .size(10.0)
+                        .color(theme::gray(110)),
+                );
+                ui.add_space(4.0);
+                egui::ScrollArea::vertical().show(ui, |ui| {
+                    for (i, saved) in self.config.saved_searches.iter().enumerate() {
+                        let active = self.query == saved.query;
+                        let entry = ui.selectable_label(active, egui::RichText::new(&saved.name).size(13.0));
+                        let entry = entry.on_hover_text(&saved.query);
+                        if entry.clicked() {
+                            run = Some(saved.query.clone());
+                        }
+                        entry.context_menu(|ui| {
+                            if ui.button("Remove").clicked() {
+                                remove = Some(i);
+                                ui.close_menu();
+                            }
+                        });
+                    }
+                });
+            });
+        if let Some(query) = run {
+            self.run_saved_search(ctx, query);
+        }
+        if let Some(i) = remove {
+            self.config.saved_searches.remove(i);
+            if let Err(e) = self.config.save() {
+                self.notice = Some(format!("Couldn't save settings: {}", e));
+            }
+        }
+    }
+
+    /// Asks for a name to save the current query under. Saving under a name
+    /// that's taken replaces that search.
+    fn show_save_search_prompt(&mut self, ctx: &egui::Context) {
+        let Some(name) = &mut self.save_search_name else {
+            return;
+        };
+        let mut decided = None;
+        egui::Window::new("Save search")
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::vec2(0.0, 0.0))
+            .show(ctx, |ui| {
+                ui.label(
+                    egui::RichText::new(&self.query)
+                        .size(11.0)
+                        .color(theme::gray(120)),
+                );
+                ui.add_space(4.0);
+                let field = ui.add(egui::TextEdit::singleline(name).hint_text("Name"));
+                field.request_focus();
+                let entered = field.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+                ui.add_space(6.0);
+                ui.horizontal(|ui| {
+                    let named = !name.trim().is_empty();
+                    if ui.add_enabled(named, egui::Button::new("Save")).clicked() || (entered && named) {
+                        decided = Some(true);
+                    }
+                    if ui.button("Cancel").clicked() || ui.input(|i| i.key_pressed(egui::Key::Escape)) {
+                        decided = Some(false);
+                    }
+                });
+            });
+        match decided {
+            Some(true) => {
+                let name = self.save_search_name.take().unwrap_or_default().trim().to_string();
+                let query = self.query.clone();
+                match self.config.saved_searches.iter_mut().find(|s| s.name == name) {
+                    Some(saved) => saved.query = query,
+                    None => self.config.saved_searches.push(SavedSearch { name, query }),
+                }
+                if let Err(e) = self.config.save() {
+                    self.notice = Some(format!("Couldn't save settings: {}", e));
+                }
+            }
+            Some(false) => self.save_search_name = None,
+            None => {}
+        }
+    }
+
+    /// Switch "Find duplicates" mode and re-run the current query in it
+    fn set_find_duplicates(&mut self, on: bool) {
+        self.find_duplicates = on;
+        if on && !self.config.detect_duplicates {
+            self.notice = Some(
+                "Files aren't hashed yet: set detect_duplicates = true in the config file and rebuild the index"
+                    .into(),
+            );
+        }
+        let _ = self.search_tx.send(SearchCommand::Duplicates(on));
+        self.selected_index = None;
+        self.clear_multi_selection();
+        if self.is_searchable(&self.last_query_sent) {
+            let _ = self
+                .search_tx
+                .send(SearchCommand::Query(self.last_query_sent.clone()));
+        } else {
+            self.results.clear();
+            self.ranked_results.clear();
+        }
+    }
+
+    /// Removable chip in the search box showing the active folder scope
+    fn scope_chip(&mut self, ui: &mut egui::Ui) {
+        let Some(dir) = &self.search_scope else {
+            return;
+        };
+        let name = dir
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_else(|| dir.to_string_lossy().to_string());
+        let mut clear = false;
+        egui::Frame::NONE
+            .inner_margin(egui::Margin::symmetric(6, 2))
+            .corner_radius(egui::CornerRadius::same(4))
+            .fill(theme::rgb(30, 50, 80))
+            .show(ui, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label(
+                        egui::RichText::new(format!("in {}", name))
+                            .size(12.0)
+                            .color(theme::rgb(150, 190, 255)),
+                    )
+                    .on_hover_text(dir.to_string_lossy());
+                    let x = ui.add(
+                        egui::Button::new(egui::RichText::new("✕").size(10.0)).frame(false),
+                    );
+                    if x.on_hover_text("Search everywhere").clicked() {
+                        clear = true;
+                    }
+                });
+            });
+        if clear {
+            self.set_search_scope(None);
+        }
+    }
+
+    fn names_only_chip(&mut self, ui: &mut egui::Ui) {
+        let mut clear = false;
+        egui::Frame::NONE
+            .inner_margin(egui::Margin::symmetric(6, 2))
+            .corner_radius(egui::CornerRadius::same(4))
+            .fill(theme::rgb(25, 60, 30))
+            .show(ui, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label(
+                        egui::RichText::new("names only")
+                            .size(12.0)
+                            .color(theme::rgb(90, 210, 90)),
+                    )
+                    .on_hover_text("Contents and metadata aren't searched (Ctrl+Shift+F)");
+                    let x = ui.add(
+                        egui::Button::new(egui::RichText::new("✕").size(10.0)).frame(false),
+                    );
+                    if x.on_hover_text("Search contents and metadata too").clicked() {
+                        clear = true;
+                    }
+                });
+            });
+        if clear {
+            self.set_names_only(false);
+        }
+    }
+
+    /// Switch the sticky names-only mode, remembering it for next time
+    fn set_names_only(&mut self, on: bool) {
+        self.config.names_only = on;
+        if let Err(e) = self.config.save() {
+            self.notice = Some(format!("Couldn't save settings: {}", e));
+        }
+        self.apply_search_settings();
+    }
+
+    /// Push changed search settings to the search thread and re-run the current query
+    fn apply_search_settings(&mut self) {
+        let _ = self.search_tx.send(SearchCommand::Configure(Box::new(self.config.clone())));
+        if self.is_searchable(&self.last_query_sent) {
+            let _ = self
+                .search_tx
+                .send(SearchCommand::Query(self.last_query_sent.clone()));
+        }
     }
 }
 
 // ── File type icon based on extension ──
 fn file_icon(result: &SearchResult) -> (&'static str, egui::Color32) {
     if result.is_dir {
-        return ("D", egui::Color32::from_rgb(90, 170, 255));
+        return ("D", theme::rgb(90, 170, 255));
     }
 
     let ext = result
@@ -844,97 +3240,128 @@ fn file_icon(result: &SearchResult) -> (&'static str, egui::Color32) {
 
     match ext.as_str() {
         // Rust
-        "rs" => ("Rs", egui::Color32::from_rgb(255, 120, 50)),
+        "rs" => ("Rs", theme::rgb(255, 120, 50)),
         // JavaScript / TypeScript
-        "js" | "jsx" | "mjs" => ("Js", egui::Color32::from_rgb(255, 220, 60)),
-        "ts" | "tsx" => ("Ts", egui::Color32::from_rgb(50, 130, 240)),
+        "js" | "jsx" | "mjs" => ("Js", theme::rgb(255, 220, 60)),
+        "ts" | "tsx" => ("Ts", theme::rgb(50, 130, 240)),
         // Python
-        "py" => ("Py", egui::Color32::from_rgb(80, 180, 80)),
+        "py" => ("Py", theme::rgb(80, 180, 80)),
         // Go
-        "go" => ("Go", egui::Color32::from_rgb(0, 190, 220)),
+        "go" => ("Go", theme::rgb(0, 190, 220)),
         // C / C++
-        "c" | "h" => ("C", egui::Color32::from_rgb(100, 150, 220)),
-        "cpp" | "hpp" | "cc" | "cxx" => ("C+", egui::Color32::from_rgb(100, 150, 220)),
+        "c" | "h" => ("C", theme::rgb(100, 150, 220)),
+        "cpp" | "hpp" | "cc" | "cxx" => ("C+", theme::rgb(100, 150, 220)),
         // Java / Kotlin
-        "java" => ("Jv", egui::Color32::from_rgb(230, 100, 50)),
-        "kt" | "kts" => ("Kt", egui::Color32::from_rgb(170, 100, 255)),
+        "java" => ("Jv", theme::rgb(230, 100, 50)),
+        "kt" | "kts" => ("Kt", theme::rgb(170, 100, 255)),
         // Ruby
-        "rb" => ("Rb", egui::Color32::from_rgb(220, 50, 50)),
+        "rb" => ("Rb", theme::rgb(220, 50, 50)),
         // Swift
-        "swift" => ("Sw", egui::Color32::from_rgb(255, 130, 50)),
+        "swift" => ("Sw", theme::rgb(255, 130, 50)),
         // Shell
-        "sh" | "bash" | "zsh" => ("Sh", egui::Color32::from_rgb(130, 200, 100)),
+        "sh" | "bash" | "zsh" => ("Sh", theme::rgb(130, 200, 100)),
         // Web
-        "html" | "htm" => ("Ht", egui::Color32::from_rgb(230, 100, 50)),
-        "css" | "scss" | "sass" => ("Cs", egui::Color32::from_rgb(80, 140, 230)),
-        "vue" => ("Vu", egui::Color32::from_rgb(65, 184, 131)),
-        "svelte" => ("Sv", egui::Color32::from_rgb(255, 62, 0)),
+        "html" | "htm" => ("Ht", theme::rgb(230, 100, 50)),
+        "css" | "scss" | "sass" => ("Cs", theme::rgb(80, 140, 230)),
+        "vue" => ("Vu", theme::rgb(65, 184, 131)),
+        "svelte" => ("Sv", theme::rgb(255, 62, 0)),
         // Data / Config
-        "json" => ("Js", egui::Color32::from_rgb(200, 200, 100)),
-        "yaml" | "yml" => ("Ym", egui::Color32::from_rgb(200, 100, 100)),
-        "toml" => ("Tm", egui::Color32::from_rgb(150, 150, 200)),
-        "xml" => ("Xm", egui::Color32::from_rgb(200, 150, 100)),
-        "csv" => ("Cv", egui::Color32::from_rgb(100, 200, 100)),
-        "sql" => ("Sq", egui::Color32::from_rgb(200, 150, 50)),
+        "json" => ("Js", theme::rgb(200, 200, 100)),
+        "yaml" | "yml" => ("Ym", theme::rgb(200, 100, 100)),
+        "toml" => ("Tm", theme::rgb(150, 150, 200)),
+        "xml" => ("Xm", theme::rgb(200, 150, 100)),
+        "csv" => ("Cv", theme::rgb(100, 200, 100)),
+        "sql" => ("Sq", theme::rgb(200, 150, 50)),
         // Documents
-        "md" | "markdown" => ("Md", egui::Color32::from_rgb(100, 180, 230)),
-        "txt" => ("Tx", egui::Color32::from_gray(160)),
-        "pdf" => ("Pd", egui::Color32::from_rgb(230, 70, 70)),
-        "doc" | "docx" => ("Dc", egui::Color32::from_rgb(50, 120, 220)),
+        "md" | "markdown" => ("Md", theme::rgb(100, 180, 230)),
+        "txt" => ("Tx", theme::gray(160)),
+        "pdf" => ("Pd", theme::rgb(230, 70, 70)),
+        "doc" | "docx" => ("Dc", theme::rgb(50, 120, 220)),
         // Images
         "png" | "jpg" | "jpeg" | "gif" | "svg" | "webp" | "bmp" | "ico" => {
-            ("Im", egui::Color32::from_rgb(200, 120, 220))
+            ("Im", theme::rgb(200, 120, 220))
         }
         // Audio / Video
         "mp3" | "wav" | "flac" | "ogg" | "aac" => {
-            ("Au", egui::Color32::from_rgb(255, 150, 100))
+            ("Au", theme::rgb(255, 150, 100))
         }
         "mp4" | "mkv" | "avi" | "mov" | "webm" => {
-            ("Vi", egui::Color32::from_rgb(200, 100, 200))
+            ("Vi", theme::rgb(200, 100, 200))
         }
         // Archives
         "zip" | "tar" | "gz" | "bz2" | "xz" | "7z" | "rar" => {
-            ("Zp", egui::Color32::from_rgb(180, 150, 100))
+            ("Zp", theme::rgb(180, 150, 100))
         }
         // Binary / Executable
-        "exe" | "dll" | "so" | "dylib" => ("Bn", egui::Color32::from_rgb(200, 80, 80)),
+        "exe" | "dll" | "so" | "dylib" => ("Bn", theme::rgb(200, 80, 80)),
         // Git
         "gitignore" | "gitattributes" | "gitmodules" => {
-            ("Gt", egui::Color32::from_rgb(240, 80, 50))
+            ("Gt", theme::rgb(240, 80, 50))
         }
         // Docker
-        "dockerfile" => ("Dk", egui::Color32::from_rgb(50, 150, 220)),
+        "dockerfile" => ("Dk", theme::rgb(50, 150, 220)),
         // Log
-        "log" => ("Lg", egui::Color32::from_gray(130)),
+        "log" => ("Lg", theme::gray(130)),
         // Env
-        "env" => ("En", egui::Color32::from_rgb(255, 200, 50)),
+        "env" => ("En", theme::rgb(255, 200, 50)),
         // Default
-        _ => ("F", egui::Color32::from_gray(120)),
+        _ => ("F", theme::gray(120)),
     }
 }
 
-fn header_label(ui: &mut egui::Ui, text: &str, width: f32) {
-    ui.allocate_ui(egui::vec2(width, 16.0), |ui| {
-        ui.label(
-            egui::RichText::new(text)
-                .size(10.0)
-                .strong()
-                .color(egui::Color32::from_gray(100)),
-        );
-    });
+/// "Owner: alice · Group: staff" for the path column's tooltip
+fn owner_label(result: &SearchResult) -> Option<String> {
+    let owner = result.owner.as_deref()?;
+    Some(match &result.group {
+        Some(group) => format!("Owner: {} · Group: {}", owner, group),
+        None => format!("Owner: {}", owner),
+    })
+}
+
+/// Size to draw a thumbnail at in the Name column: fit into the row height,
+/// keeping its aspect ratio
+fn thumbnail_size(image: egui::Vec2) -> egui::Vec2 {
+    const MAX: f32 = 18.0;
+    let scale = MAX / image.x.max(image.y).max(1.0);
+    image * scale
 }
 
-fn header_label_right(ui: &mut egui::Ui, text: &str, width: f32) {
+fn git_badge_color(status: GitStatus) -> egui::Color32 {
+    match status {
+        GitStatus::Modified => theme::rgb(255, 190, 60),
+        GitStatus::Added | GitStatus::Renamed => theme::rgb(90, 210, 90),
+        GitStatus::Untracked => theme::gray(130),
+    }
+}
+
+/// Clickable column header. `sort` is `Some(ascending)` on the column the
+/// results are sorted by, which gets an arrow.
+fn header_label(ui: &mut egui::Ui, text: &str, width: f32, sort: Option<bool>) -> egui::Response {
+    ui.allocate_ui(egui::vec2(width, 16.0), |ui| header_text(ui, text, sort))
+        .inner
+}
+
+fn header_label_right(ui: &mut egui::Ui, text: &str, width: f32, sort: Option<bool>) -> egui::Response {
     ui.allocate_ui(egui::vec2(width, 16.0), |ui| {
         ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
-            ui.label(
-                egui::RichText::new(text)
-                    .size(10.0)
-                    .strong()
-                    .color(egui::Color32::from_gray(100)),
-            );
-        });
-    });
+            header_text(ui, text, sort)
+        })
+        .inner
+    })
+    .inner
+}
+
+fn header_text(ui: &mut egui::Ui, text: &str, sort: Option<bool>) -> egui::Response {
+    let (text, color) = match sort {
+        Some(true) => (format!("{} ▲", text), theme::gray(170)),
+        Some(false) => (format!("{} ▼", text), theme::gray(170)),
+        None => (text.to_string(), theme::gray(100)),
+    };
+    ui.add(
+        egui::Label::new(egui::RichText::new(text).size(10.0).strong().color(color))
+            .sense(egui::Sense::click()),
+    )
+    .on_hover_cursor(egui::CursorIcon::PointingHand)
 }
 
 struct ColumnWidths {
@@ -963,11 +3390,206 @@ fn compute_column_widths(total: f32) -> ColumnWidths {
     }
 }
 
+/// Ancestors of a path as (label, path) pairs, outermost first. Unless `full`
+/// is set, the home directory collapses into a single `~` segment.
+fn breadcrumb_segments(path: &std::path::Path, full: bool) -> Vec<(String, std::path::PathBuf)> {
+    let home = if full { None } else { dirs::home_dir() };
+    let mut segments = Vec::new();
+    let mut acc = std::path::PathBuf::new();
+
+    for component in path.components() {
+        acc.push(component);
+        if home.as_ref().is_some_and(|h| h == &acc) {
+            segments.clear();
+            segments.push(("~".to_string(), acc.clone()));
+            continue;
+        }
+        let label = match component {
+            std::path::Component::RootDir => std::path::MAIN_SEPARATOR.to_string(),
+            other => other.as_os_str().to_string_lossy().to_string(),
+        };
+        segments.push((label, acc.clone()));
+    }
+    segments
+}
+
+/// Clickable breadcrumbs for a path; clicking a folder segment opens it.
+/// Long paths keep the first two and last three segments around an ellipsis,
+/// unless `full` is set. Must be called inside a right-to-left layout
+/// (segments are added last-first).
+fn breadcrumbs(ui: &mut egui::Ui, path: &std::path::Path, is_dir: bool, full: bool) {
+    const HEAD: usize = 2;
+    const TAIL: usize = 3;
+
+    let segments = breadcrumb_segments(path, full);
+    let last = segments.len().saturating_sub(1);
+    let collapse = !full && segments.len() > HEAD + TAIL + 1;
+
+    for (i, (label, target)) in segments.iter().enumerate().rev() {
+        if collapse && i >= HEAD && i < segments.len() - TAIL {
+            if i == HEAD {
+                let hidden: Vec<&str> = segments[HEAD..segments.len() - TAIL]
+                    .iter()
+                    .map(|(l, _)| l.as_str())
+                    .collect();
+                ui.label(
+                    egui::RichText::new("…")
+                        .size(10.0)
+                        .color(theme::gray(70)),
+                )
+                .on_hover_text(hidden.join("/"));
+                breadcrumb_separator(ui);
+            }
+            continue;
+        }
+
+        // The file itself isn't a folder to jump to
+        if i == last && !is_dir {
+            ui.label(
+                egui::RichText::new(label)
+                    .size(10.0)
+                    .color(theme::gray(130)),
+            );
+        } else {
+            let resp = ui.add(
+                egui::Label::new(
+                    egui::RichText::new(label)
+                        .size(10.0)
+                        .color(theme::gray(90)),
+                )
+                .sense(egui::Sense::click()),
+            );
+            if resp.hovered() {
+                ui.ctx().set_cursor_icon(egui::CursorIcon::PointingHand);
+            }
+            if resp.on_hover_text(target.to_string_lossy()).clicked() {
+                let _ = open::that(target);
+            }
+        }
+
+        // Root "/" already reads as a separator
+        if i > 0 && !(i == 1 && segments[0].0 == std::path::MAIN_SEPARATOR.to_string()) {
+            breadcrumb_separator(ui);
+        }
+    }
+}
+
+fn breadcrumb_separator(ui: &mut egui::Ui) {
+    ui.label(
+        egui::RichText::new("›")
+            .size(10.0)
+            .color(theme::gray(50)),
+    );
+}
+
+/// Dimmed snippet text with the matched words drawn brighter
+fn snippet_layout(snippet: &str, highlights: &[std::ops::Range<usize>]) -> egui::text::LayoutJob {
+    let font = egui::FontId::proportional(11.0);
+    let plain = egui::TextFormat::simple(font.clone(), theme::gray(110));
+    let matched = egui::TextFormat::simple(font, theme::rgb(230, 200, 120));
+
+    let mut job = egui::text::LayoutJob::default();
+    let mut pos = 0;
+    for range in highlights {
+        // Ranges come from tantivy's tokenizer and sit on char boundaries
+        let (Some(before), Some(word)) = (snippet.get(pos..range.start), snippet.get(range.clone())) else {
+            continue;
+        };
+        job.append(before, 0.0, plain.clone());
+        job.append(word, 0.0, matched.clone());
+        pos = range.end;
+    }
+    job.append(snippet.get(pos..).unwrap_or(""), 0.0, plain);
+    job
+}
+
+/// Keep the last `max_len - 3` characters behind a `...`. Counts chars, not
+/// bytes, so non-ASCII paths are never cut inside a character.
 fn truncate_path(path: &str, max_len: usize) -> String {
-    if path.len() <= max_len {
-        path.to_string()
+    let len = path.chars().count();
+    if len <= max_len {
+        return path.to_string();
+    }
+    let keep = max_len.saturating_sub(3);
+    let start = path
+        .char_indices()
+        .nth(len - keep)
+        .map_or(path.len(), |(i, _)| i);
+    format!("...{}", &path[start..])
+}
+
+/// Full paths of the results matching `pattern`, in result order. The glob
+/// is matched against the whole path; `*` also crosses directory separators.
+fn paths_matching_glob(results: &[SearchResult], pattern: &str) -> Result<Vec<String>, globset::Error> {
+    if pattern.is_empty() {
+        return Ok(Vec::new());
+    }
+    let matcher = globset::Glob::new(pattern)?.compile_matcher();
+    Ok(results
+        .iter()
+        .filter(|r| matcher.is_match(&r.file_path))
+        .map(|r| r.file_path.to_string_lossy().to_string())
+        .collect())
+}
+
+/// Whether the index is still being filled, so results may be missing files.
+fn index_is_building(status: &IndexStatus) -> bool {
+    matches!(
+        status,
+        IndexStatus::Counting | IndexStatus::Starting | IndexStatus::Indexing | IndexStatus::Committing
+    )
+}
+
+/// Height of a result row that hasn't been drawn yet: the main line, plus
+/// one for media details and one for a snippet
+fn estimated_row_height(result: &SearchResult) -> f32 {
+    let extra_lines = [result.media_summary.is_some(), result.content_snippet.is_some()]
+        .into_iter()
+        .filter(|&line| line)
+        .count();
+    32.0 + 16.0 * extra_lines as f32
+}
+
+/// Whether the search box has the keyboard with its text cursor at the start
+/// and nothing selected
+fn search_cursor_at_start(ctx: &egui::Context) -> bool {
+    ctx.memory(|m| m.has_focus(search_box_id()))
+        && egui::text_edit::TextEditState::load(ctx, search_box_id())
+            .and_then(|state| state.cursor.char_range())
+            .is_none_or(|range| range.primary.index == 0 && range.secondary.index == 0)
+}
+
+fn search_box_id() -> egui::Id {
+    egui::Id::new("search_box")
+}
+
+/// Whether a query is long enough to be worth dispatching. Field-scoped
+/// filters (`ext:c`, `size>0`) are always allowed, however short.
+fn is_searchable(query: &str, min_len: usize) -> bool {
+    let trimmed = query.trim();
+    !trimmed.is_empty()
+        && (trimmed.chars().count() >= min_len
+            || trimmed.contains(':')
+            || trimmed.contains('>')
+            || trimmed.contains('<'))
+}
+
+/// Time left, to the second under a minute and coarser above it
+fn format_eta(secs: u64) -> String {
+    if secs >= 3600 {
+        format!("{}h {}m", secs / 3600, secs % 3600 / 60)
+    } else if secs >= 60 {
+        format!("{}m {}s", secs / 60, secs % 60)
     } else {
-        format!("...{}", &path[path.len() - (max_len - 3)..])
+        format!("{}s", secs)
+    }
+}
+
+/// Changes made by the last index pass, while it's being shown
+fn last_pass_stats(status: &IndexStatus) -> Option<&IndexStats> {
+    match status {
+        IndexStatus::Ready(stats) | IndexStatus::Watching(stats) => stats.as_ref(),
+        _ => None,
     }
 }
 
@@ -981,6 +3603,77 @@ fn format_count(n: u64) -> String {
     }
 }
 
+/// Open a result and record it for the open-history ranking boost.
+fn open_result(
+    config: &Config,
+    search_tx: &Sender<SearchCommand>,
+    path: &std::path::Path,
+    is_dir: bool,
+) {
+    opener::open(config, path, is_dir);
+    let _ = search_tx.send(SearchCommand::Opened(path.to_path_buf()));
+}
+
+/// Open several files one after another on a background thread, so a slow
+/// app launch doesn't freeze the window.
+fn open_many(config: &Config, search_tx: &Sender<SearchCommand>, targets: Vec<(PathBuf, bool)>) {
+    for (path, _) in &targets {
+        let _ = search_tx.send(SearchCommand::Opened(path.clone()));
+    }
+    let config = config.clone();
+    std::thread::spawn(move || {
+        for (path, is_dir) in targets {
+            opener::open(&config, &path, is_dir);
+        }
+    });
+}
+
+/// Ask for a folder with the platform's folder dialog. The dialog runs on a
+/// thread; the receiver gets `None` if it was cancelled or no dialog tool is
+/// available (the settings window also takes a typed path).
+fn pick_folder(ctx: &egui::Context) -> Receiver<Option<PathBuf>> {
+    let (tx, rx) = mpsc::channel();
+    let ctx = ctx.clone();
+    std::thread::spawn(move || {
+        #[cfg(target_os = "macos")]
+        let output = std::process::Command::new("osascript")
+            .arg("-e")
+            .arg(r#"POSIX path of (choose folder with prompt "Choose a folder to index")"#)
+            .output();
+
+        #[cfg(target_os = "windows")]
+        let output = std::process::Command::new("powershell")
+            .args([
+                "-NoProfile",
+                "-Command",
+                "Add-Type -AssemblyName System.Windows.Forms; \
+                 $d = New-Object System.Windows.Forms.FolderBrowserDialog; \
+                 if ($d.ShowDialog() -eq 'OK') { $d.SelectedPath }",
+            ])
+            .output();
+
+        #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+        let output = std::process::Command::new("zenity")
+            .args(["--file-selection", "--directory", "--title=Choose a folder to index"])
+            .output()
+            .or_else(|_| {
+                std::process::Command::new("kdialog")
+                    .args(["--getexistingdirectory", "."])
+                    .output()
+            });
+
+        let dir = output
+            .ok()
+            .filter(|out| out.status.success())
+            .map(|out| String::from_utf8_lossy(&out.stdout).trim().to_string())
+            .filter(|dir| !dir.is_empty())
+            .map(PathBuf::from);
+        let _ = tx.send(dir);
+        ctx.request_repaint();
+    });
+    rx
+}
+
 /// Open the system "Open With" chooser for a file.
 fn open_with_chooser(path: &std::path::Path) {
     let path = path.to_path_buf();
@@ -1026,6 +3719,228 @@ do shell script "open -a " & quoted form of appPath & " " & quoted form of "{}"
     });
 }
 
+/// Put files on the clipboard as files rather than text, so they can be
+/// pasted into a folder or a chat app.
+///
+/// On Linux the clipboard gets a `text/uri-list` through `wl-copy` (Wayland)
+/// or `xclip` (X11), whichever is installed. Most apps accept that, but some
+/// file managers only paste their own format (GNOME Files wants
+/// `x-special/gnome-copied-files`), so pasting there may do nothing.
+fn copy_files_to_clipboard(paths: Vec<PathBuf>) {
+    // Run in a thread so we don't block the GUI
+    std::thread::spawn(move || {
+        #[cfg(target_os = "macos")]
+        {
+            // NSPasteboard with file URLs, as Finder's Copy does; the paths go in
+            // as arguments of the script's run handler, so nothing needs quoting
+            let script = r#"ObjC.import("AppKit");
+function run(argv) {
+    const pasteboard = $.NSPasteboard.generalPasteboard;
+    pasteboard.clearContents;
+    pasteboard.writeObjects($(argv.map(path => $.NSURL.fileURLWithPath(path))));
+}"#;
+            let copied = std::process::Command::new("osascript")
+                .args(["-l", "JavaScript", "-e", script])
+                .args(&paths)
+                .status();
+            if !copied.is_ok_and(|s| s.success()) {
+                eprintln!("drozosearch: couldn't copy files to the clipboard");
+            }
+        }
+
+        #[cfg(target_os = "windows")]
+        {
+            // Windows PowerShell's Set-Clipboard puts a file drop list on the
+            // clipboard; the paths travel in an environment variable so none of
+            // them is parsed as PowerShell
+            let list: Vec<_> = paths.iter().map(|p| p.to_string_lossy()).collect();
+            let copied = std::process::Command::new("powershell")
+                .args([
+                    "-NoProfile",
+                    "-NonInteractive",
+                    "-Command",
+                    "Set-Clipboard -LiteralPath ($env:DROZO_CLIPBOARD_FILES -split \"`n\")",
+                ])
+                .env("DROZO_CLIPBOARD_FILES", list.join("\n"))
+                .status();
+            if !copied.is_ok_and(|s| s.success()) {
+                eprintln!("drozosearch: couldn't copy files to the clipboard");
+            }
+        }
+
+        #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+        {
+            use std::io::Write;
+            use std::process::{Command, Stdio};
+
+            let uris: String = paths.iter().map(|p| format!("{}\r\n", file_uri(p))).collect();
+            let wayland = std::env::var_os("WAYLAND_DISPLAY").is_some();
+            let mut commands = vec![
+                ("xclip", vec!["-selection", "clipboard", "-t", "text/uri-list", "-i"]),
+            ];
+            if wayland {
+                commands.insert(0, ("wl-copy", vec!["--type", "text/uri-list"]));
+            }
+            let copied = commands.into_iter().any(|(program, args)| {
+                let Ok(mut child) = Command::new(program).args(args).stdin(Stdio::piped()).spawn() else {
+                    return false;
+                };
+                // Both fork to keep serving the clipboard, so the wait is short
+                let written = child.stdin.take().is_some_and(|mut stdin| stdin.write_all(uris.as_bytes()).is_ok());
+                child.wait().is_ok_and(|s| s.success()) && written
+            });
+            if !copied {
+                eprintln!("drozosearch: couldn't copy files to the clipboard (install wl-clipboard or xclip)");
+            }
+        }
+    });
+}
+
+/// Menu label of [`reveal_in_file_manager`], named after the platform's file manager
+#[cfg(target_os = "macos")]
+const REVEAL_LABEL: &str = "Reveal in Finder";
+#[cfg(target_os = "windows")]
+const REVEAL_LABEL: &str = "Show in Explorer";
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+const REVEAL_LABEL: &str = "Show in file manager";
+
+/// Show a file in the Quick Look preview panel
+#[cfg(target_os = "macos")]
+fn quick_look(path: &std::path::Path) {
+    let path = path.to_path_buf();
+    // qlmanage runs until the preview is closed, so wait for it off the GUI thread
+    std::thread::spawn(move || {
+        let _ = std::process::Command::new("qlmanage")
+            .arg("-p")
+            .arg(&path)
+            // It logs its own diagnostics on every preview
+            .stdout(std::process::Stdio::null())
+            .stderr(std::process::Stdio::null())
+            .status();
+    });
+}
+
+/// Open the folder holding `path` in the file manager with `path` selected.
+fn reveal_in_file_manager(path: &std::path::Path) {
+    let path = path.to_path_buf();
+    // Run in a thread so we don't block the GUI
+    std::thread::spawn(move || {
+        #[cfg(target_os = "macos")]
+        {
+            let _ = std::process::Command::new("open").arg("-R").arg(&path).spawn();
+        }
+
+        #[cfg(target_os = "windows")]
+        {
+            use std::os::windows::process::CommandExt;
+            // Explorer parses its own command line: the path must be quoted after the comma
+            let _ = std::process::Command::new("explorer")
+                .raw_arg(format!("/select,\"{}\"", path.display()))
+                .spawn();
+        }
+
+        #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+        {
+            // The FileManager1 D-Bus interface (Nautilus, Dolphin, Nemo, Caja, ...),
+            // then file managers' own select flags, then just the folder
+            let shown = std::process::Command::new("dbus-send")
+                .args([
+                    "--session",
+                    "--print-reply",
+                    "--dest=org.freedesktop.FileManager1",
+                    "--type=method_call",
+                    "/org/freedesktop/FileManager1",
+                    "org.freedesktop.FileManager1.ShowItems",
+                ])
+                .arg(format!("array:string:{}", file_uri(&path)))
+                .arg("string:")
+                .output()
+                .is_ok_and(|out| out.status.success());
+            let shown = shown
+                || ["nautilus", "dolphin", "nemo"].iter().any(|manager| {
+                    std::process::Command::new(manager)
+                        .arg("--select")
+                        .arg(&path)
+                        .spawn()
+                        .is_ok()
+                });
+            if !shown {
+                if let Some(parent) = path.parent() {
+                    let _ = open::that(parent);
+                }
+            }
+        }
+    });
+}
+
+/// `file://` URI of an absolute path, percent-encoding all but unreserved characters
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+fn file_uri(path: &std::path::Path) -> String {
+    use std::os::unix::ffi::OsStrExt;
+    let mut uri = String::from("file://");
+    for &b in path.as_os_str().as_bytes() {
+        if b.is_ascii_alphanumeric() || b"/-_.~".contains(&b) {
+            uri.push(b as char);
+        } else {
+            uri.push_str(&format!("%{:02X}", b));
+        }
+    }
+    uri
+}
+
+/// Open a directory in a new file manager window, even if one is already
+/// showing it (plain `open::that` tends to just focus the existing window).
+fn open_in_new_window(dir: &std::path::Path) {
+    let dir = dir.to_path_buf();
+    std::thread::spawn(move || {
+        #[cfg(target_os = "macos")]
+        {
+            let script = format!(
+                r#"tell application "Finder"
+    make new Finder window to (POSIX file "{}")
+    activate
+end tell"#,
+                dir.to_string_lossy().replace('"', "\\\"")
+            );
+            let _ = std::process::Command::new("osascript")
+                .arg("-e")
+                .arg(&script)
+                .spawn();
+        }
+
+        #[cfg(target_os = "windows")]
+        {
+            // explorer.exe always opens a fresh window
+            let _ = std::process::Command::new("explorer").arg(&dir).spawn();
+        }
+
+        #[cfg(target_os = "linux")]
+        {
+            // Nautilus supports --new-window; other file managers open a window anyway
+            let status = std::process::Command::new("nautilus")
+                .arg("--new-window")
+                .arg(&dir)
+                .spawn();
+            if status.is_err() {
+                let _ = open::that(&dir);
+            }
+        }
+    });
+}
+
+/// Start a terminal in `dir` on a background thread. Without a usable
+/// terminal the folder opens in the file manager instead.
+fn open_terminal(config: &Config, dir: &std::path::Path) {
+    let config = config.clone();
+    let dir = dir.to_path_buf();
+    std::thread::spawn(move || {
+        if !opener::open_terminal(&config, &dir) {
+            eprintln!("drozosearch: no terminal found, opening the folder instead");
+            let _ = open::that(&dir);
+        }
+    });
+}
+
 #[cfg(target_os = "macos")]
 fn macos_hide_app() {
     use objc2_app_kit::NSApplication;
@@ -1047,3 +3962,88 @@ fn macos_show_app() {
         app.activateIgnoringOtherApps(true);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use eframe::Storage;
+
+    use super::*;
+
+    #[test]
+    fn short_paths_are_kept_whole() {
+        assert_eq!(truncate_path("/home/me/a.txt", 14), "/home/me/a.txt");
+        assert_eq!(truncate_path("/home/me/a.txt", 80), "/home/me/a.txt");
+        assert_eq!(truncate_path("", 5), "");
+    }
+
+    #[test]
+    fn long_paths_keep_their_tail() {
+        let truncated = truncate_path("/home/me/projects/report.pdf", 14);
+        assert_eq!(truncated, ".../report.pdf");
+        assert_eq!(truncated.chars().count(), 14);
+    }
+
+    #[test]
+    fn truncation_counts_characters_not_bytes() {
+        // Every character here is several bytes long, so a byte-based cut
+        // would land inside one and panic.
+        let path = "/dokumenty/żółć/日本語のファイル.txt";
+        for max_len in 3..path.chars().count() {
+            let truncated = truncate_path(path, max_len);
+            assert!(truncated.starts_with("..."));
+            assert_eq!(truncated.chars().count(), max_len);
+            assert!(path.ends_with(&truncated[3..]));
+        }
+        assert_eq!(truncate_path("/żółć/ąę.txt", 9), "...ąę.txt");
+        assert_eq!(truncate_path("🦀🦀🦀🦀🦀", 4), "...🦀");
+    }
+
+    #[test]
+    fn tiny_limits_leave_only_the_ellipsis() {
+        assert_eq!(truncate_path("/a/long/path", 2), "...");
+        assert_eq!(truncate_path("/a/long/path", 0), "...");
+    }
+
+    /// In-memory stand-in for eframe's on-disk storage
+    #[derive(Default)]
+    struct MemoryStorage(HashMap<String, String>);
+
+    impl Storage for MemoryStorage {
+        fn get_string(&self, key: &str) -> Option<String> {
+            self.0.get(key).cloned()
+        }
+
+        fn set_string(&mut self, key: &str, value: String) {
+            self.0.insert(key.to_string(), value);
+        }
+
+        fn flush(&mut self) {}
+    }
+
+    #[test]
+    fn view_state_round_trips_through_storage() {
+        let mut storage = MemoryStorage::default();
+        let view = ViewState {
+            sort_column: SortColumn::Modified,
+            sort_ascending: false,
+            settings_open: true,
+        };
+        eframe::set_value(&mut storage, ViewState::KEY, &view);
+        let restored: ViewState = eframe::get_value(&storage, ViewState::KEY).unwrap();
+        assert_eq!(restored.sort_column, SortColumn::Modified);
+        assert!(!restored.sort_ascending);
+        assert!(restored.settings_open);
+    }
+
+    #[test]
+    fn missing_view_state_fields_fall_back_to_defaults() {
+        let mut storage = MemoryStorage::default();
+        assert!(eframe::get_value::<ViewState>(&storage, ViewState::KEY).is_none());
+
+        storage.set_string(ViewState::KEY, "(sort_column: size)".to_string());
+        let restored: ViewState = eframe::get_value(&storage, ViewState::KEY).unwrap();
+        assert_eq!(restored.sort_column, SortColumn::Size);
+        assert!(restored.sort_ascending);
+        assert!(!restored.settings_open);
+    }
+}
@@ -0,0 +1,187 @@
+use std::io::Write;
+
+use clap::Parser;
+use tantivy::Index;
+
+use drozosearch::config::Config;
+use drozosearch::index::reader::SearchEngine;
+use drozosearch::index::schema;
+use drozosearch::types::SearchResult;
+
+/// Printed under the options in `--help`
+const AFTER_HELP: &str = "\
+Without --query the search window opens. With it, the existing index is
+searched and results are printed, one per line as path<TAB>size<TAB>modified
+(bytes, unix seconds), or as a JSON array with --json. The app must have
+built the index at least once.
+
+Exit status: 0 if something was found, 1 if nothing was, 2 on errors.";
+
+/// Results printed when `--limit` isn't given
+const DEFAULT_LIMIT: usize = 50;
+
+#[derive(Parser)]
+#[command(name = "drozosearch", version, about = "Desktop file search", after_help = AFTER_HELP)]
+struct Args {
+    /// Search the index from the terminal instead of opening the window
+    #[arg(short, long, value_name = "TEXT")]
+    query: Option<String>,
+    /// How many results to print at most
+    #[arg(
+        short = 'n',
+        long,
+        value_name = "N",
+        requires = "query",
+        value_parser = positive_limit,
+        // So `-1` gets the message below rather than "unknown argument"
+        allow_negative_numbers = true
+    )]
+    limit: Option<usize>,
+    /// Print the results as a JSON array
+    #[arg(long, requires = "query")]
+    json: bool,
+}
+
+/// A headless search requested on the command line
+pub struct CliSearch {
+    pub query: String,
+    pub limit: usize,
+    pub json: bool,
+}
+
+/// Parse the arguments (with the program name). `Ok(None)` means no search
+/// was asked for and the GUI should start. `--help` and `--version` come
+/// back as errors too; [`clap::Error::exit`] prints them and exits with the
+/// right status.
+pub fn parse_args(args: impl IntoIterator<Item = String>) -> Result<Option<CliSearch>, clap::Error> {
+    // Older macOS passes a process serial number when launching the bundle
+    let args = args.into_iter().filter(|arg| !arg.starts_with("-psn_"));
+    let args = Args::try_parse_from(args)?;
+    Ok(args.query.map(|query| CliSearch {
+        query,
+        limit: args.limit.unwrap_or(DEFAULT_LIMIT),
+        json: args.json,
+    }))
+}
+
+fn positive_limit(value: &str) -> Result<usize, String> {
+    value
+        .parse()
+        .ok()
+        .filter(|&n: &usize| n > 0)
+        .ok_or_else(|| "must be a positive number".to_string())
+}
+
+/// Run the search and print the results; returns the process exit code.
+pub fn run(search: &CliSearch) -> i32 {
+    match search_index(search) {
+        Ok(results) => {
+            if let Err(e) = print_results(&results, search.json) {
+                // A closed pipe (`| head`) is not worth a message
+                if e.kind() != std::io::ErrorKind::BrokenPipe {
+                    eprintln!("drozosearch: {}", e);
+                    return 2;
+                }
+            }
+            if results.is_empty() {
+                1
+            } else {
+                0
+            }
+        }
+        Err(e) => {
+            eprintln!("drozosearch: {}", e);
+            2
+        }
+    }
+}
+
+fn search_index(search: &CliSearch) -> Result<Vec<SearchResult>, String> {
+    let config = Config::load();
+    // Opened as-is: unlike the app, never rebuild (and so wipe) an index here
+    let index = Index::open_in_dir(&config.index_path).map_err(|e| {
+        format!(
+            "can't open the index at {} ({}); start the app once to build it",
+            config.index_path.display(),
+            e
+        )
+    })?;
+    schema::register_tokenizers(&index);
+    if index.schema() != schema::build_schema() {
+        return Err("the index was built by another version; start the app to rebuild it".into());
+    }
+    let engine = SearchEngine::new(index, &config).map_err(|e| e.to_string())?;
+    Ok(engine.search(&search.query, search.limit))
+}
+
+fn print_results(results: &[SearchResult], json: bool) -> std::io::Result<()> {
+    let stdout = std::io::stdout();
+    let mut out = std::io::BufWriter::new(stdout.lock());
+    if json {
+        serde_json::to_writer_pretty(&mut out, results)?;
+        writeln!(out)?;
+    } else {
+        for r in results {
+            writeln!(out, "{}\t{}\t{}", r.file_path.display(), r.file_size, r.modified)?;
+        }
+    }
+    out.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::error::ErrorKind;
+
+    fn parse(args: &[&str]) -> Result<Option<CliSearch>, clap::Error> {
+        parse_args(std::iter::once("drozosearch").chain(args.iter().copied()).map(String::from))
+    }
+
+    #[test]
+    fn no_arguments_start_the_gui() {
+        assert!(parse(&[]).unwrap().is_none());
+        assert!(parse(&["-psn_0_1234567"]).unwrap().is_none());
+    }
+
+    #[test]
+    fn query_with_options() {
+        let search = parse(&["--query", "report ext:pdf"]).unwrap().unwrap();
+        assert_eq!(search.query, "report ext:pdf");
+        assert_eq!(search.limit, DEFAULT_LIMIT);
+        assert!(!search.json);
+
+        let search = parse(&["-q", "todo", "-n", "5", "--json"]).unwrap().unwrap();
+        assert_eq!((search.query.as_str(), search.limit, search.json), ("todo", 5, true));
+        let search = parse(&["--limit=3", "--query=a b"]).unwrap().unwrap();
+        assert_eq!((search.query.as_str(), search.limit), ("a b", 3));
+    }
+
+    #[test]
+    fn bad_limits_are_reported() {
+        for limit in ["0", "-1", "ten", ""] {
+            let error = parse(&["--query", "x", "--limit", limit]).err().unwrap();
+            assert_eq!(error.kind(), ErrorKind::ValueValidation, "{}", limit);
+            assert!(error.to_string().contains("positive number"), "{}", error);
+        }
+        let error = parse(&["--query", "x", "--limit"]).err().unwrap();
+        assert_eq!(error.kind(), ErrorKind::InvalidValue);
+    }
+
+    #[test]
+    fn options_need_a_query() {
+        let error = parse(&["--json"]).err().unwrap();
+        assert_eq!(error.kind(), ErrorKind::MissingRequiredArgument);
+        let error = parse(&["--limit", "5"]).err().unwrap();
+        assert_eq!(error.kind(), ErrorKind::MissingRequiredArgument);
+    }
+
+    #[test]
+    fn unknown_arguments_and_help() {
+        assert_eq!(parse(&["--frobnicate"]).err().unwrap().kind(), ErrorKind::UnknownArgument);
+        assert_eq!(parse(&["stray"]).err().unwrap().kind(), ErrorKind::UnknownArgument);
+        let help = parse(&["--help"]).err().unwrap();
+        assert_eq!(help.kind(), ErrorKind::DisplayHelp);
+        assert!(help.to_string().contains("Exit status"));
+        assert_eq!(parse(&["-h"]).err().unwrap().kind(), ErrorKind::DisplayHelp);
+    }
+}
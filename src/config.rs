@@ -1,25 +1,650 @@
-use std::path::PathBuf;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 
+use serde::{Deserialize, Serialize};
+
+use crate::indexer::walker;
+use crate::types::{FileCategory, MatchType};
+
+/// Where directories go in the result list
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DirectoryOrder {
+    /// Ranked together with files by relevance
+    Mixed,
+    DirsFirst,
+    FilesFirst,
+    /// Directories are excluded from results entirely
+    HideDirs,
+}
+
+impl DirectoryOrder {
+    pub const ALL: [DirectoryOrder; 4] = [
+        DirectoryOrder::Mixed,
+        DirectoryOrder::DirsFirst,
+        DirectoryOrder::FilesFirst,
+        DirectoryOrder::HideDirs,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            DirectoryOrder::Mixed => "Mixed by relevance",
+            DirectoryOrder::DirsFirst => "Directories first",
+            DirectoryOrder::FilesFirst => "Files first",
+            DirectoryOrder::HideDirs => "Hide directories",
+        }
+    }
+}
+
+/// Color scheme of the window
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Theme {
+    Dark,
+    Light,
+    /// Follow the OS light/dark preference where it can be detected (dark otherwise)
+    System,
+}
+
+impl Theme {
+    pub const ALL: [Theme; 3] = [Theme::Dark, Theme::Light, Theme::System];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Theme::Dark => "Dark",
+            Theme::Light => "Light",
+            Theme::System => "Follow system",
+        }
+    }
+}
+
+/// What closing the main window does
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CloseAction {
+    /// Keep running in the system tray
+    HideToTray,
+    Quit,
+}
+
+impl CloseAction {
+    pub const ALL: [CloseAction; 2] = [CloseAction::HideToTray, CloseAction::Quit];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            CloseAction::HideToTray => "Hide to tray",
+            CloseAction::Quit => "Quit",
+        }
+    }
+}
+
+/// Which kinds of match the result list shows; all of them by default
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MatchTypeFilter {
+    pub name: bool,
+    pub content: bool,
+    pub metadata: bool,
+}
+
+impl Default for MatchTypeFilter {
+    fn default() -> Self {
+        MatchTypeFilter {
+            name: true,
+            content: true,
+            metadata: true,
+        }
+    }
+}
+
+impl MatchTypeFilter {
+    pub fn shows(&self, match_type: &MatchType) -> bool {
+        match match_type {
+            MatchType::FileName => self.name,
+            MatchType::Content => self.content,
+            MatchType::Metadata => self.metadata,
+        }
+    }
+}
+
+/// A query kept under a name in the sidebar
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SavedSearch {
+    pub name: String,
+    pub query: String,
+}
+
+/// How much each signal counts when results are ranked. Every score is a
+/// weighted sum of these signals, so raising a weight makes its signal matter
+/// more and 0 ignores it.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RankWeights {
+    /// Full-text relevance (BM25) of the match
+    pub relevance: f32,
+    /// File name equal to the query, with or without its extension
+    pub exact_name: f32,
+    /// File name starting with the query
+    pub name_prefix: f32,
+    /// File name containing the query
+    pub name_contains: f32,
+    /// Recently modified files
+    pub recency: f32,
+    /// Files close to the top of their folder tree
+    pub shallow_path: f32,
+    /// Files over directories
+    pub file_over_dir: f32,
+    /// How often query terms occur in a content match
+    pub term_frequency: f32,
+    /// Having opened a file from the results before
+    pub open_history: f32,
+    /// Penalty for each typo a fuzzy file name match needed
+    pub fuzzy_edit: f32,
+}
+
+impl Default for RankWeights {
+    fn default() -> Self {
+        RankWeights {
+            relevance: 2.0,
+            exact_name: 5.0,
+            name_prefix: 2.0,
+            name_contains: 1.5,
+            recency: 0.8,
+            shallow_path: 0.4,
+            file_over_dir: 0.1,
+            term_frequency: 0.6,
+            open_history: 0.5,
+            fuzzy_edit: 1.0,
+        }
+    }
+}
+
+impl RankWeights {
+    /// Each weight with a label, for the settings sliders
+    pub fn labeled_mut(&mut self) -> [(&'static str, &mut f32); 10] {
+        [
+            ("Text relevance", &mut self.relevance),
+            ("Exact name", &mut self.exact_name),
+            ("Name starts with query", &mut self.name_prefix),
+            ("Name contains query", &mut self.name_contains),
+            ("Recently modified", &mut self.recency),
+            ("Shallow path", &mut self.shallow_path),
+            ("Files over directories", &mut self.file_over_dir),
+            ("Term frequency", &mut self.term_frequency),
+            ("Opened before", &mut self.open_history),
+            ("Typo penalty", &mut self.fuzzy_edit),
+        ]
+    }
+}
+
+#[derive(Clone)]
 pub struct Config {
     pub root_dirs: Vec<PathBuf>,
+    /// How many levels below a root the indexer goes; None for no limit
+    pub max_depth: Option<usize>,
+    /// Roots with their own depth limit instead of `max_depth` (the value is
+    /// None for no limit). Written as `{ path, max_depth }` entries of
+    /// `root_dirs` in the config file.
+    pub root_depths: HashMap<PathBuf, Option<usize>>,
     pub index_path: PathBuf,
     pub max_file_size: u64,
+    /// Index the first `max_file_size` bytes of bigger text files instead
+    /// of leaving their content out
+    pub truncate_large_files: bool,
+    /// Hash the bytes of files up to `max_file_size` while indexing, for
+    /// "Find duplicates". Costs a full read of files that aren't text.
+    pub detect_duplicates: bool,
     pub commit_interval: u64,
+    /// Also commit this many seconds after the last commit, however few
+    /// files went in since; 0 commits by `commit_interval` count only
+    pub commit_interval_secs: u64,
+    /// Memory the index writer buffers new documents in before writing them
+    /// out (at least 15 MB); lower it on machines with little RAM
+    pub writer_heap_bytes: usize,
+    /// Require a double-click to open a result; a single click only selects it
+    pub double_click_to_open: bool,
+    /// Stop drawing and animating while the window is hidden in the tray
+    pub idle_when_hidden: bool,
+    /// Don't search until the trimmed query has at least this many characters
+    pub min_query_len: usize,
+    /// When a search finds hardly anything, retry with typo-tolerant matching
+    /// on file names (`recieve` finds `receive.txt`)
+    pub fuzzy_matching: bool,
+    /// Match the query against file names and extensions only, skipping
+    /// contents and metadata; much faster on large indexes
+    pub names_only: bool,
+    /// Weights of the ranking signals; `[rank_weights]` in the config file
+    pub rank_weights: RankWeights,
+    pub close_action: CloseAction,
+    /// Ask what closing the window should do until the user remembers a choice
+    pub ask_on_close: bool,
+    /// Where the opened files behind `rank_weights.open_history` are kept
+    pub open_history_path: PathBuf,
+    /// Where roots seen as mount points are recorded, so an unplugged drive
+    /// isn't taken for an emptied folder (see [`volumes::is_available`])
+    ///
+    /// [`volumes::is_available`]: crate::indexer::volumes::is_available
+    pub mounted_roots_path: PathBuf,
+    /// Show git status badges (M/A/??) for results inside git repositories
+    pub show_git_status: bool,
+    /// Show absolute, untruncated paths instead of `~`-shortened ones
+    pub show_full_paths: bool,
+    pub directory_order: DirectoryOrder,
+    /// Match types shown in the results; `[match_types]` in the config file
+    pub match_types: MatchTypeFilter,
+    /// Named queries listed in the sidebar, in the order they were saved
+    pub saved_searches: Vec<SavedSearch>,
+    pub theme: Theme,
+    /// Command used to open files of a category instead of the OS default,
+    /// e.g. `code --goto {path}`. `{path}` is replaced with the file path.
+    pub open_commands: HashMap<FileCategory, String>,
+    /// Command for "Open terminal here", e.g. `alacritty --working-directory
+    /// {path}`. `{path}` is replaced with the folder, which is also the
+    /// command's working directory. Empty uses the platform's terminal.
+    pub terminal_command: String,
+    /// Read image dimensions and EXIF (camera, capture time, GPS), and
+    /// audio/video duration and codec (via `ffprobe`, if installed) while
+    /// indexing. Costs extra I/O per media file.
+    pub index_media_metadata: bool,
+    /// Only index files with these extensions (plus all directories), e.g.
+    /// `["rs", "md"]`. Empty indexes every file.
+    pub index_extensions: Vec<String>,
+    /// Also index the content of files with these extensions, on top of
+    /// the built-in text types, e.g. `["ncl", "jsonnet"]`
+    pub extra_text_extensions: Vec<String>,
+    /// Never index the content of files with these extensions (or names,
+    /// for extensionless ones like `makefile`), even built-in text types;
+    /// they're still found by name
+    pub exclude_text_extensions: Vec<String>,
+    /// Recognize the text in images with `tesseract`, if it's installed, and
+    /// index it as their content. Slow: seconds per image.
+    pub ocr_images: bool,
+    /// Images bigger than this many bytes aren't OCR'd
+    pub ocr_max_bytes: u64,
+    /// Images with more pixels than this (width × height) aren't OCR'd
+    pub ocr_max_pixels: u64,
+    /// Directory names never entered while indexing, wherever they are
+    /// (`node_modules`, `.git`, ...). Takes effect on the next index pass.
+    pub skip_dirs: Vec<String>,
+    /// Follow symlinks while indexing. A file reachable several ways is
+    /// indexed once, under the first path it's found by.
+    pub follow_symlinks: bool,
+    /// Incremental passes skip the files of folders whose modification time
+    /// hasn't changed. Much faster on big trees, but a file edited in place
+    /// while the app isn't watching is only picked up by a rebuild.
+    pub quick_rescan: bool,
+    /// Threads reading file content while indexing
+    pub threads: usize,
+    /// Serve `GET /search?q=...` on `127.0.0.1` at this port for other tools;
+    /// 0 turns it off. There is no authentication, so never expose the port.
+    /// Read at startup; other settings changed in the app reach the API's
+    /// results from the next request on.
+    pub api_port: u16,
+    /// System-wide shortcut that brings the window up, e.g. `Alt+Space` or
+    /// `Ctrl+Shift+F`. Empty disables it.
+    pub global_hotkey: String,
 }
 
 impl Default for Config {
     fn default() -> Self {
-        let home = dirs::home_dir().unwrap_or_else(|| PathBuf::from("/"));
+        Config::defaults_for_home(dirs::home_dir())
+    }
+}
+
+impl Config {
+    /// The defaults for a user whose home directory is `home`.
+    ///
+    /// Without a home directory there is no sensible default root. Falling
+    /// back to `/` would index the whole filesystem, so start with no roots
+    /// and let the app ask for a folder instead.
+    fn defaults_for_home(home: Option<PathBuf>) -> Self {
         let data_dir = dirs::data_dir()
-            .unwrap_or_else(|| home.join(".local/share"))
-            .join("drozosearch")
-            .join("index");
+            .or_else(|| home.as_ref().map(|h| h.join(".local/share")))
+            .unwrap_or_else(std::env::temp_dir)
+            .join("drozosearch");
 
         Config {
-            root_dirs: vec![home],
-            index_path: data_dir,
+            root_dirs: home.into_iter().collect(),
+            max_depth: Some(20),
+            root_depths: HashMap::new(),
+            index_path: data_dir.join("index"),
             max_file_size: 10 * 1024 * 1024, // 10 MB
+            truncate_large_files: true,
+            detect_duplicates: false,
             commit_interval: 10_000,
+            commit_interval_secs: 5,
+            writer_heap_bytes: 50_000_000,
+            double_click_to_open: false,
+            idle_when_hidden: true,
+            min_query_len: 2,
+            fuzzy_matching: true,
+            names_only: false,
+            rank_weights: RankWeights::default(),
+            open_history_path: data_dir.join("open_history.tsv"),
+            mounted_roots_path: data_dir.join("mounted_roots.txt"),
+            close_action: CloseAction::HideToTray,
+            ask_on_close: true,
+            show_git_status: false,
+            show_full_paths: false,
+            directory_order: DirectoryOrder::Mixed,
+            match_types: MatchTypeFilter::default(),
+            saved_searches: Vec::new(),
+            theme: Theme::Dark,
+            open_commands: HashMap::new(),
+            terminal_command: String::new(),
+            index_media_metadata: false,
+            index_extensions: Vec::new(),
+            extra_text_extensions: Vec::new(),
+            exclude_text_extensions: Vec::new(),
+            ocr_images: false,
+            ocr_max_bytes: 20 * 1024 * 1024, // 20 MB
+            ocr_max_pixels: 40_000_000,
+            skip_dirs: walker::DEFAULT_SKIP_DIRS.iter().map(|d| d.to_string()).collect(),
+            follow_symlinks: false,
+            quick_rescan: false,
+            threads: std::thread::available_parallelism().map_or(4, |n| n.get()),
+            global_hotkey: "Alt+Space".into(),
+            api_port: 0,
         }
     }
+
+    /// `~/.config/drozosearch/config.toml` (or the platform equivalent)
+    pub fn file_path() -> Option<PathBuf> {
+        dirs::config_dir().map(|d| d.join("drozosearch").join("config.toml"))
+    }
+
+    /// Depth limit for the walk of `root`
+    pub fn max_depth_for(&self, root: &Path) -> Option<usize> {
+        self.root_depths.get(root).copied().unwrap_or(self.max_depth)
+    }
+
+    /// Depth limit for a walk starting at `path`, somewhere under one of the
+    /// roots, and whether `path` itself is within the limit
+    pub fn depth_below(&self, path: &Path) -> (Option<usize>, bool) {
+        let Some(root) = self
+            .root_dirs
+            .iter()
+            .filter(|root| path.starts_with(root))
+            .max_by_key(|root| root.components().count())
+        else {
+            return (self.max_depth, true);
+        };
+        let depth = path.components().count() - root.components().count();
+        match self.max_depth_for(root) {
+            Some(limit) => (Some(limit.saturating_sub(depth)), depth <= limit),
+            None => (None, true),
+        }
+    }
+
+    /// Read the config file, falling back to defaults for missing keys or an
+    /// unreadable file. On first run the defaults are written out so there is
+    /// something to edit.
+    pub fn load() -> Self {
+        let Some(path) = Self::file_path() else {
+            return Config::default();
+        };
+        match std::fs::read_to_string(&path) {
+            Ok(text) => match toml::from_str::<ConfigFile>(&text) {
+                Ok(file) => file.into(),
+                Err(e) => {
+                    eprintln!("drozosearch: ignoring invalid {}: {}", path.display(), e);
+                    Config::default()
+                }
+            },
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                let config = Config::default();
+                if let Err(e) = config.save_to(&path) {
+                    eprintln!("drozosearch: could not write {}: {}", path.display(), e);
+                }
+                config
+            }
+            Err(e) => {
+                eprintln!("drozosearch: could not read {}: {}", path.display(), e);
+                Config::default()
+            }
+        }
+    }
+
+    /// Write the config back to the config file.
+    pub fn save(&self) -> std::io::Result<()> {
+        match Self::file_path() {
+            Some(path) => self.save_to(&path),
+            None => Err(std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                "no config directory",
+            )),
+        }
+    }
+
+    fn save_to(&self, path: &Path) -> std::io::Result<()> {
+        let text = toml::to_string_pretty(&ConfigFile::from(self))
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, text)
+    }
+}
+
+/// A `root_dirs` entry: a plain path, or a table giving the root its own
+/// depth limit
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+enum RootEntry {
+    Path(PathBuf),
+    Dir(RootDir),
+}
+
+#[derive(Serialize, Deserialize)]
+struct RootDir {
+    path: PathBuf,
+    /// Replaces the global `max_depth` for this root; 0 for no limit
+    max_depth: usize,
+}
+
+/// `max_depth` in the config file, where 0 means no limit
+fn depth_to_file(depth: Option<usize>) -> usize {
+    depth.unwrap_or(0)
+}
+
+fn depth_from_file(depth: usize) -> Option<usize> {
+    (depth > 0).then_some(depth)
+}
+
+/// On-disk form of [`Config`]. Every key is optional and unknown keys are
+/// ignored, so old and hand-edited files keep loading.
+#[derive(Serialize, Deserialize)]
+#[serde(default)]
+struct ConfigFile {
+    root_dirs: Vec<RootEntry>,
+    /// 0 for no limit
+    max_depth: usize,
+    index_path: PathBuf,
+    max_file_size: u64,
+    truncate_large_files: bool,
+    detect_duplicates: bool,
+    commit_interval: u64,
+    commit_interval_secs: u64,
+    writer_heap_bytes: usize,
+    index_extensions: Vec<String>,
+    extra_text_extensions: Vec<String>,
+    exclude_text_extensions: Vec<String>,
+    ocr_images: bool,
+    ocr_max_bytes: u64,
+    ocr_max_pixels: u64,
+    index_media_metadata: bool,
+    skip_dirs: Vec<String>,
+    follow_symlinks: bool,
+    quick_rescan: bool,
+    threads: usize,
+    global_hotkey: String,
+    api_port: u16,
+    min_query_len: usize,
+    fuzzy_matching: bool,
+    names_only: bool,
+    double_click_to_open: bool,
+    idle_when_hidden: bool,
+    rank_weights: RankWeights,
+    open_history_path: PathBuf,
+    mounted_roots_path: PathBuf,
+    close_action: CloseAction,
+    ask_on_close: bool,
+    show_git_status: bool,
+    show_full_paths: bool,
+    directory_order: DirectoryOrder,
+    match_types: MatchTypeFilter,
+    saved_searches: Vec<SavedSearch>,
+    theme: Theme,
+    open_commands: HashMap<FileCategory, String>,
+    terminal_command: String,
+}
+
+impl Default for ConfigFile {
+    fn default() -> Self {
+        ConfigFile::from(&Config::default())
+    }
+}
+
+impl From<&Config> for ConfigFile {
+    fn from(c: &Config) -> Self {
+        ConfigFile {
+            root_dirs: c
+                .root_dirs
+                .iter()
+                .map(|root| match c.root_depths.get(root) {
+                    Some(&depth) => RootEntry::Dir(RootDir {
+                        path: root.clone(),
+                        max_depth: depth_to_file(depth),
+                    }),
+                    None => RootEntry::Path(root.clone()),
+                })
+                .collect(),
+            max_depth: depth_to_file(c.max_depth),
+            index_path: c.index_path.clone(),
+            max_file_size: c.max_file_size,
+            truncate_large_files: c.truncate_large_files,
+            detect_duplicates: c.detect_duplicates,
+            commit_interval: c.commit_interval,
+            commit_interval_secs: c.commit_interval_secs,
+            writer_heap_bytes: c.writer_heap_bytes,
+            index_extensions: c.index_extensions.clone(),
+            extra_text_extensions: c.extra_text_extensions.clone(),
+            exclude_text_extensions: c.exclude_text_extensions.clone(),
+            ocr_images: c.ocr_images,
+            ocr_max_bytes: c.ocr_max_bytes,
+            ocr_max_pixels: c.ocr_max_pixels,
+            index_media_metadata: c.index_media_metadata,
+            skip_dirs: c.skip_dirs.clone(),
+            follow_symlinks: c.follow_symlinks,
+            quick_rescan: c.quick_rescan,
+            threads: c.threads,
+            global_hotkey: c.global_hotkey.clone(),
+            api_port: c.api_port,
+            min_query_len: c.min_query_len,
+            fuzzy_matching: c.fuzzy_matching,
+            names_only: c.names_only,
+            double_click_to_open: c.double_click_to_open,
+            idle_when_hidden: c.idle_when_hidden,
+            rank_weights: c.rank_weights,
+            open_history_path: c.open_history_path.clone(),
+            mounted_roots_path: c.mounted_roots_path.clone(),
+            close_action: c.close_action,
+            ask_on_close: c.ask_on_close,
+            show_git_status: c.show_git_status,
+            show_full_paths: c.show_full_paths,
+            directory_order: c.directory_order,
+            match_types: c.match_types,
+            saved_searches: c.saved_searches.clone(),
+            theme: c.theme,
+            open_commands: c.open_commands.clone(),
+            terminal_command: c.terminal_command.clone(),
+        }
+    }
+}
+
+impl From<ConfigFile> for Config {
+    fn from(f: ConfigFile) -> Self {
+        let mut root_dirs = Vec::new();
+        let mut root_depths = HashMap::new();
+        for entry in f.root_dirs {
+            match entry {
+                RootEntry::Path(path) => root_dirs.push(path),
+                RootEntry::Dir(dir) => {
+                    root_depths.insert(dir.path.clone(), depth_from_file(dir.max_depth));
+                    root_dirs.push(dir.path);
+                }
+            }
+        }
+        Config {
+            root_dirs,
+            max_depth: depth_from_file(f.max_depth),
+            root_depths,
+            index_path: f.index_path,
+            max_file_size: f.max_file_size,
+            truncate_large_files: f.truncate_large_files,
+            detect_duplicates: f.detect_duplicates,
+            commit_interval: f.commit_interval,
+            commit_interval_secs: f.commit_interval_secs,
+            writer_heap_bytes: f.writer_heap_bytes,
+            index_extensions: f.index_extensions,
+            extra_text_extensions: f.extra_text_extensions,
+            exclude_text_extensions: f.exclude_text_extensions,
+            ocr_images: f.ocr_images,
+            ocr_max_bytes: f.ocr_max_bytes,
+            ocr_max_pixels: f.ocr_max_pixels,
+            index_media_metadata: f.index_media_metadata,
+            skip_dirs: f.skip_dirs,
+            follow_symlinks: f.follow_symlinks,
+            quick_rescan: f.quick_rescan,
+            threads: f.threads,
+            global_hotkey: f.global_hotkey,
+            api_port: f.api_port,
+            min_query_len: f.min_query_len,
+            fuzzy_matching: f.fuzzy_matching,
+            names_only: f.names_only,
+            double_click_to_open: f.double_click_to_open,
+            idle_when_hidden: f.idle_when_hidden,
+            rank_weights: f.rank_weights,
+            open_history_path: f.open_history_path,
+            mounted_roots_path: f.mounted_roots_path,
+            close_action: f.close_action,
+            ask_on_close: f.ask_on_close,
+            show_git_status: f.show_git_status,
+            show_full_paths: f.show_full_paths,
+            directory_order: f.directory_order,
+            match_types: f.match_types,
+            saved_searches: f.saved_searches,
+            theme: f.theme,
+            open_commands: f.open_commands,
+            terminal_command: f.terminal_command,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn missing_home_dir_gives_no_roots() {
+        let config = Config::defaults_for_home(None);
+        assert!(config.root_dirs.is_empty());
+        assert!(!config.root_dirs.contains(&PathBuf::from("/")));
+        // Still somewhere to keep the index
+        assert!(config.index_path.ends_with("drozosearch/index"));
+    }
+
+    #[test]
+    fn home_dir_is_the_default_root() {
+        let config = Config::defaults_for_home(Some(PathBuf::from("/home/me")));
+        assert_eq!(config.root_dirs, [PathBuf::from("/home/me")]);
+    }
+
+    #[test]
+    fn empty_roots_survive_saving() {
+        let config = Config::defaults_for_home(None);
+        let text = toml::to_string(&ConfigFile::from(&config)).unwrap();
+        let loaded = Config::from(toml::from_str::<ConfigFile>(&text).unwrap());
+        assert!(loaded.root_dirs.is_empty());
+    }
 }
@@ -0,0 +1,158 @@
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver};
+
+use eframe::egui;
+
+use drozosearch::types::SearchResult;
+
+/// Outcome of an export: where it was written, or why it failed.
+/// `None` when the save dialog was cancelled.
+pub type ExportOutcome = Option<Result<(PathBuf, usize), String>>;
+
+/// Ask where to save, then write `results` there as CSV. Both happen on a
+/// background thread; the receiver gets the outcome.
+pub fn export_csv(ctx: &egui::Context, results: Vec<SearchResult>) -> Receiver<ExportOutcome> {
+    let (tx, rx) = mpsc::channel();
+    let ctx = ctx.clone();
+    std::thread::spawn(move || {
+        let outcome = pick_save_path("drozosearch-results.csv").map(|path| {
+            write_csv(&path, &results)
+                .map(|()| (path.clone(), results.len()))
+                .map_err(|e| format!("Couldn't write {}: {}", path.display(), e))
+        });
+        let _ = tx.send(outcome);
+        ctx.request_repaint();
+    });
+    rx
+}
+
+/// One row per result: name, full path, size in bytes, modified time (ISO
+/// 8601, UTC) and match type, under a header row. Lines end in CRLF, as
+/// RFC 4180 (and Excel) expect.
+pub fn write_csv(path: &Path, results: &[SearchResult]) -> io::Result<()> {
+    let mut out = csv::WriterBuilder::new()
+        .terminator(csv::Terminator::CRLF)
+        .from_path(path)?;
+    out.write_record(["name", "path", "size", "modified", "match_type"])?;
+    for r in results {
+        let modified = chrono::DateTime::from_timestamp(r.modified, 0)
+            .map(|t| t.to_rfc3339_opts(chrono::SecondsFormat::Secs, true))
+            .unwrap_or_default();
+        out.write_record([
+            r.file_name.as_str(),
+            &r.file_path.to_string_lossy(),
+            &r.file_size.to_string(),
+            &modified,
+            &r.match_type.to_string(),
+        ])?;
+    }
+    out.flush()
+}
+
+/// Ask for a file to save to with the platform's save dialog. Blocks until
+/// it's answered; `None` if it was cancelled or no dialog tool is available.
+fn pick_save_path(default_name: &str) -> Option<PathBuf> {
+    #[cfg(target_os = "macos")]
+    let output = std::process::Command::new("osascript")
+        .arg("-e")
+        .arg(format!(
+            r#"POSIX path of (choose file name with prompt "Export results" default name "{}")"#,
+            default_name
+        ))
+        .output();
+
+    #[cfg(target_os = "windows")]
+    let output = std::process::Command::new("powershell")
+        .args([
+            "-NoProfile",
+            "-Command",
+            &format!(
+                "Add-Type -AssemblyName System.Windows.Forms; \
+                 $d = New-Object System.Windows.Forms.SaveFileDialog; \
+                 $d.Filter = 'CSV files (*.csv)|*.csv'; $d.FileName = '{}'; \
+                 if ($d.ShowDialog() -eq 'OK') {{ $d.FileName }}",
+                default_name
+            ),
+        ])
+        .output();
+
+    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    let output = std::process::Command::new("zenity")
+        .args([
+            "--file-selection",
+            "--save",
+            "--confirm-overwrite",
+            "--title=Export results",
+            &format!("--filename={}", default_name),
+        ])
+        .output()
+        .or_else(|_| {
+            std::process::Command::new("kdialog")
+                .args(["--getsavefilename", default_name])
+                .output()
+        });
+
+    output
+        .ok()
+        .filter(|out| out.status.success())
+        .map(|out| String::from_utf8_lossy(&out.stdout).trim().to_string())
+        .filter(|path| !path.is_empty())
+        .map(PathBuf::from)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use drozosearch::types::MatchType;
+
+    fn result(path: &str, match_type: MatchType) -> SearchResult {
+        let path = PathBuf::from(path);
+        SearchResult {
+            file_name: path.file_name().unwrap().to_string_lossy().into_owned(),
+            file_path: path,
+            match_type,
+            file_size: 2048,
+            modified: 1_700_000_000,
+            score: 1.0,
+            content_snippet: None,
+            snippet_highlights: Vec::new(),
+            is_dir: false,
+            media_summary: None,
+            mime_type: None,
+            content_truncated: false,
+            content_hash: None,
+            owner: None,
+            group: None,
+        }
+    }
+
+    #[test]
+    fn rows_round_trip_through_a_csv_reader() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("out.csv");
+        let results = [
+            result("/home/me/plain.txt", MatchType::FileName),
+            result("/home/me/a, \"quoted\"\nname.md", MatchType::Content),
+        ];
+        write_csv(&path, &results).unwrap();
+
+        let bytes = std::fs::read(&path).unwrap();
+        assert!(bytes.starts_with(b"name,path,size,modified,match_type\r\n"));
+        let mut reader = csv::Reader::from_path(&path).unwrap();
+        let rows: Vec<csv::StringRecord> = reader.records().map(Result::unwrap).collect();
+        assert_eq!(rows.len(), 2);
+        assert_eq!(
+            &rows[0],
+            vec!["plain.txt", "/home/me/plain.txt", "2048", "2023-11-14T22:13:20Z", "Name"]
+        );
+        assert_eq!(&rows[1][0], "a, \"quoted\"\nname.md");
+        assert_eq!(&rows[1][4], "Content");
+    }
+
+    #[test]
+    fn unwritable_destination_is_an_error() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(write_csv(&dir.path().join("missing/out.csv"), &[]).is_err());
+    }
+}
@@ -0,0 +1,219 @@
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// How long a repo's statuses are reused before they're read again
+const REPO_CACHE_TTL: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GitStatus {
+    Modified,
+    Added,
+    Renamed,
+    Untracked,
+}
+
+impl GitStatus {
+    pub fn badge(&self) -> &'static str {
+        match self {
+            GitStatus::Modified => "M",
+            GitStatus::Added => "A",
+            GitStatus::Renamed => "R",
+            GitStatus::Untracked => "??",
+        }
+    }
+}
+
+/// Lazily looks up git status for result files on a background thread.
+///
+/// The UI asks for the status of rows it is drawing; unknown paths are queued
+/// and answered asynchronously. Answers are cached by path and the file's
+/// modified time, so an edited file is looked up again. Clean files and files
+/// outside any repo cache as `None`.
+pub struct GitStatusCache {
+    request_tx: Sender<PathBuf>,
+    response_rx: Receiver<(PathBuf, Option<GitStatus>)>,
+    statuses: HashMap<PathBuf, (i64, Option<GitStatus>)>,
+    pending: HashSet<PathBuf>,
+}
+
+impl GitStatusCache {
+    pub fn new(ctx: eframe::egui::Context) -> Self {
+        let (request_tx, request_rx) = mpsc::channel::<PathBuf>();
+        let (response_tx, response_rx) = mpsc::channel();
+        thread::spawn(move || status_thread(request_rx, response_tx, ctx));
+        GitStatusCache {
+            request_tx,
+            response_rx,
+            statuses: HashMap::new(),
+            pending: HashSet::new(),
+        }
+    }
+
+    /// Status of `path` if known; queues a lookup otherwise.
+    pub fn status(&mut self, path: &Path, modified: i64) -> Option<GitStatus> {
+        while let Ok((p, status)) = self.response_rx.try_recv() {
+            self.pending.remove(&p);
+            let mtime = self.statuses.get(&p).map_or(modified, |(m, _)| *m);
+            self.statuses.insert(p, (mtime, status));
+        }
+
+        match self.statuses.get(path) {
+            Some((m, status)) if *m == modified => *status,
+            _ => {
+                if self.pending.insert(path.to_path_buf()) {
+                    // Remember which mtime this lookup is for
+                    self.statuses.insert(path.to_path_buf(), (modified, None));
+                    let _ = self.request_tx.send(path.to_path_buf());
+                }
+                None
+            }
+        }
+    }
+}
+
+fn status_thread(
+    rx: Receiver<PathBuf>,
+    tx: Sender<(PathBuf, Option<GitStatus>)>,
+    ctx: eframe::egui::Context,
+) {
+    // repo root → (when fetched, status of every dirty file in it)
+    let mut repos: HashMap<PathBuf, (Instant, HashMap<PathBuf, GitStatus>)> = HashMap::new();
+
+    for path in rx {
+        let status = find_repo_root(&path).and_then(|root| {
+            let fresh = repos
+                .get(&root)
+                .is_some_and(|(fetched, _)| fetched.elapsed() < REPO_CACHE_TTL);
+            if !fresh {
+                repos.insert(root.clone(), (Instant::now(), repo_status(&root)));
+            }
+            repos.get(&root)?.1.get(&path).copied()
+        });
+        if tx.send((path, status)).is_err() {
+            return;
+        }
+        ctx.request_repaint();
+    }
+}
+
+/// Walk up from a file to the nearest directory containing `.git`.
+fn find_repo_root(path: &Path) -> Option<PathBuf> {
+    path.ancestors()
+        .skip(1)
+        .find(|dir| dir.join(".git").exists())
+        .map(Path::to_path_buf)
+}
+
+/// Status of every dirty file in the repo at `root`, read with libgit2, so
+/// no `git` binary is needed. Ignored files aren't listed.
+fn repo_status(root: &Path) -> HashMap<PathBuf, GitStatus> {
+    let mut statuses = HashMap::new();
+    let Ok(repo) = git2::Repository::open(root) else {
+        return statuses;
+    };
+    let mut options = git2::StatusOptions::new();
+    options
+        .include_untracked(true)
+        .recurse_untracked_dirs(true)
+        .renames_head_to_index(true);
+    let Ok(entries) = repo.statuses(Some(&mut options)) else {
+        return statuses;
+    };
+
+    for entry in entries.iter() {
+        let flags = entry.status();
+        let status = if flags.is_wt_new() {
+            GitStatus::Untracked
+        } else if flags.is_index_renamed() {
+            GitStatus::Renamed
+        } else if flags.is_index_new() {
+            GitStatus::Added
+        } else if flags.is_index_modified() || flags.is_wt_modified() {
+            GitStatus::Modified
+        } else {
+            continue; // deleted/conflicted: not a file we can show
+        };
+        // The new name of a renamed file; `entry.path()` is the old one
+        let delta = entry.head_to_index().or_else(|| entry.index_to_workdir());
+        if let Some(path) = delta.as_ref().and_then(|d| d.new_file().path()) {
+            statuses.insert(root.join(path), status);
+        }
+    }
+    statuses
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+
+    fn commit_all(repo: &git2::Repository, message: &str) {
+        let mut index = repo.index().unwrap();
+        index.add_all(["*"], git2::IndexAddOption::DEFAULT, None).unwrap();
+        index.write().unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let signature = git2::Signature::now("Test", "test@example.com").unwrap();
+        let parent = repo.head().ok().map(|head| head.peel_to_commit().unwrap());
+        let parents: Vec<&git2::Commit> = parent.iter().collect();
+        repo.commit(Some("HEAD"), &signature, &signature, message, &tree, &parents)
+            .unwrap();
+    }
+
+    #[test]
+    fn statuses_of_dirty_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        let repo = git2::Repository::init(root).unwrap();
+        fs::write(root.join(".gitignore"), "*.log\n").unwrap();
+        fs::write(root.join("clean.txt"), "clean").unwrap();
+        fs::write(root.join("edited.txt"), "before").unwrap();
+        fs::write(root.join("old_name.txt"), "a file that gets renamed").unwrap();
+        fs::write(root.join("removed.txt"), "gone soon").unwrap();
+        commit_all(&repo, "first");
+
+        fs::write(root.join("edited.txt"), "after").unwrap();
+        fs::remove_file(root.join("removed.txt")).unwrap();
+        fs::rename(root.join("old_name.txt"), root.join("new_name.txt")).unwrap();
+        fs::write(root.join("staged.txt"), "new and added").unwrap();
+        fs::create_dir(root.join("sub")).unwrap();
+        fs::write(root.join("sub/untracked.txt"), "new").unwrap();
+        fs::write(root.join("debug.log"), "ignored").unwrap();
+        let mut index = repo.index().unwrap();
+        index.remove_path(Path::new("old_name.txt")).unwrap();
+        index.add_path(Path::new("new_name.txt")).unwrap();
+        index.add_path(Path::new("staged.txt")).unwrap();
+        index.write().unwrap();
+
+        let statuses = repo_status(root);
+        let status = |name: &str| statuses.get(&root.join(name)).copied();
+        assert_eq!(status("edited.txt"), Some(GitStatus::Modified));
+        assert_eq!(status("new_name.txt"), Some(GitStatus::Renamed));
+        assert_eq!(status("staged.txt"), Some(GitStatus::Added));
+        assert_eq!(status("sub/untracked.txt"), Some(GitStatus::Untracked));
+        assert_eq!(status("clean.txt"), None);
+        assert_eq!(status("debug.log"), None);
+        assert_eq!(status("removed.txt"), None);
+        assert_eq!(statuses.len(), 4);
+    }
+
+    #[test]
+    fn outside_a_repo_nothing_is_dirty() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("file.txt"), "x").unwrap();
+        assert!(repo_status(dir.path()).is_empty());
+        assert_eq!(find_repo_root(&dir.path().join("file.txt")), None);
+    }
+
+    #[test]
+    fn repo_root_is_the_nearest_folder_with_git() {
+        let dir = tempfile::tempdir().unwrap();
+        git2::Repository::init(dir.path()).unwrap();
+        fs::create_dir_all(dir.path().join("a/b")).unwrap();
+        let file = dir.path().join("a/b/c.txt");
+        assert_eq!(find_repo_root(&file), Some(dir.path().to_path_buf()));
+    }
+}
@@ -0,0 +1,44 @@
+use std::path::PathBuf;
+
+/// How many recent searches are kept
+pub const HISTORY_LIMIT: usize = 50;
+
+/// `search_history.txt` next to the session in the data directory, one
+/// query per line, most recent first
+pub fn file_path() -> Option<PathBuf> {
+    dirs::data_dir().map(|d| d.join("drozosearch").join("search_history.txt"))
+}
+
+/// The saved searches, or none if there isn't a readable file
+pub fn load() -> Vec<String> {
+    file_path()
+        .and_then(|path| std::fs::read_to_string(path).ok())
+        .map(|text| {
+            text.lines()
+                .filter(|line| !line.trim().is_empty())
+                .take(HISTORY_LIMIT)
+                .map(String::from)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+pub fn save(history: &[String]) -> std::io::Result<()> {
+    let path = file_path().ok_or_else(|| {
+        std::io::Error::new(std::io::ErrorKind::NotFound, "no data directory")
+    })?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let mut text = history.join("\n");
+    text.push('\n');
+    std::fs::write(path, text)
+}
+
+/// Move `query` to the front of `history`, dropping an older copy and
+/// whatever falls past [`HISTORY_LIMIT`]
+pub fn remember(history: &mut Vec<String>, query: &str) {
+    history.retain(|q| q != query);
+    history.insert(0, query.to_string());
+    history.truncate(HISTORY_LIMIT);
+}
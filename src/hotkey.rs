@@ -0,0 +1,99 @@
+use std::str::FromStr;
+
+use global_hotkey::hotkey::{Code, HotKey, Modifiers};
+use global_hotkey::{GlobalHotKeyEvent, GlobalHotKeyManager, HotKeyState};
+
+/// Parse `Modifier+...+Key`, like `Alt+Space` or `Ctrl+Shift+F`,
+/// case-insensitively. At least one modifier is required so the shortcut
+/// doesn't swallow ordinary typing.
+pub fn parse(spec: &str) -> Result<HotKey, String> {
+    let parts: Vec<&str> = spec.split('+').map(str::trim).collect();
+    let (key, modifiers) = parts.split_last().ok_or("empty shortcut")?;
+    let mut mods = Modifiers::empty();
+    for modifier in modifiers {
+        mods |= match modifier.to_lowercase().as_str() {
+            "ctrl" | "control" => Modifiers::CONTROL,
+            "alt" | "option" => Modifiers::ALT,
+            "shift" => Modifiers::SHIFT,
+            // Super/Windows/Command key
+            "super" | "win" | "cmd" | "command" | "meta" => Modifiers::SUPER,
+            other => return Err(format!("unknown modifier \"{}\"", other)),
+        };
+    }
+    if mods.is_empty() {
+        return Err(format!("\"{}\" needs a modifier like Alt or Ctrl", spec));
+    }
+
+    let lower = key.to_lowercase();
+    let code = match lower.as_str() {
+        "space" => Code::Space,
+        "enter" | "return" => Code::Enter,
+        "tab" => Code::Tab,
+        "esc" | "escape" => Code::Escape,
+        k if k.len() == 1 && k.chars().all(|c| c.is_ascii_alphabetic()) => {
+            Code::from_str(&format!("Key{}", k.to_uppercase())).map_err(|e| e.to_string())?
+        }
+        k if k.len() == 1 && k.chars().all(|c| c.is_ascii_digit()) => {
+            Code::from_str(&format!("Digit{}", k)).map_err(|e| e.to_string())?
+        }
+        k if k.starts_with('f') && k[1..].parse::<u8>().is_ok_and(|n| (1..=24).contains(&n)) => {
+            Code::from_str(&k.to_uppercase()).map_err(|e| e.to_string())?
+        }
+        _ => return Err(format!("unknown key \"{}\"", key)),
+    };
+    Ok(HotKey::new(Some(mods), code))
+}
+
+/// A system-wide shortcut, registered until dropped. Presses arrive on
+/// [`GlobalHotKeyEvent::receiver`], even while the window is hidden or
+/// another app has focus.
+pub struct Registration {
+    _manager: GlobalHotKeyManager,
+    id: u32,
+}
+
+impl Registration {
+    /// Whether `event` is this shortcut being pressed (not released)
+    pub fn is_pressed(&self, event: &GlobalHotKeyEvent) -> bool {
+        event.id() == self.id && event.state() == HotKeyState::Pressed
+    }
+}
+
+/// Register `spec` as a system-wide shortcut.
+pub fn register(spec: &str) -> Result<Registration, String> {
+    let hotkey = parse(spec)?;
+    let manager = GlobalHotKeyManager::new().map_err(|e| e.to_string())?;
+    manager.register(hotkey).map_err(|e| e.to_string())?;
+    Ok(Registration {
+        _manager: manager,
+        id: hotkey.id(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_modifiers_and_keys_case_insensitively() {
+        assert_eq!(parse("Alt+Space"), Ok(HotKey::new(Some(Modifiers::ALT), Code::Space)));
+        assert_eq!(
+            parse("ctrl + SHIFT + f"),
+            Ok(HotKey::new(Some(Modifiers::CONTROL | Modifiers::SHIFT), Code::KeyF))
+        );
+        assert_eq!(parse("Cmd+Return"), Ok(HotKey::new(Some(Modifiers::SUPER), Code::Enter)));
+        assert_eq!(parse("Win+7"), Ok(HotKey::new(Some(Modifiers::SUPER), Code::Digit7)));
+        assert_eq!(parse("Option+f12"), Ok(HotKey::new(Some(Modifiers::ALT), Code::F12)));
+        assert_eq!(parse("Ctrl+Esc"), Ok(HotKey::new(Some(Modifiers::CONTROL), Code::Escape)));
+    }
+
+    #[test]
+    fn rejects_unknown_or_unmodified_shortcuts() {
+        assert!(parse("").is_err());
+        assert!(parse("Space").unwrap_err().contains("needs a modifier"));
+        assert!(parse("Hyper+Space").unwrap_err().contains("unknown modifier"));
+        assert!(parse("Alt+F25").unwrap_err().contains("unknown key"));
+        assert!(parse("Alt+PageDown").unwrap_err().contains("unknown key"));
+        assert!(parse("Alt+").unwrap_err().contains("unknown key"));
+    }
+}
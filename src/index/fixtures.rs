@@ -0,0 +1,194 @@
+use std::path::PathBuf;
+
+use tantivy::Index;
+
+use super::schema;
+use super::writer::IndexWriter;
+use crate::indexer::content::TextContent;
+use crate::indexer::media::MediaInfo;
+use crate::indexer::metadata::FileMetadata;
+use crate::indexer::mime;
+
+const WORDS: &[&str] = &[
+    "main", "config", "report", "server", "client", "utils", "parser", "index", "search",
+    "render", "thread", "notes", "invoice", "budget", "draft", "readme", "backup", "test",
+    "schema", "query", "widget", "layout", "payment", "user", "service", "handler", "cache",
+];
+
+const EXTENSIONS: &[&str] = &["rs", "md", "txt", "toml", "json", "py", "js", "pdf", "png", ""];
+
+/// Deterministic xorshift so fixtures are identical across runs.
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    fn pick<'a>(&mut self, items: &[&'a str]) -> &'a str {
+        items[(self.next() % items.len() as u64) as usize]
+    }
+}
+
+/// An empty in-memory index with the real schema and tokenizers
+pub(crate) fn empty_index() -> Index {
+    let index = Index::create_in_ram(schema::build_schema());
+    schema::register_tokenizers(&index);
+    index
+}
+
+/// Build an in-memory index of `count` synthetic files spread over a
+/// project-like tree, using the real schema and `IndexWriter`.
+///
+/// Shared by benches and tests so they exercise the same corpus. Roughly one
+/// in ten entries is a directory; text files get a few sentences of content.
+pub fn synthetic_index(count: usize) -> tantivy::Result<Index> {
+    let index = empty_index();
+    let mut writer = IndexWriter::new(&index, 50_000_000, 10_000, None)?;
+    let mut rng = Rng(0x9E37_79B9_7F4A_7C15);
+    let now = chrono::Utc::now().timestamp();
+
+    for i in 0..count {
+        let depth = 1 + (rng.next() % 6) as usize;
+        let mut path = PathBuf::from("/home/bench");
+        for _ in 0..depth {
+            path.push(rng.pick(WORDS));
+        }
+
+        let is_dir = i % 10 == 0;
+        let ext = rng.pick(EXTENSIONS);
+        let name = if is_dir || ext.is_empty() {
+            format!("{}_{}", rng.pick(WORDS), i)
+        } else {
+            format!("{}_{}.{}", rng.pick(WORDS), i, ext)
+        };
+        path.push(name);
+
+        let meta = FileMetadata {
+            size: if is_dir { 0 } else { rng.next() % (8 * 1024 * 1024) },
+            modified: now - (rng.next() % (3 * 365 * 86_400)) as i64,
+            created: now - (3 * 365 * 86_400),
+            permissions: "rw-r--r--".to_string(),
+            owner: String::new(),
+            group: String::new(),
+            is_dir,
+            content_hash: None,
+        };
+
+        let content = (!is_dir && matches!(ext, "rs" | "md" | "txt" | "py" | "js")).then(|| {
+            let text = (0..40)
+                .map(|_| rng.pick(WORDS))
+                .collect::<Vec<_>>()
+                .join(" ");
+            TextContent { text, truncated: false }
+        });
+
+        let mime_type = mime::detect(&path, is_dir, true);
+        writer.add_file(&path, &meta, mime_type, content.as_ref(), None, None)?;
+        writer.maybe_commit()?;
+    }
+
+    writer.commit()?;
+    Ok(index)
+}
+
+/// A file for `index_of_files`, for tests that look at more than names and
+/// content. Start from [`file`] and set what the test is about.
+pub(crate) struct TestFile {
+    /// Ends in `/` for a directory
+    pub path: String,
+    /// Indexed as the file's text, unless empty
+    pub text: String,
+    pub size: u64,
+    /// Also used as the creation time
+    pub modified: i64,
+    pub media: Option<MediaInfo>,
+}
+
+/// A file as [`add_files`] adds it: 1 KiB (or empty, for a directory),
+/// modified a day ago, with no text
+pub(crate) fn file(path: &str) -> TestFile {
+    TestFile {
+        path: path.to_string(),
+        text: String::new(),
+        size: if path.ends_with('/') { 0 } else { 1024 },
+        modified: chrono::Utc::now().timestamp() - 86_400,
+        media: None,
+    }
+}
+
+/// Build an in-memory index of the given files: `(path, content)`, where a
+/// path ending in `/` is a directory and `content` is indexed as the file's
+/// text. Every entry gets the same size and times, so only what a test sets
+/// tells them apart.
+#[cfg(test)]
+pub fn index_of(files: &[(&str, &str)]) -> tantivy::Result<Index> {
+    let files: Vec<TestFile> = files
+        .iter()
+        .map(|(path, text)| TestFile { text: text.to_string(), ..file(path) })
+        .collect();
+    index_of_files(&files)
+}
+
+/// Build an in-memory index of the given files, like [`index_of`] but with
+/// their sizes, times and media details
+#[cfg(test)]
+pub(crate) fn index_of_files(files: &[TestFile]) -> tantivy::Result<Index> {
+    let index = empty_index();
+    let mut writer = IndexWriter::new(&index, 50_000_000, 10_000, None)?;
+    for file in files {
+        add_file(&mut writer, file)?;
+    }
+    writer.commit()?;
+    Ok(index)
+}
+
+/// Add `(path, content)` files to an index without committing, where a
+/// path ending in `/` is a directory and `content` is indexed as the file's
+/// text. Tests build whole indexes of them with `index_of`.
+pub fn add_files(writer: &mut IndexWriter, files: &[(&str, &str)]) -> tantivy::Result<()> {
+    for (path, text) in files {
+        add_file(writer, &TestFile { text: text.to_string(), ..file(path) })?;
+    }
+    Ok(())
+}
+
+fn add_file(writer: &mut IndexWriter, file: &TestFile) -> tantivy::Result<()> {
+    let is_dir = file.path.ends_with('/');
+    let path = PathBuf::from(file.path.trim_end_matches('/'));
+    let meta = FileMetadata {
+        size: file.size,
+        modified: file.modified,
+        created: file.modified,
+        permissions: "rw-r--r--".to_string(),
+        owner: String::new(),
+        group: String::new(),
+        is_dir,
+        content_hash: None,
+    };
+    let content = (!file.text.is_empty()).then(|| TextContent {
+        text: file.text.clone(),
+        truncated: false,
+    });
+    let mime_type = mime::detect(&path, is_dir, content.is_some());
+    writer.add_file(&path, &meta, mime_type, content.as_ref(), None, file.media.as_ref())
+}
+
+/// A search engine over `index` that ranks depth below `roots`. Its open
+/// history lives in the returned directory, gone once that's dropped.
+#[cfg(test)]
+pub fn engine(
+    index: Index,
+    roots: &[impl AsRef<std::path::Path>],
+) -> (super::reader::SearchEngine, tempfile::TempDir) {
+    let history = tempfile::tempdir().unwrap();
+    let config = crate::config::Config {
+        root_dirs: roots.iter().map(|root| root.as_ref().to_path_buf()).collect(),
+        open_history_path: history.path().join("open_history.tsv"),
+        ..Default::default()
+    };
+    (super::reader::SearchEngine::new(index, &config).unwrap(), history)
+}
@@ -0,0 +1,174 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Most paths remembered; the weakest entry is dropped beyond this
+const MAX_ENTRIES: usize = 500;
+
+/// Files the user opened from search results: path → (times opened, last opened).
+///
+/// Persisted as a small tab-separated file (`count\tlast_opened\tpath` per
+/// line) so the ranking boost survives restarts. Backslashes, tabs and line
+/// breaks in paths are escaped (`\\`, `\t`, `\n`, `\r`) so every entry stays on
+/// one line. Unreadable lines are skipped.
+#[derive(Default)]
+pub struct OpenHistory {
+    file: Option<PathBuf>,
+    entries: HashMap<String, (u32, i64)>,
+}
+
+impl OpenHistory {
+    /// Load the history from `file`; a missing file is an empty history.
+    pub fn load(file: &Path) -> Self {
+        let entries = fs::read_to_string(file)
+            .unwrap_or_default()
+            .lines()
+            .filter_map(|line| {
+                let mut parts = line.splitn(3, '\t');
+                let count = parts.next()?.parse().ok()?;
+                let last = parts.next()?.parse().ok()?;
+                Some((unescape(parts.next()?), (count, last)))
+            })
+            .collect();
+        OpenHistory {
+            file: Some(file.to_path_buf()),
+            entries,
+        }
+    }
+
+    /// Read the table again from its file, for changes saved by another
+    /// [`OpenHistory`] over the same file
+    pub fn reload(&mut self) {
+        if let Some(file) = self.file.take() {
+            *self = OpenHistory::load(&file);
+        }
+    }
+
+    /// Record that `path` was opened at `now` and save the table.
+    pub fn record(&mut self, path: &str, now: i64) {
+        let entry = self.entries.entry(path.to_string()).or_insert((0, now));
+        entry.0 = entry.0.saturating_add(1);
+        entry.1 = now;
+
+        if self.entries.len() > MAX_ENTRIES {
+            let weakest = self
+                .entries
+                .iter()
+                .map(|(p, &(count, last))| (p, boost(count, last, now)))
+                .min_by(|a, b| a.1.total_cmp(&b.1))
+                .map(|(p, _)| p.clone());
+            if let Some(p) = weakest {
+                self.entries.remove(&p);
+            }
+        }
+        self.save();
+    }
+
+    /// How strongly to favor `path`, from 0 (never opened) towards 1
+    /// (opened often and recently).
+    pub fn boost(&self, path: &str, now: i64) -> f32 {
+        self.entries
+            .get(path)
+            .map_or(0.0, |&(count, last)| boost(count, last, now))
+    }
+
+    fn save(&self) {
+        let Some(file) = &self.file else {
+            return;
+        };
+        let text: String = self
+            .entries
+            .iter()
+            .map(|(path, (count, last))| format!("{}\t{}\t{}\n", count, last, escape(path)))
+            .collect();
+        if let Some(dir) = file.parent() {
+            let _ = fs::create_dir_all(dir);
+        }
+        if let Err(e) = fs::write(file, text) {
+            eprintln!("drozosearch: failed to save open history ({})", e);
+        }
+    }
+}
+
+/// Frequency saturates (1 open → 0.25, 3 → 0.5, 9 → 0.75) and fades with
+/// time since the last open (a month → half strength).
+fn boost(count: u32, last_opened: i64, now: i64) -> f32 {
+    let count = count as f32;
+    let frequency = count / (count + 3.0);
+    let days = (now - last_opened).max(0) as f32 / 86_400.0;
+    frequency / (1.0 + days / 30.0)
+}
+
+fn escape(path: &str) -> String {
+    let mut out = String::with_capacity(path.len());
+    for c in path.chars() {
+        match c {
+            '\\' => out.push_str("\\\\"),
+            '\t' => out.push_str("\\t"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+/// Undo [`escape`]; a backslash before anything else is kept as it is
+fn unescape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('\\') => out.push('\\'),
+            Some('t') => out.push('\t'),
+            Some('n') => out.push('\n'),
+            Some('r') => out.push('\r'),
+            Some(other) => {
+                out.push('\\');
+                out.push(other);
+            }
+            None => out.push('\\'),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn paths_with_tabs_and_line_breaks_survive_saving() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("open_history.tsv");
+        let odd = "/home/me/two\nlines\tand tab\\n.txt";
+        let windows = r"C:\Users\me\notes.txt";
+
+        let mut history = OpenHistory::load(&file);
+        history.record(odd, 1_000);
+        history.record(odd, 2_000);
+        history.record(windows, 2_000);
+
+        let text = fs::read_to_string(&file).unwrap();
+        assert_eq!(text.lines().count(), 2);
+        let loaded = OpenHistory::load(&file);
+        assert_eq!(loaded.entries.get(odd), Some(&(2, 2_000)));
+        assert_eq!(loaded.entries.get(windows), Some(&(1, 2_000)));
+    }
+
+    #[test]
+    fn boost_grows_with_opens_and_fades_with_time() {
+        let now = 1_000_000;
+        let mut history = OpenHistory::default();
+        assert_eq!(history.boost("/a", now), 0.0);
+        history.record("/a", now);
+        let once = history.boost("/a", now);
+        history.record("/a", now);
+        assert!(history.boost("/a", now) > once);
+        assert!(history.boost("/a", now + 60 * 86_400) < history.boost("/a", now));
+    }
+}
@@ -0,0 +1,208 @@
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use fs4::FileExt;
+use tantivy::collector::TopDocs;
+use tantivy::query::AllQuery;
+use tantivy::{Index, TantivyError};
+
+use super::schema;
+
+/// Open the index at `path`, creating it if it doesn't exist yet.
+///
+/// An index left behind by a crash can open fine and then fail on every
+/// search (corrupt segment, truncated doc store). To avoid a permanently
+/// broken search, the opened index is probed first; if the probe fails the
+/// directory is wiped and a fresh index is created in its place.
+///
+/// An index written with an older schema (missing fields) is rebuilt too.
+///
+/// Returns the index and, if it had to be rebuilt, a notice for the user.
+pub fn open_or_repair(path: &Path) -> tantivy::Result<(Index, Option<&'static str>)> {
+    let tantivy_schema = schema::build_schema();
+
+    let notice = if let Ok(index) = Index::open_in_dir(path) {
+        schema::register_tokenizers(&index);
+        if index.schema() != tantivy_schema {
+            eprintln!("drozosearch: index schema is outdated, rebuilding");
+            "Index format changed: rebuilding the index"
+        } else {
+            match probe(&index) {
+                Ok(()) => return Ok((index, None)),
+                Err(e) => {
+                    eprintln!("drozosearch: index failed integrity probe ({}), rebuilding", e);
+                    "Index was repaired: it was damaged and is being rebuilt"
+                }
+            }
+        }
+    } else if path.join("meta.json").exists() {
+        eprintln!("drozosearch: index metadata unreadable, rebuilding");
+        "Index was repaired: it was damaged and is being rebuilt"
+    } else {
+        return Ok((create(path, tantivy_schema)?, None));
+    };
+
+    clear_dir(path)?;
+    Ok((create(path, tantivy_schema)?, Some(notice)))
+}
+
+fn create(path: &Path, tantivy_schema: tantivy::schema::Schema) -> tantivy::Result<Index> {
+    let index = Index::create_in_dir(path, tantivy_schema)?;
+    schema::register_tokenizers(&index);
+    Ok(index)
+}
+
+/// Status shown by a copy of the app started while another one runs
+pub const HELD_ELSEWHERE: &str =
+    "another drozoSearch instance is running, so this one only searches its index";
+
+/// This process's claim on an index directory, released when dropped or
+/// when the process exits, however it exits
+pub struct IndexLock {
+    _file: fs::File,
+}
+
+/// Claim the index at `path`, so that a second copy of the app doesn't
+/// index, repair or rebuild it at the same time. `Ok(None)` when another
+/// process holds it.
+///
+/// tantivy's writer lock isn't enough for this: it's only held while a
+/// writer is open, not while the app idles between passes. The lock file
+/// sits next to the index directory rather than in it, where clearing the
+/// directory for a rebuild would remove it.
+pub fn lock(path: &Path) -> io::Result<Option<IndexLock>> {
+    let file = fs::OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(path.with_extension("lock"))?;
+    match file.try_lock_exclusive() {
+        Ok(()) => Ok(Some(IndexLock { _file: file })),
+        Err(e) if e.raw_os_error() == fs4::lock_contended_error().raw_os_error() => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+/// Open the index at `path` as it is, for searching only. Unlike
+/// [`open_or_repair`] it's never rebuilt: another process owns it.
+pub fn open_read_only(path: &Path) -> tantivy::Result<Index> {
+    let index = Index::open_in_dir(path)?;
+    schema::register_tokenizers(&index);
+    if index.schema() != schema::build_schema() {
+        return Err(TantivyError::SchemaError(
+            "the index was built by another version".to_string(),
+        ));
+    }
+    Ok(index)
+}
+
+/// Run a trivial query and load one stored document, which touches the
+/// postings, fast fields and doc store of the first segment.
+fn probe(index: &Index) -> tantivy::Result<()> {
+    let reader = index.reader()?;
+    let searcher = reader.searcher();
+    let top = searcher.search(&AllQuery, &TopDocs::with_limit(1))?;
+    if let Some((_, addr)) = top.first() {
+        searcher.doc::<tantivy::TantivyDocument>(*addr)?;
+    }
+    Ok(())
+}
+
+/// Remove everything inside the index directory, keeping the directory itself.
+fn clear_dir(path: &Path) -> std::io::Result<()> {
+    for entry in fs::read_dir(path)? {
+        let entry_path = entry?.path();
+        if entry_path.is_dir() {
+            fs::remove_dir_all(&entry_path)?;
+        } else {
+            fs::remove_file(&entry_path)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::index::fixtures;
+    use crate::index::writer::IndexWriter;
+
+    fn index_one_file(index: &Index) {
+        let mut writer = IndexWriter::new(index, 15_000_000, 10_000, None).unwrap();
+        fixtures::add_files(&mut writer, &[("/r/kept.txt", "still here")]).unwrap();
+        writer.commit().unwrap();
+    }
+
+    fn doc_count(index: &Index) -> u64 {
+        index.reader().unwrap().searcher().num_docs()
+    }
+
+    #[test]
+    fn healthy_index_opens_as_it_is() {
+        let dir = tempfile::tempdir().unwrap();
+        let (index, notice) = open_or_repair(dir.path()).unwrap();
+        assert!(notice.is_none());
+        index_one_file(&index);
+        drop(index);
+
+        let (index, notice) = open_or_repair(dir.path()).unwrap();
+        assert!(notice.is_none());
+        assert_eq!(doc_count(&index), 1);
+    }
+
+    #[test]
+    fn corrupt_segment_triggers_a_rebuild() {
+        let dir = tempfile::tempdir().unwrap();
+        let (index, _) = open_or_repair(dir.path()).unwrap();
+        index_one_file(&index);
+        drop(index);
+
+        // Garble the doc stores and postings; meta.json still lists the segment
+        for entry in fs::read_dir(dir.path()).unwrap() {
+            let path = entry.unwrap().path();
+            let ext = path.extension().and_then(|e| e.to_str()).unwrap_or("");
+            if matches!(ext, "store" | "idx" | "term") {
+                fs::write(&path, b"not a segment file").unwrap();
+            }
+        }
+
+        let (index, notice) = open_or_repair(dir.path()).unwrap();
+        assert!(notice.is_some_and(|n| n.starts_with("Index was repaired")));
+        assert_eq!(doc_count(&index), 0);
+        assert!(probe(&index).is_ok());
+    }
+
+    #[test]
+    fn unreadable_metadata_triggers_a_rebuild() {
+        let dir = tempfile::tempdir().unwrap();
+        let (index, _) = open_or_repair(dir.path()).unwrap();
+        index_one_file(&index);
+        drop(index);
+        fs::write(dir.path().join("meta.json"), b"{ truncated").unwrap();
+
+        let (index, notice) = open_or_repair(dir.path()).unwrap();
+        assert!(notice.is_some());
+        assert_eq!(doc_count(&index), 0);
+    }
+
+    #[test]
+    fn lock_is_held_until_dropped() {
+        let dir = tempfile::tempdir().unwrap();
+        let index_path = dir.path().join("index");
+        let first = lock(&index_path).unwrap();
+        assert!(first.is_some());
+        assert!(index_path.with_extension("lock").exists());
+        // Locks belong to open files, so a second claim fails in-process too
+        assert!(lock(&index_path).unwrap().is_none());
+
+        drop(first);
+        assert!(lock(&index_path).unwrap().is_some());
+    }
+
+    #[test]
+    fn lock_in_a_missing_directory_is_an_error() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(lock(&dir.path().join("missing/index")).is_err());
+    }
+}
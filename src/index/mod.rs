@@ -1,3 +1,8 @@
+pub mod fixtures;
+pub mod history;
+pub mod integrity;
+pub mod query;
 pub mod schema;
+pub mod stats;
 pub mod writer;
 pub mod reader;
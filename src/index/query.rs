@@ -0,0 +1,1307 @@
+use std::ops::Bound;
+use std::path::{Path, MAIN_SEPARATOR_STR};
+use std::sync::Arc;
+
+use tantivy::query::{
+    BooleanQuery, FuzzyTermQuery, Occur, PhraseQuery, Query, RangeQuery, RegexQuery, TermQuery,
+};
+use tantivy::schema::{Field, IndexRecordOption};
+use tantivy::Term;
+
+use super::schema::SchemaFields;
+use crate::types::FileCategory;
+
+/// A raw query string split into its free-text part and structured filters.
+///
+/// Filters may appear anywhere in the string and are all required (AND);
+/// the free text is handed to the multi-field parser, where its terms are
+/// combined as usual, and is the only part that contributes to the score.
+/// `ext:rs name:main size>1kb foo bar` therefore means "files that are .rs
+/// AND named main AND over 1 KB, ranked by how well they match foo bar".
+pub struct ParsedQuery {
+    /// Whatever is left after filter tokens are removed — scored by the
+    /// normal multi-field parser. Quoted phrases keep their quotes, and
+    /// `AND` / `OR` / `NOT` / parentheses are kept as operators (see
+    /// [`boolean_text`]).
+    pub text: String,
+    /// The words `text` asks for, without operators, quotes or excluded
+    /// words; what file names are compared with when ranking
+    pub plain: String,
+    /// Constraints every result must satisfy (they don't affect scoring)
+    pub filters: Vec<Box<dyn Query>>,
+    /// A `name:` filter is present, so every result matched on its file name
+    pub has_name_filter: bool,
+    /// Wildcard patterns (`*.test.js`) each result's file name must match.
+    /// Their index filters are in `filters` too, but only narrow the candidates.
+    pub name_globs: Vec<NameRegex>,
+}
+
+/// How a numeric field's values are written in queries
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum NumericKind {
+    /// u64 byte count: `1mb`, `500k`, `42`
+    Bytes,
+    /// Plain u64 count: pixels, seconds
+    Count,
+    /// i64 unix seconds: `2024-01-31` (local day) or a raw timestamp
+    Timestamp,
+}
+
+/// Numeric fields that can be range-filtered: query name → (schema field, kind).
+/// Adding a numeric field to the schema only needs a row here.
+const NUMERIC_FIELDS: &[(&str, &str, NumericKind)] = &[
+    ("size", "file_size", NumericKind::Bytes),
+    ("modified", "modified", NumericKind::Timestamp),
+    ("mtime", "modified", NumericKind::Timestamp),
+    ("created", "created", NumericKind::Timestamp),
+    ("ctime", "created", NumericKind::Timestamp),
+    ("width", "width", NumericKind::Count),
+    ("height", "height", NumericKind::Count),
+    ("duration", "duration", NumericKind::Count),
+];
+
+/// Pull recognized filter tokens (numeric ranges, `name`, `is`, `path`,
+/// `ext`, `category`, `owner`, `type`, `tag`, wildcard patterns) out of the query.
+pub fn parse_query(raw: &str, fields: &SchemaFields) -> ParsedQuery {
+    let mut text_tokens = Vec::new();
+    let mut filters: Vec<Box<dyn Query>> = Vec::new();
+    let mut has_name_filter = false;
+    let mut name_globs = Vec::new();
+
+    for token in split_tokens(raw) {
+        if let Some(glob) = NameRegex::from_glob(&token) {
+            filters.push(glob.query(fields.file_path));
+            name_globs.push(glob);
+            has_name_filter = true;
+        } else if let Some(range) = parse_numeric_filter(&token) {
+            filters.push(range);
+        } else if let Some(name) = parse_keyword_filter(&token, &["name"]) {
+            // Quoted values are taken literally
+            match NameRegex::from_wildcards(&name).filter(|_| !token.contains('"')) {
+                Some(glob) => {
+                    filters.push(glob.query(fields.file_path));
+                    name_globs.push(glob);
+                }
+                None => filters.push(name_query(fields, &name)),
+            }
+            has_name_filter = true;
+        } else if let Some(ext) = parse_keyword_filter(&token, &["ext"]) {
+            filters.push(extension_query(fields, &ext));
+        } else if let Some(names) = parse_keyword_filter(&token, &["category", "cat"]) {
+            filters.push(category_query(fields, &names));
+        } else if let Some(kind) = parse_keyword_filter(&token, &["is"]).and_then(|k| kind_query(fields, &k)) {
+            filters.push(kind);
+        } else if let Some(path) = parse_keyword_filter(&token, &["path"]) {
+            filters.push(path_query(fields, &path));
+        } else if let Some(owner) = parse_keyword_filter(&token, &["owner", "user"]) {
+            filters.push(owner_query(fields, &owner));
+        } else if let Some(mime) = parse_keyword_filter(&token, &["type", "mime"]) {
+            filters.push(mime_query(fields, &mime));
+        } else if let Some(tag) = parse_keyword_filter(&token, &["tag", "tags"]) {
+            filters.push(tag_query(fields, &tag));
+        } else {
+            text_tokens.push(token);
+        }
+    }
+
+    let (text, plain) = boolean_text(&text_tokens.join(" "));
+    ParsedQuery {
+        text,
+        plain,
+        filters,
+        has_name_filter,
+        name_globs,
+    }
+}
+
+/// One piece of free text, as far as boolean syntax is concerned
+#[derive(Clone, Debug, PartialEq)]
+enum BoolToken {
+    Open,
+    Close,
+    And,
+    Or,
+    Not,
+    /// A word or quoted phrase, ready for the query parser, and whether it
+    /// was written with a `-` (excluded) or `+` (required) in front
+    Operand { text: String, prefix: Option<char> },
+}
+
+/// Characters the query parser gives a meaning to inside a word. Words with
+/// any of them are passed as a quoted phrase, which the parser takes
+/// literally (escaping them with `\` isn't accepted everywhere).
+const PARSER_SPECIAL: &[char] = &['+', '-', '&', '|', '!', '(', ')', '{', '}', '[', ']', '^', '"', '~', '*', '?', ':', '\\', '/'];
+
+/// Make free text safe for the query parser while keeping boolean syntax:
+/// `AND`, `OR` and `NOT` (uppercase; lowercase ones are ordinary words),
+/// `-word` / `+word`, and parenthesized groups. Everything else is literal.
+///
+/// Unbalanced parentheses are closed or dropped, operators with nothing to
+/// act on are dropped, and a query that only excludes (`NOT test`) starts
+/// from all documents. Returns the text for the parser and the plain words
+/// it asks for (not the excluded ones).
+pub fn boolean_text(text: &str) -> (String, String) {
+    let mut out: Vec<BoolToken> = Vec::new();
+    let mut depth = 0usize;
+    for token in bool_tokens(text) {
+        let last = out.last();
+        let after_operand = matches!(last, Some(BoolToken::Operand { .. } | BoolToken::Close));
+        match token {
+            BoolToken::And | BoolToken::Or if !after_operand => {}
+            BoolToken::Not if matches!(last, Some(BoolToken::Not)) => {}
+            BoolToken::Open => {
+                depth += 1;
+                out.push(token);
+            }
+            BoolToken::Close if depth == 0 => {}
+            BoolToken::Close => {
+                depth -= 1;
+                close_group(&mut out);
+            }
+            token => out.push(token),
+        }
+    }
+    while depth > 0 {
+        depth -= 1;
+        close_group(&mut out);
+    }
+    trim_dangling(&mut out);
+
+    // Only excluded words at the top level: exclude them from everything
+    let mut positive = false;
+    let mut level = 0usize;
+    let mut negate_next = false;
+    for token in &out {
+        match token {
+            BoolToken::Not => negate_next = true,
+            BoolToken::Open => {
+                if level == 0 && !negate_next {
+                    positive = true;
+                }
+                level += 1;
+                negate_next = false;
+            }
+            BoolToken::Close => level -= 1,
+            BoolToken::Operand { prefix, .. } => {
+                if level == 0 && !negate_next && *prefix != Some('-') {
+                    positive = true;
+                }
+                negate_next = false;
+            }
+            BoolToken::And | BoolToken::Or => {}
+        }
+    }
+
+    let mut parts: Vec<String> = Vec::new();
+    if !out.is_empty() && !positive {
+        parts.push("*".to_string());
+    }
+    let mut plain = Vec::new();
+    // Whether each open group (and the top level) is excluded
+    let mut excluded = vec![false];
+    let mut negate_next = false;
+    for token in out {
+        let inside_excluded = *excluded.last().unwrap_or(&false);
+        match token {
+            BoolToken::Open => {
+                excluded.push(inside_excluded || negate_next);
+                negate_next = false;
+                parts.push("(".to_string());
+            }
+            BoolToken::Close => {
+                excluded.pop();
+                parts.push(")".to_string());
+            }
+            BoolToken::And => parts.push("AND".to_string()),
+            BoolToken::Or => parts.push("OR".to_string()),
+            BoolToken::Not => {
+                negate_next = true;
+                parts.push("NOT".to_string());
+            }
+            BoolToken::Operand { text, prefix } => {
+                if !inside_excluded && !negate_next && prefix != Some('-') {
+                    plain.push(text.trim_matches('"').to_string());
+                }
+                negate_next = false;
+                parts.push(match prefix {
+                    Some(p) => format!("{}{}", p, text),
+                    None => text,
+                });
+            }
+        }
+    }
+    (parts.join(" "), plain.join(" "))
+}
+
+/// Close the innermost group, dropping it if it ended up empty
+fn close_group(out: &mut Vec<BoolToken>) {
+    trim_dangling(out);
+    if out.last() == Some(&BoolToken::Open) {
+        out.pop();
+        // A `NOT` or `AND` before the empty group has nothing to act on now
+        trim_dangling(out);
+    } else {
+        out.push(BoolToken::Close);
+    }
+}
+
+/// Drop trailing operators that have no operand after them
+fn trim_dangling(out: &mut Vec<BoolToken>) {
+    while matches!(out.last(), Some(BoolToken::And | BoolToken::Or | BoolToken::Not)) {
+        out.pop();
+    }
+}
+
+/// Split free text into parentheses, operators and operands. Quoted phrases
+/// stay whole; an unterminated quote runs to the end.
+fn bool_tokens(text: &str) -> Vec<BoolToken> {
+    let mut tokens = Vec::new();
+    let mut chars = text.chars().peekable();
+    while let Some(&c) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+            continue;
+        }
+        if c == '(' || c == ')' {
+            chars.next();
+            tokens.push(if c == '(' { BoolToken::Open } else { BoolToken::Close });
+            continue;
+        }
+        let mut word = String::new();
+        let mut in_quotes = false;
+        while let Some(&c) = chars.peek() {
+            if !in_quotes && (c.is_whitespace() || c == '(' || c == ')') {
+                break;
+            }
+            if c == '"' {
+                in_quotes = !in_quotes;
+            }
+            word.push(c);
+            chars.next();
+        }
+        tokens.push(match word.as_str() {
+            "AND" | "&&" => BoolToken::And,
+            "OR" | "||" => BoolToken::Or,
+            "NOT" => BoolToken::Not,
+            _ => match operand(&word) {
+                Some(token) => token,
+                None => continue,
+            },
+        });
+    }
+    tokens
+}
+
+/// A word or phrase with its optional `-`/`+` prefix; special characters
+/// are neutralized by quoting. None for a bare `-` or `+`.
+fn operand(word: &str) -> Option<BoolToken> {
+    let (prefix, body) = match word.chars().next() {
+        Some(p @ ('-' | '+')) => (Some(p), &word[1..]),
+        _ => (None, word),
+    };
+    if !body.chars().any(char::is_alphanumeric) {
+        return None;
+    }
+    // Quoted phrases pass through this too, as `"` is special
+    let text = if body.contains(PARSER_SPECIAL) {
+        format!("\"{}\"", body.replace('"', ""))
+    } else {
+        body.to_string()
+    };
+    Some(BoolToken::Operand { text, prefix })
+}
+
+/// Split on whitespace, keeping quoted values (`name:"my file"`, `"exact
+/// phrase"`) and bracketed ranges (`size:[1mb TO 5mb]`) together as one token.
+/// Quotes are kept; an unterminated quote runs to the end of the query.
+fn split_tokens(raw: &str) -> Vec<String> {
+    let mut tokens = Vec::new();
+    let mut current = String::new();
+    let mut in_quotes = false;
+    let mut in_range = false;
+
+    for c in raw.chars() {
+        match c {
+            '"' => in_quotes = !in_quotes,
+            '[' if !in_quotes && current.ends_with(':') => in_range = true,
+            ']' if !in_quotes => in_range = false,
+            c if c.is_whitespace() && !in_quotes && !in_range => {
+                if !current.is_empty() {
+                    tokens.push(std::mem::take(&mut current));
+                }
+                continue;
+            }
+            _ => {}
+        }
+        current.push(c);
+    }
+    if !current.is_empty() {
+        tokens.push(current);
+    }
+    tokens
+}
+
+/// Parse a numeric filter token on any field in `NUMERIC_FIELDS`.
+///
+/// Supported forms (the `:` is optional before an operator):
+///   `size>1mb`, `size:<500kb`            — strict comparison
+///   `size>=1mb`, `modified:<=2024-06-30` — inclusive comparison
+///   `size:0`, `size:=4kb`                — exact value
+///   `size:=1mb±10%`                      — approximate size (`+-` works too)
+///   `created:[2024-01-01 TO 2024-03-31]` — inclusive range, `*` for open ends
+///   `size:1mb..100mb`, `size:1mb..`       — inclusive range, either end left open
+///
+/// A date stands for its whole (local) day, so `modified:2024-01-31` matches
+/// anything changed that day and `modified:>2024-01-31` starts the next one.
+/// Relative dates (`modified:today`, `modified:last7days`, `created:lastmonth`)
+/// stand for the days from then up to and including today.
+/// Tokens that don't parse are left in the text query.
+fn parse_numeric_filter(token: &str) -> Option<Box<dyn Query>> {
+    let lower = token.to_lowercase();
+    let &(name, field, kind) = NUMERIC_FIELDS
+        .iter()
+        .filter(|(name, _, _)| lower.starts_with(name))
+        .max_by_key(|(name, _, _)| name.len())?;
+    let rest = &lower[name.len()..];
+
+    let (lower, upper) = match rest.strip_prefix(':') {
+        Some(value) if value.starts_with(['>', '<']) => comparison(value, kind)?,
+        Some(value) => {
+            let range = match value.strip_prefix('[').and_then(|r| r.strip_suffix(']')) {
+                Some(range) => Some(range.split_once(" to ")?),
+                // A bare `..` isn't a range
+                None => value.split_once("..").filter(|(from, to)| !from.is_empty() || !to.is_empty()),
+            };
+            match range {
+                Some((from, to)) => (
+                    range_end(from, kind, |(lo, _)| Bound::Included(lo))?,
+                    range_end(to, kind, |(_, hi)| Bound::Included(hi))?,
+                ),
+                None => exact(value.strip_prefix('=').unwrap_or(value), kind)?,
+            }
+        }
+        None => comparison(rest, kind)?,
+    };
+
+    let field = field.to_string();
+    Some(match kind {
+        NumericKind::Bytes | NumericKind::Count => Box::new(RangeQuery::new_u64_bounds(
+            field,
+            lower.map(|v| v as u64),
+            upper.map(|v| v as u64),
+        )),
+        NumericKind::Timestamp => Box::new(RangeQuery::new_i64_bounds(field, lower, upper)),
+    })
+}
+
+type Bounds = (Bound<i64>, Bound<i64>);
+
+/// `>v`, `>=v`, `<v` or `<=v`
+fn comparison(expr: &str, kind: NumericKind) -> Option<Bounds> {
+    if let Some(v) = expr.strip_prefix(">=") {
+        Some((Bound::Included(parse_value(v, kind)?.0), Bound::Unbounded))
+    } else if let Some(v) = expr.strip_prefix('>') {
+        Some((Bound::Excluded(parse_value(v, kind)?.1), Bound::Unbounded))
+    } else if let Some(v) = expr.strip_prefix("<=") {
+        Some((Bound::Unbounded, Bound::Included(parse_value(v, kind)?.1)))
+    } else if let Some(v) = expr.strip_prefix('<') {
+        Some((Bound::Unbounded, Bound::Excluded(parse_value(v, kind)?.0)))
+    } else {
+        None
+    }
+}
+
+/// An exact value, with an optional `±N%` tolerance for sizes
+fn exact(value: &str, kind: NumericKind) -> Option<Bounds> {
+    let tolerance = value.split_once('±').or_else(|| value.split_once("+-"));
+    if let (Some((v, pct)), NumericKind::Bytes) = (tolerance, kind) {
+        let bytes = parse_value(v, kind)?.0;
+        let delta = (bytes as f64 * parse_percent(pct)? / 100.0) as i64;
+        // Past 100% the range reaches down to empty files
+        return Some((
+            Bound::Included(bytes.saturating_sub(delta).max(0)),
+            Bound::Included(bytes.saturating_add(delta)),
+        ));
+    }
+    let (lo, hi) = parse_value(value, kind)?;
+    Some((Bound::Included(lo), Bound::Included(hi)))
+}
+
+/// One end of a `[a TO b]` or `a..b` range; `*` or nothing leaves it open.
+fn range_end(
+    value: &str,
+    kind: NumericKind,
+    pick: impl Fn((i64, i64)) -> Bound<i64>,
+) -> Option<Bound<i64>> {
+    match value.trim() {
+        "*" | "" => Some(Bound::Unbounded),
+        v => Some(pick(parse_value(v, kind)?)),
+    }
+}
+
+/// Parse a value into the inclusive span it stands for: a single number, or
+/// the first and last second of a day.
+fn parse_value(value: &str, kind: NumericKind) -> Option<(i64, i64)> {
+    match kind {
+        NumericKind::Bytes => {
+            let bytes = i64::try_from(parse_size(value)?).ok()?;
+            Some((bytes, bytes))
+        }
+        NumericKind::Count => {
+            let n = value.parse::<i64>().ok().filter(|n| *n >= 0)?;
+            Some((n, n))
+        }
+        NumericKind::Timestamp => {
+            if let Ok(ts) = value.parse::<i64>() {
+                return Some((ts, ts));
+            }
+            let today = chrono::Local::now().date_naive();
+            let (first, last) = match chrono::NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+                Ok(date) => (date, date),
+                Err(_) => (relative_start(value, today)?, today),
+            };
+            // Next midnight rather than +24h, so DST days come out right
+            Some((start_of_day(first)?, start_of_day(last.succ_opt()?)? - 1))
+        }
+    }
+}
+
+/// First day covered by a relative date, counting today as one of the days:
+/// `today`, `yesterday`, `lastweek` / `lastmonth` / `lastyear` (the past 7
+/// days, month or year), and `lastNdays` / `lastNweeks` / `lastNmonths`.
+/// `yesterday` ends today too, so `modified:yesterday` means "since yesterday".
+fn relative_start(value: &str, today: chrono::NaiveDate) -> Option<chrono::NaiveDate> {
+    let rest = match value {
+        "today" => return Some(today),
+        "yesterday" => return today.pred_opt(),
+        _ => value.strip_prefix("last")?,
+    };
+    let split = rest.find(|c: char| !c.is_ascii_digit()).unwrap_or(rest.len());
+    let (count, unit) = rest.split_at(split);
+    let n: u32 = if count.is_empty() { 1 } else { count.parse().ok()? };
+    if n == 0 {
+        return None;
+    }
+    match unit {
+        "day" | "days" => today.checked_sub_days(chrono::Days::new(u64::from(n) - 1)),
+        "week" | "weeks" => today.checked_sub_days(chrono::Days::new(u64::from(n) * 7 - 1)),
+        "month" | "months" => today.checked_sub_months(chrono::Months::new(n))?.succ_opt(),
+        "year" | "years" => today.checked_sub_months(chrono::Months::new(n * 12))?.succ_opt(),
+        _ => None,
+    }
+}
+
+fn start_of_day(day: chrono::NaiveDate) -> Option<i64> {
+    day.and_hms_opt(0, 0, 0)?
+        .and_local_timezone(chrono::Local)
+        .earliest()
+        .map(|t| t.timestamp())
+}
+
+/// The pattern of a query written as `/pattern/`, which searches file names
+/// by regular expression instead of words.
+pub fn regex_pattern(raw: &str) -> Option<&str> {
+    let inner = raw.trim().strip_prefix('/')?.strip_suffix('/')?;
+    (!inner.is_empty()).then_some(inner)
+}
+
+/// A `/pattern/` query, e.g. `/^test_.*\.rs$/`. Without `^` or `$` the
+/// pattern may match anywhere in the name, like `grep`.
+pub struct NameRegex {
+    /// The pattern as written, checked against each candidate's file name
+    name: regex::Regex,
+    /// The pattern wrapped to match whole `file_path` terms (the index's
+    /// automaton regexes are implicitly anchored and don't support `^`/`$`)
+    path: Arc<tantivy_fst::Regex>,
+}
+
+impl NameRegex {
+    /// Compile `pattern`; the error is a short message for the user.
+    pub fn new(pattern: &str) -> Result<NameRegex, String> {
+        let name = regex::Regex::new(pattern).map_err(|e| {
+            // The full message is a multi-line caret diagram; the last line says what's wrong
+            let message = e.to_string();
+            let last = message.lines().last().unwrap_or_default();
+            format!("Invalid regex: {}", last.trim_start_matches("error: "))
+        })?;
+
+        let sep = regex::escape(MAIN_SEPARATOR_STR);
+        let any_name_chars = format!("[^{}]*", sep);
+        // Every path ending in a name: what's left when the pattern can't be
+        // narrowed down by the index
+        let any_name = format!(".*{}{}", sep, any_name_chars);
+        let path = if has_top_level_alternation(pattern) {
+            // `^foo|bar$` anchors each branch on its own; stripping the
+            // outer `^` and `$` would anchor both
+            tantivy_fst::Regex::new(&any_name)
+        } else {
+            let (inner, anchored_start) = match pattern.strip_prefix('^') {
+                Some(rest) => (rest, true),
+                None => (pattern, false),
+            };
+            // A trailing `$` unless it's escaped (`\$`)
+            let escapes = inner.trim_end_matches('$').chars().rev().take_while(|&c| c == '\\').count();
+            let (inner, anchored_end) = match inner.strip_suffix('$') {
+                Some(rest) if escapes % 2 == 0 => (rest, true),
+                _ => (inner, false),
+            };
+            let path_pattern = format!(
+                ".*{}{}(?:{}){}",
+                sep,
+                if anchored_start { "" } else { &any_name_chars },
+                inner,
+                if anchored_end { "" } else { &any_name_chars },
+            );
+            // Falls back on what the index can't run, e.g. `^` mid-pattern
+            // or lazy `*?`; the name check still applies the whole pattern
+            tantivy_fst::Regex::new(&path_pattern).or_else(|_| tantivy_fst::Regex::new(&any_name))
+        };
+        let path = path.map_err(|e| format!("Invalid regex: {}", e))?;
+        Ok(NameRegex {
+            name,
+            path: Arc::new(path),
+        })
+    }
+
+    /// Candidate documents for the pattern, to be confirmed with
+    /// [`NameRegex::is_match`]: inside a wrapped pattern `.` can still cross
+    /// a path separator.
+    ///
+    /// Unlike word queries this can't look up a few terms: the automaton is
+    /// run against the whole `file_path` term dictionary, i.e. every indexed
+    /// path, so it gets noticeably slower as the index grows (and patterns
+    /// starting with `.*` can't skip any of it). Callers should keep their
+    /// usual cap on how many hits are fetched and ranked.
+    pub fn query(&self, file_path: Field) -> Box<dyn Query> {
+        Box::new(RegexQuery::from_regex(self.path.clone(), file_path))
+    }
+
+    /// A bare wildcard token such as `*.test.js` or `app_??.rs`: `*` is any
+    /// run of characters and `?` any one character. It matches whole file
+    /// names, ignoring case. Tokens without a wildcard, quoted ones,
+    /// `key:value` filters and words ending a question (`what?`) return None;
+    /// `name:what?` takes the wildcard anyway.
+    pub fn from_glob(token: &str) -> Option<NameRegex> {
+        if token.contains([':', '"']) || !token.chars().any(|c| c != '*' && c != '?') {
+            return None;
+        }
+        // Trailing `?`s are punctuation unless the token is clearly a file
+        // name, with a `*` or an extension
+        let body = token.trim_end_matches('?');
+        let glob = token.contains('*') || body.contains('?') || (body.len() < token.len() && body.contains('.'));
+        if !glob {
+            return None;
+        }
+        NameRegex::from_wildcards(token)
+    }
+
+    /// The wildcard pattern of a `name:` value; None without a `*` or `?`
+    fn from_wildcards(token: &str) -> Option<NameRegex> {
+        if !token.contains(['*', '?']) {
+            return None;
+        }
+        let any_char = format!("[^{}]", regex::escape(MAIN_SEPARATOR_STR));
+        let mut pattern = String::from("^");
+        for c in token.chars() {
+            match c {
+                '*' => pattern.push_str(&format!("{}*", any_char)),
+                '?' => pattern.push_str(&any_char),
+                c => push_any_case(&mut pattern, c),
+            }
+        }
+        pattern.push('$');
+        NameRegex::new(&pattern).ok()
+    }
+
+    pub fn is_match(&self, file_name: &str) -> bool {
+        self.name.is_match(file_name)
+    }
+}
+
+/// Whether `pattern` has a `|` outside any group or character class
+fn has_top_level_alternation(pattern: &str) -> bool {
+    let mut depth = 0usize;
+    let mut in_class = false;
+    let mut chars = pattern.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => {
+                chars.next();
+            }
+            '[' => in_class = true,
+            ']' => in_class = false,
+            '(' if !in_class => depth += 1,
+            ')' if !in_class => depth = depth.saturating_sub(1),
+            '|' if !in_class && depth == 0 => return true,
+            _ => {}
+        }
+    }
+    false
+}
+
+/// Append `c` to a regex pattern, matching it in either case. Spelled-out
+/// classes rather than `(?i)`, which the index's regexes don't take.
+fn push_any_case(pattern: &mut String, c: char) {
+    match c {
+        c if c.is_alphabetic() && c.to_uppercase().eq(c.to_lowercase()) => pattern.push(c),
+        c if c.is_alphabetic() => {
+            pattern.push('[');
+            pattern.extend(c.to_lowercase());
+            pattern.extend(c.to_uppercase());
+            pattern.push(']');
+        }
+        c => pattern.push_str(&regex::escape(c.encode_utf8(&mut [0; 4]))),
+    }
+}
+
+/// `path:projects/backend` — the full path contains the text, ignoring case.
+/// `/` and `\` both stand for either separator, so a query works the same
+/// on every platform. Like [`NameRegex::query`] this runs an automaton over
+/// every indexed path rather than looking up a term.
+fn path_query(fields: &SchemaFields, text: &str) -> Box<dyn Query> {
+    let mut pattern = String::from(".*");
+    for c in text.chars() {
+        match c {
+            '/' | '\\' => pattern.push_str(r"[/\\]"),
+            c => push_any_case(&mut pattern, c),
+        }
+    }
+    pattern.push_str(".*");
+    match tantivy_fst::Regex::new(&pattern) {
+        Ok(regex) => Box::new(RegexQuery::from_regex(regex, fields.file_path)),
+        // Too big an automaton; match nothing rather than everything
+        Err(_) => Box::new(BooleanQuery::new(Vec::new())),
+    }
+}
+
+/// Match everything stored under `dir` (not `dir` itself).
+///
+/// `file_path` is indexed as one raw term, so this is a term range from
+/// `dir/` up to (not including) `dir` followed by the character after the
+/// separator — exactly the paths that start with `dir/`.
+pub fn path_prefix_query(dir: &Path) -> Box<dyn Query> {
+    let sep = std::path::MAIN_SEPARATOR;
+    let base = dir.to_string_lossy();
+    let base = base.trim_end_matches(sep);
+    let after_sep = char::from_u32(sep as u32 + 1).unwrap_or(char::MAX);
+    let lower = format!("{}{}", base, sep);
+    let upper = format!("{}{}", base, after_sep);
+    Box::new(RangeQuery::new_str_bounds(
+        "file_path".to_string(),
+        Bound::Included(&lower),
+        Bound::Excluded(&upper),
+    ))
+}
+
+/// Parse a `key:value` token for one of `keys` (case-insensitive), returning
+/// the value with surrounding quotes removed.
+fn parse_keyword_filter(token: &str, keys: &[&str]) -> Option<String> {
+    let (key, value) = token.split_once(':')?;
+    if !keys.iter().any(|k| key.eq_ignore_ascii_case(k)) {
+        return None;
+    }
+    let value = value.trim_matches('"');
+    (!value.is_empty()).then(|| value.to_string())
+}
+
+/// Words of a filter value, split and lowercased like the `file_name` and
+/// `metadata` tokenizers do.
+fn words(value: &str) -> Vec<String> {
+    value
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .map(str::to_lowercase)
+        .collect()
+}
+
+/// `name:main` requires the word in the file name; `name:user-service` or
+/// `name:"my file"` require the words next to each other, in order.
+fn name_query(fields: &SchemaFields, name: &str) -> Box<dyn Query> {
+    let mut terms: Vec<Term> = words(name)
+        .iter()
+        .map(|w| Term::from_field_text(fields.file_name, w))
+        .collect();
+    match terms.len() {
+        0 => Box::new(BooleanQuery::new(Vec::new())),
+        1 => Box::new(TermQuery::new(terms.remove(0), IndexRecordOption::Basic)),
+        _ => Box::new(PhraseQuery::new(terms)),
+    }
+}
+
+/// `is:dir` (or `is:folder`) keeps only directories, `is:file` only files.
+/// Any other value isn't a filter.
+fn kind_query(fields: &SchemaFields, kind: &str) -> Option<Box<dyn Query>> {
+    let is_dir = match kind.to_lowercase().as_str() {
+        "dir" | "dirs" | "directory" | "folder" | "folders" => 1,
+        "file" | "files" => 0,
+        _ => return None,
+    };
+    Some(Box::new(TermQuery::new(
+        Term::from_field_u64(fields.is_dir, is_dir),
+        IndexRecordOption::Basic,
+    )))
+}
+
+/// `ext:rs` or `ext:rs,toml` — any of the listed extensions. Extensions are
+/// indexed lowercase; the uppercase spelling still catches documents indexed
+/// before that (e.g. camera `.JPG` files) until they're re-indexed.
+fn extension_query(fields: &SchemaFields, exts: &str) -> Box<dyn Query> {
+    let mut spellings: Vec<String> = exts
+        .split(',')
+        .map(|ext| ext.trim().trim_start_matches('.').to_lowercase())
+        .filter(|ext| !ext.is_empty())
+        .flat_map(|ext| [ext.to_uppercase(), ext])
+        .collect();
+    spellings.sort();
+    spellings.dedup();
+    let clauses = spellings
+        .iter()
+        .map(|e| {
+            let term = Term::from_field_text(fields.extension, e);
+            (
+                Occur::Should,
+                Box::new(TermQuery::new(term, IndexRecordOption::Basic)) as Box<dyn Query>,
+            )
+        })
+        .collect();
+    Box::new(BooleanQuery::new(clauses))
+}
+
+/// `category:code` or `category:media,documents` — any extension of the named
+/// [`FileCategory`] groups, the same ones the result icons and openers go by.
+/// An unknown name matches nothing.
+fn category_query(fields: &SchemaFields, names: &str) -> Box<dyn Query> {
+    let exts: Vec<&str> = names
+        .split(',')
+        .filter_map(|name| FileCategory::named(name.trim()))
+        .flatten()
+        .flat_map(|category| category.extensions())
+        .copied()
+        .collect();
+    extension_query(fields, &exts.join(","))
+}
+
+/// `owner:alice` or `owner:alice,bob` — files owned by any of the listed
+/// users. Names are matched exactly, as the system spells them; an owner
+/// without a name is its numeric id (`owner:1001`).
+fn owner_query(fields: &SchemaFields, owners: &str) -> Box<dyn Query> {
+    let clauses = owners
+        .split(',')
+        .map(str::trim)
+        .filter(|owner| !owner.is_empty())
+        .map(|owner| {
+            let term = Term::from_field_text(fields.owner, owner);
+            (
+                Occur::Should,
+                Box::new(TermQuery::new(term, IndexRecordOption::Basic)) as Box<dyn Query>,
+            )
+        })
+        .collect();
+    Box::new(BooleanQuery::new(clauses))
+}
+
+/// `type:image` matches any `image/…` MIME type, `type:image/png` exactly
+/// that one; `type:audio,video` any of the listed.
+fn mime_query(fields: &SchemaFields, types: &str) -> Box<dyn Query> {
+    let clauses = types
+        .split(',')
+        .map(|t| t.trim().to_lowercase())
+        .filter(|t| !t.is_empty())
+        .map(|t| {
+            let prefix = match t.strip_suffix("/*") {
+                Some(prefix) => Some(prefix),
+                None if !t.contains('/') => Some(t.as_str()),
+                None => None,
+            };
+            let query: Box<dyn Query> = match prefix {
+                // Every type under `t/`: the term range up to `t0` ('0' follows '/')
+                Some(prefix) => Box::new(RangeQuery::new_str_bounds(
+                    "mime_type".to_string(),
+                    Bound::Included(&format!("{}/", prefix)),
+                    Bound::Excluded(&format!("{}0", prefix)),
+                )),
+                None => Box::new(TermQuery::new(
+                    Term::from_field_text(fields.mime_type, &t),
+                    IndexRecordOption::Basic,
+                )),
+            };
+            (Occur::Should, query)
+        })
+        .collect();
+    Box::new(BooleanQuery::new(clauses))
+}
+
+/// Require every word of the tag in the metadata field. The field uses the
+/// default tokenizer, so `my-project` is matched as `my` + `project`.
+fn tag_query(fields: &SchemaFields, tag: &str) -> Box<dyn Query> {
+    let terms: Vec<Box<dyn Query>> = words(tag)
+        .iter()
+        .map(|w| {
+            Box::new(TermQuery::new(
+                Term::from_field_text(fields.metadata, w),
+                IndexRecordOption::Basic,
+            )) as Box<dyn Query>
+        })
+        .collect();
+    Box::new(BooleanQuery::intersection(terms))
+}
+
+/// Words this short are never fuzzed: one typo away from a 3-letter word is
+/// half the dictionary
+const MIN_FUZZY_LEN: usize = 4;
+
+/// Edits allowed for a word: one for short words, two from 8 characters on
+fn max_edits(word: &str) -> u8 {
+    if word.chars().count() >= 8 {
+        2
+    } else {
+        1
+    }
+}
+
+/// Typo-tolerant file name query: every word of `text` must appear in the
+/// name, words of 4+ characters within one or two edits (a swap of adjacent
+/// letters counts as one). `None` if no word is long enough to fuzz, so the
+/// fallback would only repeat the exact search.
+pub fn fuzzy_name_query(fields: &SchemaFields, text: &str) -> Option<Box<dyn Query>> {
+    let words = words(text);
+    if !words.iter().any(|w| w.chars().count() >= MIN_FUZZY_LEN) {
+        return None;
+    }
+    let clauses = words
+        .iter()
+        .map(|w| {
+            let term = Term::from_field_text(fields.file_name, w);
+            let query: Box<dyn Query> = if w.chars().count() >= MIN_FUZZY_LEN {
+                Box::new(FuzzyTermQuery::new(term, max_edits(w), true))
+            } else {
+                Box::new(TermQuery::new(term, IndexRecordOption::Basic))
+            };
+            query
+        })
+        .collect();
+    Some(Box::new(BooleanQuery::intersection(clauses)))
+}
+
+/// Total edits between the words of `query` and the closest words of
+/// `file_name` — how far off a fuzzy name match was. 0 for an exact match.
+pub fn fuzzy_distance(query: &str, file_name: &str) -> u32 {
+    let name_words = words(file_name);
+    words(query)
+        .iter()
+        .map(|q| {
+            name_words
+                .iter()
+                .map(|n| edit_distance(q, n))
+                .min()
+                .unwrap_or(0)
+        })
+        .sum()
+}
+
+/// Levenshtein distance where swapping two adjacent characters costs one
+/// edit, like `FuzzyTermQuery` with transpositions enabled.
+fn edit_distance(a: &str, b: &str) -> u32 {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    // Rows i-2, i-1 and i of the distance matrix
+    let mut older: Vec<u32> = vec![0; b.len() + 1];
+    let mut prev: Vec<u32> = (0..=b.len() as u32).collect();
+    let mut row = vec![0; b.len() + 1];
+    for i in 1..=a.len() {
+        row[0] = i as u32;
+        for j in 1..=b.len() {
+            let cost = u32::from(a[i - 1] != b[j - 1]);
+            row[j] = (prev[j] + 1).min(row[j - 1] + 1).min(prev[j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                row[j] = row[j].min(older[j - 2] + 1);
+            }
+        }
+        std::mem::swap(&mut older, &mut prev);
+        std::mem::swap(&mut prev, &mut row);
+    }
+    prev[b.len()]
+}
+
+/// Parse a human-friendly size like `1mb`, `1.5GiB`, `500k` or `42` into bytes.
+/// Units are binary (1 kb = 1024 bytes), matching `format_size`.
+pub fn parse_size(s: &str) -> Option<u64> {
+    let s = s.trim().to_lowercase();
+    let split = s
+        .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+        .unwrap_or(s.len());
+    let (number, unit) = s.split_at(split);
+    let number: f64 = number.parse().ok()?;
+
+    let multiplier: u64 = match unit {
+        "" | "b" => 1,
+        "k" | "kb" | "kib" => 1024,
+        "m" | "mb" | "mib" => 1024 * 1024,
+        "g" | "gb" | "gib" => 1024 * 1024 * 1024,
+        "t" | "tb" | "tib" => 1024 * 1024 * 1024 * 1024,
+        _ => return None,
+    };
+
+    Some((number * multiplier as f64).round() as u64)
+}
+
+fn parse_percent(s: &str) -> Option<f64> {
+    let pct: f64 = s.strip_suffix('%').unwrap_or(s).parse().ok()?;
+    (pct >= 0.0).then_some(pct)
+}
+
+#[cfg(test)]
+mod tests {
+    use tantivy::collector::DocSetCollector;
+    use tantivy::schema::Value;
+    use tantivy::{Index, TantivyDocument};
+
+    use super::*;
+    use crate::index::fixtures::{self, file, TestFile};
+    use crate::index::schema;
+    use crate::indexer::media::MediaInfo;
+
+    /// Details of a `width` wide, half as high image
+    fn image(width: u64) -> Option<MediaInfo> {
+        Some(MediaInfo {
+            width: Some(width),
+            height: Some(width / 2),
+            ..MediaInfo::default()
+        })
+    }
+
+    /// Paths of the documents passing every filter of `query`, sorted
+    fn filtered(index: &Index, fields: &SchemaFields, query: &str) -> Vec<String> {
+        let parsed = parse_query(query, fields);
+        assert!(!parsed.filters.is_empty(), "no filter in {:?}", query);
+        let searcher = index.reader().unwrap().searcher();
+        let filter = BooleanQuery::intersection(parsed.filters);
+        let mut paths: Vec<String> = searcher
+            .search(&filter, &DocSetCollector)
+            .unwrap()
+            .into_iter()
+            .map(|addr| {
+                let doc: TantivyDocument = searcher.doc(addr).unwrap();
+                doc.get_first(fields.file_path).unwrap().as_str().unwrap().to_string()
+            })
+            .collect();
+        paths.sort();
+        paths
+    }
+
+    #[test]
+    fn size_units_are_binary() {
+        assert_eq!(parse_size("42"), Some(42));
+        assert_eq!(parse_size("42b"), Some(42));
+        assert_eq!(parse_size("1kb"), Some(1024));
+        assert_eq!(parse_size("500K"), Some(500 * 1024));
+        assert_eq!(parse_size("1mb"), Some(1 << 20));
+        assert_eq!(parse_size("1.5GiB"), Some(3 << 29));
+        assert_eq!(parse_size("2tb"), Some(2 << 40));
+        assert_eq!(parse_size("1 parsec"), None);
+        assert_eq!(parse_size("mb"), None);
+    }
+
+    #[test]
+    fn exact_approximate_and_empty_sizes() {
+        let index = fixtures::index_of_files(&[
+            TestFile { size: 0, ..file("/r/empty.txt") },
+            TestFile { size: 1024, ..file("/r/one_k.txt") },
+            TestFile { size: 1_000_000, ..file("/r/million.bin") },
+            TestFile { size: 1 << 20, ..file("/r/one_mib.bin") },
+            TestFile { size: 1_200_000, ..file("/r/too_big.bin") },
+        ])
+        .unwrap();
+        let fields = SchemaFields::new(&index.schema());
+        let filtered = |q| filtered(&index, &fields, q);
+
+        assert_eq!(filtered("size:0"), ["/r/empty.txt"]);
+        assert_eq!(filtered("size<1"), ["/r/empty.txt"]);
+        assert_eq!(filtered("size:=1kb"), ["/r/one_k.txt"]);
+        assert_eq!(filtered("size:1mb"), ["/r/one_mib.bin"]);
+        // 1 MiB ± 10% is 943,718 to 1,153,433 bytes
+        assert_eq!(filtered("size:=1mb±10%"), ["/r/million.bin", "/r/one_mib.bin"]);
+        assert_eq!(filtered("size:=1mb+-10%"), ["/r/million.bin", "/r/one_mib.bin"]);
+        // 0 to 3 MiB
+        assert_eq!(filtered("size:=1mb±200%").len(), 5);
+        assert_eq!(filtered("size>=1mb"), ["/r/one_mib.bin", "/r/too_big.bin"]);
+        assert_eq!(filtered("size:>1mb"), ["/r/too_big.bin"]);
+        assert_eq!(filtered("size:<=1kb"), ["/r/empty.txt", "/r/one_k.txt"]);
+    }
+
+    #[test]
+    fn malformed_sizes_stay_in_the_text() {
+        let fields = SchemaFields::new(&schema::build_schema());
+        for token in ["size:huge", "size>", "size:=1mb±lots", "size:1xb"] {
+            let parsed = parse_query(token, &fields);
+            assert!(parsed.filters.is_empty(), "{} became a filter", token);
+        }
+    }
+
+    fn local_noon(y: i32, m: u32, d: u32) -> i64 {
+        use chrono::TimeZone;
+        chrono::Local.with_ymd_and_hms(y, m, d, 12, 0, 0).single().unwrap().timestamp()
+    }
+
+    fn result_paths(results: &[crate::types::SearchResult]) -> Vec<String> {
+        let mut paths: Vec<String> = results.iter().map(|r| r.file_path.to_string_lossy().to_string()).collect();
+        paths.sort();
+        paths
+    }
+
+    #[test]
+    fn numeric_filters_on_every_field() {
+        let index = fixtures::index_of_files(&[
+            TestFile { modified: local_noon(2023, 6, 15), media: image(640), ..file("/r/old.png") },
+            TestFile { modified: local_noon(2024, 1, 31), media: image(1920), ..file("/r/new.png") },
+            TestFile { modified: local_noon(2024, 3, 1), ..file("/r/notes.txt") },
+        ])
+        .unwrap();
+        let fields = SchemaFields::new(&index.schema());
+        let filtered = |q| filtered(&index, &fields, q);
+
+        // A date is its whole local day
+        assert_eq!(filtered("modified:2024-01-31"), ["/r/new.png"]);
+        assert_eq!(filtered("modified:>2024-01-31"), ["/r/notes.txt"]);
+        assert_eq!(filtered("mtime>=2024-01-31"), ["/r/new.png", "/r/notes.txt"]);
+        assert_eq!(filtered("modified:<2024-01-01"), ["/r/old.png"]);
+        assert_eq!(filtered("created:[2023-01-01 TO 2023-12-31]"), ["/r/old.png"]);
+        assert_eq!(filtered("ctime:[2024-01-01 TO *]"), ["/r/new.png", "/r/notes.txt"]);
+        assert_eq!(filtered("created:2024-01-01..2024-02-29"), ["/r/new.png"]);
+        // Raw timestamps work as well
+        let exact = format!("modified:{}", local_noon(2024, 3, 1));
+        assert_eq!(filtered(&exact), ["/r/notes.txt"]);
+
+        assert_eq!(filtered("width>1000"), ["/r/new.png"]);
+        assert_eq!(filtered("width:[600 TO 700]"), ["/r/old.png"]);
+        assert_eq!(filtered("height:..500"), ["/r/old.png"]);
+        assert_eq!(filtered("width:640 height:320"), ["/r/old.png"]);
+        assert!(filtered("duration>0").is_empty());
+    }
+
+    #[test]
+    fn relative_dates_count_today() {
+        let today = chrono::NaiveDate::from_ymd_opt(2024, 3, 15).unwrap();
+        let day = |d| chrono::NaiveDate::from_ymd_opt(2024, 3, d);
+        assert_eq!(relative_start("today", today), day(15));
+        assert_eq!(relative_start("yesterday", today), day(14));
+        assert_eq!(relative_start("last7days", today), day(9));
+        assert_eq!(relative_start("lastweek", today), day(9));
+        assert_eq!(relative_start("last2weeks", today), day(2));
+        assert_eq!(relative_start("lastmonth", today), chrono::NaiveDate::from_ymd_opt(2024, 2, 16));
+        assert_eq!(relative_start("last0days", today), None);
+        assert_eq!(relative_start("lastfortnight", today), None);
+    }
+
+    #[test]
+    fn longer_names_are_not_taken_for_fields() {
+        let fields = SchemaFields::new(&schema::build_schema());
+        assert_eq!(parse_query("sizes:>1", &fields).filters.len(), 0);
+        assert_eq!(parse_query("widthx:>1", &fields).filters.len(), 0);
+        assert_eq!(parse_query("modified:>2024-01-01 size>1", &fields).filters.len(), 2);
+    }
+
+    #[test]
+    fn filters_and_free_text_separate_anywhere_in_the_query() {
+        let fields = SchemaFields::new(&schema::build_schema());
+        let parsed = parse_query("ext:rs name:main size>1kb modified:last30days foo bar", &fields);
+        assert_eq!(parsed.text, "foo bar");
+        assert_eq!(parsed.plain, "foo bar");
+        assert_eq!(parsed.filters.len(), 4);
+        assert!(parsed.has_name_filter);
+
+        let parsed = parse_query("foo ext:rs bar EXT:md \"exact phrase\" size:[1kb TO 2kb]", &fields);
+        assert_eq!(parsed.text, "foo bar \"exact phrase\"");
+        assert_eq!(parsed.plain, "foo bar exact phrase");
+        assert_eq!(parsed.filters.len(), 3);
+        assert!(!parsed.has_name_filter);
+
+        // Only filters: nothing to score
+        let parsed = parse_query("ext:rs is:file", &fields);
+        assert_eq!(parsed.text, "");
+        assert_eq!(parsed.filters.len(), 2);
+    }
+
+    #[test]
+    fn quoted_name_values_are_phrases() {
+        let index = fixtures::index_of(&[
+            ("/r/my file.txt", ""),
+            ("/r/file my.txt", ""),
+            ("/r/my-file.md", ""),
+            ("/r/myfile.txt", ""),
+        ])
+        .unwrap();
+        let fields = SchemaFields::new(&index.schema());
+        assert_eq!(
+            filtered(&index, &fields, "name:\"my file\""),
+            ["/r/my file.txt", "/r/my-file.md"]
+        );
+        assert_eq!(filtered(&index, &fields, "name:\"my file\" ext:md"), ["/r/my-file.md"]);
+        let parsed = parse_query("name:\"my file\" report", &fields);
+        assert_eq!(parsed.text, "report");
+    }
+
+    #[test]
+    fn filters_narrow_without_changing_scores() {
+        let index = fixtures::index_of(&[
+            ("/r/a/runtime.rs", "tokio tokio spawn"),
+            ("/r/b/server.rs", "tokio listener"),
+            ("/r/c/notes.md", "tokio tokio tokio"),
+        ])
+        .unwrap();
+        let (engine, _history) = fixtures::engine(index, &["/r"]);
+
+        let all = engine.search("tokio", 10);
+        let rust = engine.search("ext:rs tokio", 10);
+        assert_eq!(all.len(), 3);
+        assert_eq!(rust.len(), 2);
+        for result in &rust {
+            let unfiltered = all.iter().find(|r| r.file_path == result.file_path).unwrap();
+            assert_eq!(result.score, unfiltered.score);
+        }
+        // Filters alone still find everything they allow
+        assert_eq!(engine.search("ext:rs", 10).len(), 2);
+    }
+
+    #[test]
+    fn size_filter_combines_with_free_text() {
+        let mb = 1 << 20;
+        let index = fixtures::index_of_files(&[
+            TestFile { size: 2 * mb, ..file("/r/foo_big.bin") },
+            TestFile { size: 2 * mb, text: "foo inside".to_string(), ..file("/r/notes_big.txt") },
+            TestFile { size: 10 * 1024, ..file("/r/foo_small.bin") },
+            TestFile { size: 2 * mb, ..file("/r/other_big.bin") },
+        ])
+        .unwrap();
+        let (engine, _history) = fixtures::engine(index, &["/r"]);
+        let search = |q: &str| result_paths(&engine.search(q, 10));
+
+        assert_eq!(search("size>1mb foo"), ["/r/foo_big.bin", "/r/notes_big.txt"]);
+        assert_eq!(search("foo size<1mb"), ["/r/foo_small.bin"]);
+    }
+
+    #[test]
+    fn boolean_operators_are_kept_and_words_escaped() {
+        assert_eq!(boolean_text("(rust OR go) NOT test"), ("( rust OR go ) NOT test".to_string(), "rust go".to_string()));
+        assert_eq!(boolean_text("NOT test -draft"), ("* NOT test -draft".to_string(), String::new()));
+        // Lowercase operators are words; special characters are quoted
+        assert_eq!(boolean_text("not or c++"), ("not or \"c++\"".to_string(), "not or c++".to_string()));
+        // Unbalanced and dangling syntax is repaired rather than rejected
+        assert_eq!(boolean_text("((rust OR"), ("( ( rust ) )".to_string(), "rust".to_string()));
+        assert_eq!(boolean_text(") AND rust NOT"), ("rust".to_string(), "rust".to_string()));
+        assert_eq!(boolean_text("rust NOT ()"), ("rust".to_string(), "rust".to_string()));
+    }
+
+    #[test]
+    fn boolean_queries_include_and_exclude_documents() {
+        let index = fixtures::index_of(&[
+            ("/r/a.txt", "rust compiler"),
+            ("/r/b.txt", "go runtime"),
+            ("/r/c.txt", "rust test suite"),
+            ("/r/d.txt", "go test helpers"),
+            ("/r/e.txt", "python scripts"),
+        ])
+        .unwrap();
+        let (engine, _history) = fixtures::engine(index, &["/r"]);
+        let search = |q: &str| result_paths(&engine.search(q, 10));
+
+        assert_eq!(search("(rust OR go) NOT test"), ["/r/a.txt", "/r/b.txt"]);
+        assert_eq!(search("rust OR python"), ["/r/a.txt", "/r/c.txt", "/r/e.txt"]);
+        assert_eq!(search("test AND go"), ["/r/d.txt"]);
+        assert_eq!(search("go -test"), ["/r/b.txt"]);
+        // Only exclusions: everything else matches
+        assert_eq!(search("NOT test NOT python"), ["/r/a.txt", "/r/b.txt"]);
+        // Filters combine with the boolean text
+        assert_eq!(search("(rust OR go) name:c"), ["/r/c.txt"]);
+    }
+
+    #[test]
+    fn kind_filters_keep_only_directories_or_files() {
+        let index = fixtures::index_of(&[
+            ("/r/report/", ""),
+            ("/r/work/report/", ""),
+            ("/r/report.txt", "quarterly report"),
+            ("/r/notes/", ""),
+            ("/r/notes/todo.md", "write the report"),
+        ])
+        .unwrap();
+        let (engine, _history) = fixtures::engine(index, &["/r"]);
+        let search = |q: &str| result_paths(&engine.search(q, 10));
+
+        assert_eq!(search("is:dir report"), ["/r/report", "/r/work/report"]);
+        assert_eq!(search("is:folder REPORT"), ["/r/report", "/r/work/report"]);
+        assert_eq!(search("report is:file"), ["/r/notes/todo.md", "/r/report.txt"]);
+        assert_eq!(search("is:dir"), ["/r/notes", "/r/report", "/r/work/report"]);
+        // An unknown kind is just a word
+        let fields = SchemaFields::new(&schema::build_schema());
+        let parsed = parse_query("is:symlink report", &fields);
+        assert!(parsed.filters.is_empty());
+        assert_eq!(parsed.plain, "is:symlink report");
+    }
+
+    #[test]
+    fn wildcards_only_in_file_name_like_words() {
+        let fields = SchemaFields::new(&schema::build_schema());
+        let globs = |q: &str| parse_query(q, &fields).name_globs.len();
+        for query in ["*.test.js", "app_??.rs", "report*", "file?.txt", "notes.tx?", "?eport"] {
+            assert_eq!(globs(query), 1, "{}", query);
+        }
+        for query in ["what?", "why??", "is this right?", "*", "??", "\"app_??.rs\"", "ext:r?"] {
+            assert_eq!(globs(query), 0, "{}", query);
+        }
+        let parsed = parse_query("what?", &fields);
+        assert!(parsed.filters.is_empty());
+        assert_eq!(parsed.plain, "what?");
+        // `name:` asks for the wildcard explicitly, unless quoted
+        assert_eq!(globs("name:what?"), 1);
+        assert_eq!(globs("name:\"what?\""), 0);
+    }
+
+    #[test]
+    fn wildcards_match_whole_names_ignoring_case() {
+        let index = fixtures::index_of(&[
+            ("/r/app_01.rs", ""),
+            ("/r/APP_02.RS", ""),
+            ("/r/app_1.rs", ""),
+            ("/r/whatever.txt", ""),
+            ("/r/what.txt", ""),
+        ])
+        .unwrap();
+        let fields = SchemaFields::new(&index.schema());
+        let names = |query: &str| {
+            let parsed = parse_query(query, &fields);
+            let mut names: Vec<&str> = ["app_01.rs", "APP_02.RS", "app_1.rs", "whatever.txt", "what.txt"]
+                .into_iter()
+                .filter(|name| parsed.name_globs.iter().all(|g| g.is_match(name)))
+                .collect();
+            names.sort();
+            names
+        };
+        assert_eq!(names("app_??.rs"), ["APP_02.RS", "app_01.rs"]);
+        assert_eq!(names("name:wha?.txt"), ["what.txt"]);
+        assert_eq!(names("name:what*"), ["what.txt", "whatever.txt"]);
+        assert_eq!(filtered(&index, &fields, "app_??.rs"), ["/r/APP_02.RS", "/r/app_01.rs"]);
+    }
+
+    #[test]
+    fn regex_alternation_keeps_each_branch_anchored() {
+        let index = fixtures::index_of(&[
+            ("/r/foobaz.txt", ""),
+            ("/r/notes.bar", ""),
+            ("/r/bar", ""),
+            ("/r/a_foo.txt", ""),
+            ("/r/bar.txt", ""),
+        ])
+        .unwrap();
+        let (engine, _history) = fixtures::engine(index, &["/r"]);
+        let search = |q: &str| result_paths(&engine.search(q, 10));
+
+        assert_eq!(search("/^foo|bar$/"), ["/r/bar", "/r/foobaz.txt", "/r/notes.bar"]);
+        assert_eq!(search("/(^foo|bar$)/"), ["/r/bar", "/r/foobaz.txt", "/r/notes.bar"]);
+        // `^` mid-pattern is fine, the index just can't narrow it down
+        assert_eq!(search("/baz|^bar/"), ["/r/bar", "/r/bar.txt", "/r/foobaz.txt"]);
+        assert!(has_top_level_alternation("a|b"));
+        assert!(!has_top_level_alternation("(a|b)c"));
+        assert!(!has_top_level_alternation(r"[|]\|x"));
+    }
+}
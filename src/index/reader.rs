@@ -1,130 +1,763 @@
-use std::path::PathBuf;
-use tantivy::collector::TopDocs;
-use tantivy::query::QueryParser;
-use tantivy::schema::Value;
-use tantivy::{Index, ReloadPolicy};
+use std::cell::{Cell, RefCell};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::path::{Path, PathBuf};
+use tantivy::collector::{Count, DocSetCollector, TopDocs};
+use tantivy::postings::Postings;
+use tantivy::query::{BooleanQuery, ConstScoreQuery, Occur, Query, QueryParser, TermQuery};
+use tantivy::schema::{IndexRecordOption, Value};
+use tantivy::snippet::SnippetGenerator;
+use tantivy::{DocAddress, DocSet, Index, IndexReader, ReloadPolicy, Searcher, Term};
 
+use super::history::OpenHistory;
+use super::query::{
+    fuzzy_distance, fuzzy_name_query, parse_query, path_prefix_query, regex_pattern, NameRegex,
+    ParsedQuery,
+};
 use super::schema::SchemaFields;
+use crate::config::{Config, DirectoryOrder, RankWeights};
+use crate::indexer::content::{self, TextExtensions};
+use crate::indexer::media;
 use crate::types::{MatchType, SearchResult};
 
+/// How many recent result sets are kept for instant repeat searches
+const CACHE_CAPACITY: usize = 8;
+
+/// Content is not stored in the index, so snippets mean re-reading the file;
+/// only the top content hits get one
+const SNIPPET_LIMIT: usize = 30;
+
+/// Target length of a content snippet in characters
+const SNIPPET_CHARS: usize = 160;
+
+/// Most BM25 candidates fetched at once to be re-ranked: three times the
+/// page size, up to this
+const CANDIDATE_WINDOW: usize = 600;
+
+/// Results of a "Find duplicates" query whose groups are looked up
+const DUPLICATE_CANDIDATES: usize = 600;
+
+/// With fewer exact hits than this, typo-tolerant file name matches are added
+const FUZZY_BELOW: usize = 5;
+
+/// One page of search results
+#[derive(Debug, Clone, Default)]
+pub struct SearchPage {
+    pub results: Vec<SearchResult>,
+    /// Documents matching the query. An estimate: typo matches aren't
+    /// counted, and `/regex/` or wildcard name patterns are only checked
+    /// for the results that are ranked
+    pub total_hits: usize,
+    /// More results can be loaded after this page
+    pub has_more: bool,
+}
+
 pub struct SearchEngine {
     index: Index,
+    reader: IndexReader,
+    /// Opstamp of the commit `reader` was last reloaded at; None until the
+    /// first search
+    loaded_opstamp: Cell<Option<u64>>,
     fields: SchemaFields,
+    rank_weights: RankWeights,
+    directory_order: DirectoryOrder,
+    max_file_size: u64,
+    truncate_large_files: bool,
+    /// Re-reading files for snippets follows the same text types as indexing
+    extra_text_extensions: Vec<String>,
+    exclude_text_extensions: Vec<String>,
+    fuzzy_matching: bool,
+    /// Parse the query text against file names and extensions only
+    names_only: bool,
+    /// Indexed folders; path depth is ranked relative to them
+    root_dirs: Vec<PathBuf>,
+    open_history: OpenHistory,
+    /// Folder every search is restricted to, if any
+    scope: Option<PathBuf>,
+    cache: RefCell<ResultCache>,
 }
 
 impl SearchEngine {
-    pub fn new(index: Index) -> Self {
+    pub fn new(index: Index, config: &Config) -> tantivy::Result<Self> {
         let fields = SchemaFields::new(&index.schema());
-        SearchEngine { index, fields }
+        let reader = index
+            .reader_builder()
+            .reload_policy(ReloadPolicy::Manual)
+            .try_into()?;
+        let mut engine = SearchEngine {
+            index,
+            reader,
+            loaded_opstamp: Cell::new(None),
+            fields,
+            rank_weights: RankWeights::default(),
+            directory_order: DirectoryOrder::Mixed,
+            max_file_size: 0,
+            truncate_large_files: false,
+            extra_text_extensions: Vec::new(),
+            exclude_text_extensions: Vec::new(),
+            fuzzy_matching: false,
+            names_only: false,
+            root_dirs: Vec::new(),
+            open_history: OpenHistory::load(&config.open_history_path),
+            scope: None,
+            cache: RefCell::new(ResultCache::default()),
+        };
+        engine.configure(config);
+        Ok(engine)
+    }
+
+    /// Apply the search-related settings from `config` (they can change at runtime)
+    pub fn configure(&mut self, config: &Config) {
+        self.rank_weights = config.rank_weights;
+        self.directory_order = config.directory_order;
+        self.max_file_size = config.max_file_size;
+        self.truncate_large_files = config.truncate_large_files;
+        self.extra_text_extensions = config.extra_text_extensions.clone();
+        self.exclude_text_extensions = config.exclude_text_extensions.clone();
+        self.fuzzy_matching = config.fuzzy_matching;
+        self.names_only = config.names_only;
+        self.root_dirs = config.root_dirs.clone();
+        // Cached results were ranked and ordered with the old settings
+        self.clear_cache();
     }
 
+    /// Remember that the user opened `path` from the results, so it ranks a
+    /// little higher in later searches
+    pub fn record_open(&mut self, path: &Path) {
+        let now = chrono::Utc::now().timestamp();
+        self.open_history.record(&path.to_string_lossy(), now);
+        self.clear_cache();
+    }
+
+    /// Pick up opens another engine recorded in the same open history file
+    pub fn reload_open_history(&mut self) {
+        self.open_history.reload();
+        self.clear_cache();
+    }
+
+    /// Restrict all following searches to files under `dir`, or lift the restriction
+    pub fn set_scope(&mut self, dir: Option<&Path>) {
+        self.scope = dir.map(Path::to_path_buf);
+        self.clear_cache();
+    }
+
+    /// Reload the reader if the index has a commit it hasn't seen. A reload
+    /// reopens every segment and starts a new searcher generation, emptying
+    /// the result cache, so it's skipped while the commit is the same one:
+    /// reading `meta.json` costs far less.
+    fn refresh_reader(&self) {
+        let opstamp = self.index.load_metas().ok().map(|metas| metas.opstamp);
+        if opstamp.is_some() && opstamp == self.loaded_opstamp.get() {
+            return;
+        }
+        match self.reader.reload() {
+            Ok(()) => self.loaded_opstamp.set(opstamp),
+            Err(e) => {
+                eprintln!("drozosearch: index reload failed ({}), searching the previous commit", e)
+            }
+        }
+    }
+
+    /// Forget cached result sets, so the next search hits the index
+    pub fn clear_cache(&self) {
+        self.cache.borrow_mut().entries.clear();
+    }
+
+    /// Tokenize free text the same way the content field was indexed.
+    fn content_terms(&self, text: &str) -> Vec<Term> {
+        let mut terms = Vec::new();
+        if let Ok(mut analyzer) = self.index.tokenizer_for_field(self.fields.content) {
+            let mut stream = analyzer.token_stream(text);
+            while stream.advance() {
+                terms.push(Term::from_field_text(self.fields.content, &stream.token().text));
+            }
+        }
+        terms
+    }
+
+    /// Total occurrences of the given terms in one document's content.
+    fn content_term_frequency(&self, searcher: &Searcher, addr: DocAddress, terms: &[Term]) -> u32 {
+        let segment = searcher.segment_reader(addr.segment_ord);
+        let inverted = match segment.inverted_index(self.fields.content) {
+            Ok(i) => i,
+            Err(_) => return 0,
+        };
+        terms
+            .iter()
+            .filter_map(|term| {
+                inverted
+                    .read_postings(term, IndexRecordOption::WithFreqs)
+                    .ok()
+                    .flatten()
+            })
+            .map(|mut postings| {
+                // Postings start at the term's first document, and can't
+                // seek backwards to an earlier one
+                if postings.doc() <= addr.doc_id && postings.seek(addr.doc_id) == addr.doc_id {
+                    postings.term_freq()
+                } else {
+                    0
+                }
+            })
+            .sum()
+    }
+
+    /// The first `limit` results of a query; see [`search_page`](Self::search_page).
     pub fn search(&self, query_str: &str, limit: usize) -> Vec<SearchResult> {
-        if query_str.trim().is_empty() {
-            return vec![];
+        self.search_page(query_str, 0, limit).results
+    }
+
+    /// Results `offset..offset + limit` of a query, answering pages of a
+    /// recent query from the cache.
+    ///
+    /// Candidates are fetched by BM25 in windows of three times `limit` (at
+    /// most [`CANDIDATE_WINDOW`]) and each window is re-ranked on its own, so
+    /// loading more never reorders results already returned, as long as
+    /// every page asks for the same `limit`.
+    pub fn search_page(&self, query_str: &str, offset: usize, limit: usize) -> SearchPage {
+        if query_str.trim().is_empty() || limit == 0 {
+            return SearchPage::default();
         }
 
-        let reader = match self
-            .index
-            .reader_builder()
-            .reload_policy(ReloadPolicy::OnCommitWithDelay)
-            .try_into()
-        {
-            Ok(r) => r,
-            Err(_) => return vec![],
+        // Pick up the latest commit before answering. A new commit means a new
+        // searcher generation, which invalidates everything cached so far
+        self.refresh_reader();
+        let searcher = self.reader.searcher();
+        let generation = searcher.generation().generation_id();
+        let window = (limit * 3).min(CANDIDATE_WINDOW);
+        let mut cache = self.cache.borrow_mut();
+        let Some(hits) = cache.get_or_insert_with(generation, query_str, window, || {
+            self.prepare(query_str, window)
+        }) else {
+            return SearchPage::default();
         };
 
-        let searcher = reader.searcher();
+        while hits.results.len() < offset + limit && !hits.exhausted {
+            self.rank_window(&searcher, hits);
+        }
+        let end = (offset + limit).min(hits.results.len());
+        let start = offset.min(end);
+        if hits.snippets_upto < end {
+            let from = hits.snippets_upto.max(start);
+            self.attach_snippets(&searcher, hits.query.as_ref(), &mut hits.results[from..end]);
+            hits.snippets_upto = end;
+        }
+        SearchPage {
+            results: hits.results[start..end].to_vec(),
+            total_hits: hits.total_hits,
+            has_more: end < hits.results.len() || !hits.exhausted,
+        }
+    }
 
-        let mut query_parser = QueryParser::for_index(
-            &self.index,
+    /// [`search`](Self::search), with the results as a JSON array for other
+    /// tools to consume
+    pub fn search_json(&self, query_str: &str, limit: usize) -> String {
+        let results = self.search(query_str, limit);
+        // Only fails for non-string map keys or failing `Serialize` impls,
+        // neither of which `SearchResult` has
+        serde_json::to_string(&results).unwrap_or_else(|_| "[]".to_string())
+    }
+
+    /// Files with the same contents as at least one other indexed file, one
+    /// group after another: the groups wasting the most space first, each in
+    /// path order. A query keeps the groups holding one of its results; an
+    /// empty query finds them all. Only files hashed while indexing (with
+    /// `detect_duplicates` on) can be found.
+    pub fn duplicates(&self, query_str: &str, limit: usize) -> Vec<SearchResult> {
+        self.refresh_reader();
+        let searcher = self.reader.searcher();
+        let hashes = if query_str.trim().is_empty() {
+            self.shared_hashes(&searcher)
+        } else {
+            let mut seen = HashSet::new();
+            let candidates = self.prepare(query_str, DUPLICATE_CANDIDATES).map(|mut hits| {
+                self.rank_window(&searcher, &mut hits);
+                hits.results
+            });
+            candidates
+                .into_iter()
+                .flatten()
+                .filter_map(|r| r.content_hash)
+                .filter(|hash| seen.insert(hash.clone()))
+                .collect()
+        };
+
+        // (bytes wasted, files) per group, ranked on sizes from the fast
+        // field before any documents are loaded
+        let mut groups: Vec<(u64, Vec<DocAddress>)> = Vec::new();
+        for hash in hashes {
+            let mut clauses: Vec<(Occur, Box<dyn Query>)> = vec![(
+                Occur::Must,
+                Box::new(TermQuery::new(
+                    Term::from_field_text(self.fields.content_hash, &hash),
+                    IndexRecordOption::Basic,
+                )),
+            )];
+            if let Some(scope) = &self.scope {
+                clauses.push((Occur::Must, path_prefix_query(scope)));
+            }
+            let Ok(docs) = searcher.search(&BooleanQuery::new(clauses), &DocSetCollector) else {
+                continue;
+            };
+            // Files of different sizes can't be the same, whatever their hash
+            let mut by_size: HashMap<u64, Vec<DocAddress>> = HashMap::new();
+            for addr in docs {
+                by_size.entry(file_size(&searcher, addr)).or_default().push(addr);
+            }
+            for (size, docs) in by_size {
+                if docs.len() > 1 {
+                    groups.push((size * (docs.len() as u64 - 1), docs));
+                }
+            }
+        }
+        groups.sort_by_key(|(wasted, _)| std::cmp::Reverse(*wasted));
+
+        let mut results = Vec::new();
+        for (_, docs) in groups {
+            if results.len() >= limit {
+                break;
+            }
+            let mut group: Vec<SearchResult> = docs
+                .into_iter()
+                .filter_map(|addr| searcher.doc(addr).ok())
+                .filter_map(|doc| self.stored_result(&doc))
+                .collect();
+            group.sort_by(|a, b| a.file_path.cmp(&b.file_path));
+            results.extend(group);
+        }
+        results
+    }
+
+    /// Hashes that more than one document has, from the term dictionaries.
+    /// Counts include deleted documents, so some may turn out to be unique.
+    fn shared_hashes(&self, searcher: &Searcher) -> Vec<String> {
+        let mut counts: HashMap<String, u32> = HashMap::new();
+        for segment in searcher.segment_readers() {
+            let Ok(inverted) = segment.inverted_index(self.fields.content_hash) else {
+                continue;
+            };
+            let Ok(mut terms) = inverted.terms().stream() else {
+                continue;
+            };
+            while terms.advance() {
+                if let Ok(hash) = std::str::from_utf8(terms.key()) {
+                    *counts.entry(hash.to_string()).or_default() += terms.value().doc_freq;
+                }
+            }
+        }
+        counts
+            .into_iter()
+            .filter(|(_, count)| *count > 1)
+            .map(|(hash, _)| hash)
+            .collect()
+    }
+
+    /// Parse a query and build what its windows are searched with. None
+    /// if there's nothing to search for or the query can't be parsed.
+    fn prepare(&self, query_str: &str, window: usize) -> Option<RankedHits> {
+        // `/pattern/` matches file names by regex instead of going through the parser
+        let name_regex = match regex_pattern(query_str).map(NameRegex::new) {
+            Some(Ok(regex)) => Some(regex),
+            Some(Err(_)) => return None,
+            None => None,
+        };
+        let mut parsed = match &name_regex {
+            Some(regex) => ParsedQuery {
+                text: String::new(),
+                plain: String::new(),
+                filters: vec![regex.query(self.fields.file_path)],
+                has_name_filter: true,
+                name_globs: Vec::new(),
+            },
+            None => parse_query(query_str, &self.fields),
+        };
+        if self.directory_order == DirectoryOrder::HideDirs {
+            // Cheaper to exclude in the query than to fetch and drop them
+            parsed.filters.push(Box::new(TermQuery::new(
+                Term::from_field_u64(self.fields.is_dir, 0),
+                IndexRecordOption::Basic,
+            )));
+        }
+        if let Some(scope) = &self.scope {
+            parsed.filters.push(path_prefix_query(scope));
+        }
+
+        // Names-only skips the content postings, by far the largest to scan
+        let text_fields = if self.names_only {
+            vec![self.fields.file_name, self.fields.extension]
+        } else {
             vec![
                 self.fields.file_name,
                 self.fields.content,
+                self.fields.metadata,
+                self.fields.exif_text,
+                self.fields.tags,
                 self.fields.extension,
-            ],
-        );
+            ]
+        };
+        let mut query_parser = QueryParser::for_index(&self.index, text_fields);
         query_parser.set_field_boost(self.fields.file_name, 3.0);
+        query_parser.set_field_boost(self.fields.metadata, 1.5);
+        query_parser.set_field_boost(self.fields.exif_text, 1.5);
+        query_parser.set_field_boost(self.fields.tags, 1.5);
         query_parser.set_field_boost(self.fields.extension, 1.5);
 
-        let query = match query_parser.parse_query(query_str) {
-            Ok(q) => q,
-            Err(_) => {
-                let escaped: String = query_str
-                    .chars()
-                    .map(|c| {
-                        if "+-&|!(){}[]^\"~*?:\\/".contains(c) {
-                            format!("\\{}", c)
-                        } else {
-                            c.to_string()
-                        }
-                    })
-                    .collect();
-                match query_parser.parse_query(&escaped) {
-                    Ok(q) => q,
-                    Err(_) => return vec![],
+        let text_query: Option<Box<dyn Query>> = if parsed.text.is_empty() {
+            None
+        } else {
+            match query_parser.parse_query(&parsed.text) {
+                Ok(q) => Some(q),
+                Err(_) => {
+                    let escaped: String = parsed
+                        .text
+                        .chars()
+                        .map(|c| {
+                            if "+-&|!(){}[]^\"~*?:\\/".contains(c) {
+                                format!("\\{}", c)
+                            } else {
+                                c.to_string()
+                            }
+                        })
+                        .collect();
+                    match query_parser.parse_query(&escaped) {
+                        Ok(q) => Some(q),
+                        Err(_) => return None,
+                    }
                 }
             }
         };
 
-        // Retrieve more candidates than needed — we'll re-rank and trim
-        let retrieve_limit = (limit * 3).min(600);
-        let top_docs = match searcher.search(&query, &TopDocs::with_limit(retrieve_limit)) {
-            Ok(docs) => docs,
-            Err(_) => return vec![],
+        let query: Box<dyn Query> = match text_query {
+            Some(q) if parsed.filters.is_empty() => q,
+            Some(q) => {
+                let mut clauses = filter_clauses(&parsed.filters);
+                clauses.push((Occur::Must, q));
+                Box::new(BooleanQuery::new(clauses))
+            }
+            None if parsed.filters.is_empty() => return None,
+            None => Box::new(BooleanQuery::new(filter_clauses(&parsed.filters))),
         };
 
-        let query_lower = query_str.to_lowercase();
+        Some(RankedHits {
+            query,
+            parsed,
+            name_regex,
+            window,
+            fetched: 0,
+            exhausted: false,
+            total_hits: 0,
+            results: Vec::new(),
+            snippets_upto: 0,
+        })
+    }
+
+    /// Fetch the next window of BM25 candidates, re-rank it and append it
+    /// to the results.
+    fn rank_window(&self, searcher: &Searcher, hits: &mut RankedHits) {
+        let collector = (TopDocs::with_limit(hits.window).and_offset(hits.fetched), Count);
+        let mut top_docs = match searcher.search(&hits.query, &collector) {
+            Ok((docs, count)) => {
+                hits.total_hits = count;
+                docs
+            }
+            Err(_) => {
+                hits.exhausted = true;
+                return;
+            }
+        };
+        let first_window = hits.fetched == 0;
+        hits.fetched += hits.window;
+        hits.exhausted = top_docs.len() < hits.window;
+        let parsed = &hits.parsed;
+        let name_regex = &hits.name_regex;
+
+        // Hardly anything found: maybe a typo. Add file names within an edit
+        // or two; the ranking penalizes each edit so exact hits stay on top
+        let mut fuzzy_docs = HashSet::new();
+        if self.fuzzy_matching && first_window && top_docs.len() < FUZZY_BELOW {
+            if let Some(fuzzy) = fuzzy_name_query(&self.fields, &parsed.plain) {
+                let mut clauses = filter_clauses(&parsed.filters);
+                clauses.push((Occur::Must, fuzzy));
+                let fuzzy_query = BooleanQuery::new(clauses);
+                if let Ok(docs) = searcher.search(&fuzzy_query, &TopDocs::with_limit(hits.window)) {
+                    for (score, addr) in docs {
+                        if !top_docs.iter().any(|(_, a)| *a == addr) {
+                            fuzzy_docs.insert(addr);
+                            top_docs.push((score, addr));
+                        }
+                    }
+                }
+            }
+        }
+
+        let query_lower = parsed.plain.to_lowercase();
+        let filter_only = query_lower.is_empty();
         let now_ts = chrono::Utc::now().timestamp();
+        let content_terms = self.content_terms(&parsed.plain);
 
         let mut results: Vec<SearchResult> = top_docs
             .into_iter()
             .filter_map(|(bm25_score, doc_address)| {
                 let doc: tantivy::TantivyDocument = searcher.doc(doc_address).ok()?;
+                let mut result = self.stored_result(&doc)?;
+                // Frontmatter, media details, EXIF and music tags all count as metadata
+                let tags = doc.get_first(self.fields.tags).and_then(|v| v.as_str());
+                let metadata_lower: Vec<String> = [self.fields.metadata, self.fields.exif_text, self.fields.tags]
+                    .into_iter()
+                    .filter_map(|field| doc.get_first(field).and_then(|v| v.as_str()))
+                    .map(|s| s.to_lowercase())
+                    .collect();
 
-                let file_name = doc
-                    .get_first(self.fields.file_name)?
-                    .as_str()?
-                    .to_string();
-                let file_path_str = doc
-                    .get_first(self.fields.file_path)?
-                    .as_str()?
-                    .to_string();
-                let file_size = doc.get_first(self.fields.file_size)?.as_u64()?;
-                let modified = doc.get_first(self.fields.modified)?.as_i64()?;
-                let is_dir_val = doc.get_first(self.fields.is_dir)?.as_u64()?;
-                let is_dir = is_dir_val == 1;
-
-                let file_name_lower = file_name.to_lowercase();
-                let path = PathBuf::from(&file_path_str);
+                if name_regex.as_ref().is_some_and(|r| !r.is_match(&result.file_name)) {
+                    return None;
+                }
+                if parsed.name_globs.iter().any(|g| !g.is_match(&result.file_name)) {
+                    return None;
+                }
+                let file_name_lower = result.file_name.to_lowercase();
+                let file_path_str = result.file_path.to_string_lossy();
+                let fuzzy = fuzzy_docs.contains(&doc_address);
 
                 // ── Determine match type ──
-                let match_type = if file_name_lower.contains(&query_lower) {
+                result.match_type = if filter_only && parsed.has_name_filter {
                     MatchType::FileName
+                } else if filter_only {
+                    // Matched purely on size/extension/etc. filters
+                    MatchType::Metadata
+                } else if self.names_only || fuzzy || file_name_lower.contains(&query_lower) {
+                    MatchType::FileName
+                } else if metadata_lower.iter().any(|m| m.contains(&query_lower)) {
+                    MatchType::Metadata
                 } else {
                     MatchType::Content
                 };
 
+                // A music tag hit shows the matching tag where a content snippet would go
+                if let Some(tags) = tags.filter(|_| matches!(result.match_type, MatchType::Metadata) && !filter_only) {
+                    (result.content_snippet, result.snippet_highlights) = tag_snippet(tags, &query_lower);
+                }
+
+                // How often the query terms occur in the body (content hits only)
+                let term_freq = if matches!(result.match_type, MatchType::Content) {
+                    self.content_term_frequency(searcher, doc_address, &content_terms)
+                } else {
+                    0
+                };
+
                 // ── Compute composite score ──
-                let final_score =
-                    compute_rank(bm25_score, &query_lower, &file_name_lower, &path, modified, is_dir, now_ts);
-
-                Some(SearchResult {
-                    file_name,
-                    file_path: path,
-                    match_type,
-                    file_size,
-                    modified,
-                    score: final_score,
-                    content_snippet: None,
-                    is_dir,
-                })
+                result.score = compute_rank(
+                    bm25_score,
+                    &query_lower,
+                    &file_name_lower,
+                    relative_depth(&result.file_path, &self.root_dirs),
+                    result.modified,
+                    result.is_dir,
+                    now_ts,
+                    term_freq,
+                    self.open_history.boost(&file_path_str, now_ts),
+                    if fuzzy {
+                        fuzzy_distance(&query_lower, &file_name_lower)
+                    } else {
+                        0
+                    },
+                    &self.rank_weights,
+                );
+
+                Some(result)
             })
             .collect();
 
-        // Sort by our composite score (highest first)
-        results.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(std::cmp::Ordering::Equal));
-        results.truncate(limit);
-        results
+        // Sort the window by our composite score (highest first), grouping directories if configured
+        let by_score = |a: &SearchResult, b: &SearchResult| {
+            b.score.partial_cmp(&a.score).unwrap_or(std::cmp::Ordering::Equal)
+        };
+        match self.directory_order {
+            DirectoryOrder::DirsFirst => {
+                results.sort_by(|a, b| b.is_dir.cmp(&a.is_dir).then_with(|| by_score(a, b)))
+            }
+            DirectoryOrder::FilesFirst => {
+                results.sort_by(|a, b| a.is_dir.cmp(&b.is_dir).then_with(|| by_score(a, b)))
+            }
+            DirectoryOrder::Mixed | DirectoryOrder::HideDirs => results.sort_by(by_score),
+        }
+        hits.results.extend(results);
+    }
+
+    /// A result from a document's stored fields, not yet classified or scored
+    fn stored_result(&self, doc: &tantivy::TantivyDocument) -> Option<SearchResult> {
+        let text = |field| doc.get_first(field).and_then(|v| v.as_str()).map(str::to_string);
+        let number = |field| doc.get_first(field).and_then(|v| v.as_u64());
+        Some(SearchResult {
+            file_name: text(self.fields.file_name)?,
+            file_path: PathBuf::from(text(self.fields.file_path)?),
+            match_type: MatchType::Metadata,
+            file_size: number(self.fields.file_size)?,
+            modified: doc.get_first(self.fields.modified)?.as_i64()?,
+            score: 0.0,
+            content_snippet: None,
+            snippet_highlights: Vec::new(),
+            is_dir: number(self.fields.is_dir)? == 1,
+            media_summary: media::summary(
+                number(self.fields.width),
+                number(self.fields.height),
+                number(self.fields.duration),
+                doc.get_first(self.fields.codec).and_then(|v| v.as_str()),
+            ),
+            mime_type: text(self.fields.mime_type),
+            content_truncated: doc.get_first(self.fields.content_truncated).is_some(),
+            content_hash: text(self.fields.content_hash),
+            owner: text(self.fields.owner).filter(|s| !s.is_empty()),
+            group: text(self.fields.group).filter(|s| !s.is_empty()),
+        })
+    }
+
+    /// Fill in `content_snippet` for the top content hits by re-reading the
+    /// files. Files that are gone or no longer readable as text get none.
+    fn attach_snippets(&self, searcher: &Searcher, query: &dyn Query, results: &mut [SearchResult]) {
+        let Ok(mut generator) = SnippetGenerator::create(searcher, query, self.fields.content) else {
+            return;
+        };
+        generator.set_max_num_chars(SNIPPET_CHARS);
+
+        for result in results
+            .iter_mut()
+            .filter(|r| matches!(r.match_type, MatchType::Content))
+            .take(SNIPPET_LIMIT)
+        {
+            let extensions = TextExtensions {
+                extra: &self.extra_text_extensions,
+                excluded: &self.exclude_text_extensions,
+            };
+            let Ok(Some(content)) = content::read_content(
+                &result.file_path,
+                self.max_file_size,
+                self.truncate_large_files,
+                extensions,
+            ) else {
+                continue;
+            };
+            let snippet = generator.snippet(&content.text);
+            if snippet.is_empty() {
+                continue;
+            }
+            // Same-length replacement keeps the highlight byte ranges valid
+            let line = snippet
+                .fragment()
+                .replace(['\n', '\r', '\t'], " ");
+            result.content_snippet = Some(line);
+            result.snippet_highlights = snippet.highlighted().to_vec();
+        }
+    }
+}
+
+/// Number of path components below the indexed folder `path` is in. With
+/// nested roots the innermost one counts; a path under no root (the roots
+/// changed since it was indexed) counts from the filesystem root.
+fn relative_depth(path: &Path, root_dirs: &[PathBuf]) -> usize {
+    root_dirs
+        .iter()
+        .filter_map(|root| path.strip_prefix(root).ok())
+        .map(|rest| rest.components().count())
+        .min()
+        .unwrap_or_else(|| path.components().count())
+}
+
+/// File size from the fast field, without loading the document
+fn file_size(searcher: &Searcher, addr: DocAddress) -> u64 {
+    searcher
+        .segment_reader(addr.segment_ord)
+        .fast_fields()
+        .u64("file_size")
+        .ok()
+        .and_then(|column| column.first(addr.doc_id))
+        .unwrap_or(0)
+}
+
+/// The tag line holding `query_lower`, e.g. `artist: Miles Davis`, with the
+/// match highlighted
+fn tag_snippet(tags: &str, query_lower: &str) -> (Option<String>, Vec<std::ops::Range<usize>>) {
+    for line in tags.lines() {
+        let lower = line.to_lowercase();
+        if let Some(start) = lower.find(query_lower) {
+            // Lowercasing can change byte lengths outside ASCII; highlight only when it didn't
+            let mut highlights = Vec::new();
+            if lower.len() == line.len() {
+                highlights.push(start..start + query_lower.len());
+            }
+            return (Some(line.to_string()), highlights);
+        }
+    }
+    (None, Vec::new())
+}
+
+/// Filters are required but constant-scored, so BM25 comes from the free text only
+fn filter_clauses(filters: &[Box<dyn Query>]) -> Vec<(Occur, Box<dyn Query>)> {
+    filters
+        .iter()
+        .map(|f| {
+            let query: Box<dyn Query> = Box::new(ConstScoreQuery::new(f.box_clone(), 0.0));
+            (Occur::Must, query)
+        })
+        .collect()
+}
+
+/// A query's results ranked so far, and what's needed to rank more
+struct RankedHits {
+    query: Box<dyn Query>,
+    parsed: ParsedQuery,
+    name_regex: Option<NameRegex>,
+    /// BM25 candidates fetched and re-ranked at a time
+    window: usize,
+    /// Candidates fetched so far
+    fetched: usize,
+    /// The last window came back short; there are no more candidates
+    exhausted: bool,
+    /// Documents matching the query, from the last window's search
+    total_hits: usize,
+    results: Vec<SearchResult>,
+    /// Results before this index have been given their snippets
+    snippets_upto: usize,
+}
+
+/// Small LRU of recent queries' results, valid for a single searcher generation.
+#[derive(Default)]
+struct ResultCache {
+    generation: u64,
+    /// (query, window) → results, most recently used last
+    entries: VecDeque<((String, usize), RankedHits)>,
+}
+
+impl ResultCache {
+    /// The cached results of a query, or a new entry from `prepare` (None if
+    /// the query can't be searched)
+    fn get_or_insert_with(
+        &mut self,
+        generation: u64,
+        query: &str,
+        window: usize,
+        prepare: impl FnOnce() -> Option<RankedHits>,
+    ) -> Option<&mut RankedHits> {
+        if generation != self.generation {
+            self.entries.clear();
+            self.generation = generation;
+        }
+        match self
+            .entries
+            .iter()
+            .position(|((q, w), _)| q == query && *w == window)
+        {
+            Some(pos) => {
+                let entry = self.entries.remove(pos)?;
+                self.entries.push_back(entry);
+            }
+            None => {
+                let hits = prepare()?;
+                if self.entries.len() >= CACHE_CAPACITY {
+                    self.entries.pop_front();
+                }
+                self.entries.push_back(((query.to_string(), window), hits));
+            }
+        }
+        self.entries.back_mut().map(|(_, hits)| hits)
     }
 }
 
@@ -136,19 +769,27 @@ impl SearchEngine {
 ///   3. Name starts-with      — bonus if file name starts with query
 ///   4. Name contains         — moderate bonus for substring match in name
 ///   5. Recency               — recently modified files score higher
-///   6. Path depth penalty    — deeply nested files score lower
+///   6. Path depth penalty    — files nested deep inside their root score lower
 ///   7. File > directory      — files are usually more relevant
+///   8. Term frequency        — content hits that mention the query often
+///   9. Open history          — files the user opened from results before
+///  10. Fuzzy distance        — typo matches rank below everything exact
 ///
-/// All signals are combined as weighted sum. Weights were tuned by hand
-/// to produce intuitive results for common search patterns.
+/// All signals are combined as a weighted sum using `weights`. The defaults
+/// were tuned by hand to produce intuitive results for common search patterns.
+#[allow(clippy::too_many_arguments)]
 fn compute_rank(
     bm25: f32,
     query_lower: &str,
     file_name_lower: &str,
-    path: &std::path::Path,
+    depth: usize,
     modified_ts: i64,
     is_dir: bool,
     now_ts: i64,
+    term_freq: u32,
+    history_boost: f32,
+    fuzzy_edits: u32,
+    weights: &RankWeights,
 ) -> f32 {
     // ── 1. Normalize BM25 to roughly 0..1 range ──
     // BM25 scores typically range 0..30 depending on corpus. Sigmoid squash.
@@ -156,7 +797,10 @@ fn compute_rank(
 
     // ── 2. Exact name match (massive bonus) ──
     // "main.rs" searching "main.rs" → top result
-    let exact_bonus = if file_name_lower == query_lower {
+    // (filter-only queries have no text, so no name signals apply)
+    let exact_bonus = if query_lower.is_empty() {
+        0.0
+    } else if file_name_lower == query_lower {
         1.0
     } else {
         // Also check without extension: searching "main" matches "main.rs"
@@ -173,7 +817,10 @@ fn compute_rank(
 
     // ── 3. Name starts-with bonus ──
     // Searching "read" → README.md beats thread_pool.rs
-    let starts_with_bonus = if exact_bonus == 0.0 && file_name_lower.starts_with(query_lower) {
+    let starts_with_bonus = if exact_bonus == 0.0
+        && !query_lower.is_empty()
+        && file_name_lower.starts_with(query_lower)
+    {
         0.5
     } else {
         0.0
@@ -183,6 +830,7 @@ fn compute_rank(
     // Any substring match in file name is better than content-only match
     let contains_bonus = if exact_bonus == 0.0
         && starts_with_bonus == 0.0
+        && !query_lower.is_empty()
         && file_name_lower.contains(query_lower)
     {
         0.3
@@ -198,23 +846,252 @@ fn compute_rank(
     let recency = 1.0 / (1.0 + (age_hours / 24.0).ln().max(0.0)) as f32;
 
     // ── 6. Path depth penalty ──
-    // Fewer components = more likely to be a "main" file.
-    // ~/project/src/main.rs (4 components) scores higher than
-    // ~/backup/old/archive/2019/project/src/main.rs (8 components)
-    let depth = path.components().count() as f32;
+    // Fewer components below the indexed folder = more likely to be a "main" file.
+    // project/src/main.rs (3 components) scores higher than
+    // backup/old/archive/2019/project/src/main.rs (7 components),
+    // however long the path of the indexed folder itself is
+    let depth = depth as f32;
     let depth_penalty = 1.0 / (1.0 + (depth - 3.0).max(0.0) * 0.08);
 
     // ── 7. File vs directory ──
-    let type_bonus: f32 = if is_dir { 0.0 } else { 0.1 };
+    let type_bonus: f32 = if is_dir { 0.0 } else { 1.0 };
+
+    // ── 8. Term frequency ──
+    // Saturating: 1 mention → ~0.17, 5 → 0.5, 20 → 0.8. Favors the file that is
+    // actually about the topic over one that mentions it in passing.
+    let tf = term_freq as f32;
+    let term_freq_signal = tf / (tf + 5.0);
+
+    // ── 10. Fuzzy distance ──
+    // "recieve" finding receive.txt is useful, but each edit costs more than
+    // any name bonus a fuzzy hit could still earn
+    let fuzzy_penalty = fuzzy_edits as f32;
 
     // ── Weighted combination ──
-    let score = bm25_norm * 2.0        // baseline relevance
-        + exact_bonus * 5.0            // exact match dominates
-        + starts_with_bonus * 2.0      // prefix match is strong
-        + contains_bonus * 1.5         // substring in name is good
-        + recency * 0.8               // recent files get a bump
-        + depth_penalty * 0.4         // shallow paths preferred
-        + type_bonus;                  // files over directories
+    let score = bm25_norm * weights.relevance              // baseline relevance
+        + exact_bonus * weights.exact_name                 // exact match dominates
+        + starts_with_bonus * weights.name_prefix          // prefix match is strong
+        + contains_bonus * weights.name_contains           // substring in name is good
+        + recency * weights.recency                        // recent files get a bump
+        + depth_penalty * weights.shallow_path             // shallow paths preferred
+        + type_bonus * weights.file_over_dir               // files over directories
+        + term_freq_signal * weights.term_frequency        // topic density for content hits
+        + history_boost * weights.open_history             // files opened before
+        - fuzzy_penalty * weights.fuzzy_edit;              // typo matches below exact ones
 
     score
 }
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+
+    use tantivy::query::AllQuery;
+
+    use super::*;
+    use crate::index::fixtures;
+    use crate::index::schema;
+    use crate::index::writer::IndexWriter;
+
+    fn paths(results: &[SearchResult]) -> Vec<String> {
+        results.iter().map(|r| r.file_path.to_string_lossy().into_owned()).collect()
+    }
+
+    fn empty_hits(window: usize) -> RankedHits {
+        let fields = SchemaFields::new(&schema::build_schema());
+        RankedHits {
+            query: Box::new(AllQuery),
+            parsed: parse_query("", &fields),
+            name_regex: None,
+            window,
+            fetched: 0,
+            exhausted: true,
+            total_hits: 0,
+            results: Vec::new(),
+            snippets_upto: 0,
+        }
+    }
+
+    #[test]
+    fn cache_hits_misses_and_generations() {
+        let mut cache = ResultCache::default();
+        let prepared = Cell::new(0);
+        let prepare = |window| {
+            prepared.set(prepared.get() + 1);
+            Some(empty_hits(window))
+        };
+
+        cache.get_or_insert_with(1, "report", 60, || prepare(60));
+        assert_eq!(prepared.get(), 1);
+        // Same query and window: answered from the cache
+        cache.get_or_insert_with(1, "report", 60, || prepare(60));
+        assert_eq!(prepared.get(), 1);
+        // Another window of the same query is another entry
+        cache.get_or_insert_with(1, "report", 600, || prepare(600));
+        assert_eq!(prepared.get(), 2);
+        assert_eq!(cache.entries.len(), 2);
+        // A new searcher generation drops everything
+        cache.get_or_insert_with(2, "report", 60, || prepare(60));
+        assert_eq!(prepared.get(), 3);
+        assert_eq!(cache.entries.len(), 1);
+    }
+
+    #[test]
+    fn cache_is_bounded() {
+        let mut cache = ResultCache::default();
+        for i in 0..CACHE_CAPACITY + 3 {
+            cache.get_or_insert_with(1, &format!("query {}", i), 60, || Some(empty_hits(60)));
+        }
+        assert_eq!(cache.entries.len(), CACHE_CAPACITY);
+        // The oldest went first
+        assert_eq!(cache.entries.front().unwrap().0 .0, "query 3");
+    }
+
+    #[test]
+    fn commit_invalidates_cached_results() {
+        let index = fixtures::index_of(&[("/r/alpha.txt", "")]).unwrap();
+        let (engine, _history) = fixtures::engine(index.clone(), &["/r"]);
+
+        let first = engine.search("alpha", 10);
+        assert_eq!(paths(&first), ["/r/alpha.txt"]);
+        let generation = engine.cache.borrow().generation;
+        // Hit: the same entry answers, nothing is added
+        assert_eq!(paths(&engine.search("alpha", 10)), paths(&first));
+        assert_eq!(engine.cache.borrow().entries.len(), 1);
+        // Miss: a different page size is a different candidate window
+        engine.search("alpha", 100);
+        assert_eq!(engine.cache.borrow().entries.len(), 2);
+
+        let mut writer = IndexWriter::new(&index, 15_000_000, 10_000, None).unwrap();
+        fixtures::add_files(&mut writer, &[("/r/alpha_two.txt", "")]).unwrap();
+        writer.commit().unwrap();
+
+        // The reader reloads at the new commit, a new generation
+        let after = engine.search("alpha", 10);
+        assert_ne!(engine.cache.borrow().generation, generation);
+        assert_eq!(engine.cache.borrow().entries.len(), 1);
+        assert_eq!(after.len(), 2);
+    }
+
+    #[test]
+    fn opened_file_outranks_an_equivalent_unopened_one() {
+        let index = fixtures::index_of(&[
+            ("/r/a/report.txt", "quarterly numbers"),
+            ("/r/b/report.txt", "quarterly numbers"),
+        ])
+        .unwrap();
+        let (mut engine, _history) = fixtures::engine(index, &["/r"]);
+
+        let before = engine.search("report", 10);
+        assert_eq!(before.len(), 2);
+        assert_eq!(before[0].score, before[1].score);
+
+        // Whichever came second is the one opened
+        let opened = before[1].file_path.clone();
+        engine.record_open(&opened);
+        let after = engine.search("report", 10);
+        assert_eq!(after[0].file_path, opened);
+        assert!(after[0].score > after[1].score);
+    }
+
+    #[test]
+    fn depth_is_measured_from_the_indexed_folder() {
+        let short = "/r";
+        let long = "/home/a_rather_long_user_name/Documents/work/archive";
+        let index = fixtures::index_of(&[
+            ("/r/project/src/notes.md", "meeting"),
+            ("/home/a_rather_long_user_name/Documents/work/archive/project/src/notes.md", "meeting"),
+        ])
+        .unwrap();
+        let (engine, _history) = fixtures::engine(index, &[short, long]);
+
+        let results = engine.search("notes", 10);
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].score, results[1].score);
+
+        assert_eq!(relative_depth(Path::new("/r/project/src/notes.md"), &engine.root_dirs), 3);
+        // Outside every root the whole path counts
+        assert_eq!(relative_depth(Path::new("/elsewhere/notes.md"), &engine.root_dirs), 3);
+    }
+
+    #[test]
+    fn denser_content_match_ranks_higher() {
+        let index = fixtures::index_of(&[
+            ("/r/a/passing.md", "tokio and some other words about unrelated things here"),
+            ("/r/b/topic.md", "tokio tokio and tokio runtime tokio tasks tokio here"),
+        ])
+        .unwrap();
+        let (mut engine, history) = fixtures::engine(index, &["/r"]);
+        let scores = |engine: &SearchEngine| {
+            let results = engine.search("tokio", 10);
+            assert_eq!(paths(&results)[0], "/r/b/topic.md");
+            results[0].score - results[1].score
+        };
+        let with_density = scores(&engine);
+
+        // Without BM25, term frequency alone still separates them...
+        let mut config = Config {
+            root_dirs: vec![PathBuf::from("/r")],
+            open_history_path: history.path().join("open_history.tsv"),
+            ..Config::default()
+        };
+        config.rank_weights.relevance = 0.0;
+        engine.configure(&config);
+        assert!(scores(&engine) > 0.0);
+
+        // ...and weighing it at zero leaves only BM25's share of the gap
+        config.rank_weights.relevance = RankWeights::default().relevance;
+        config.rank_weights.term_frequency = 0.0;
+        engine.configure(&config);
+        let without = engine.search("tokio", 10);
+        assert!(with_density > without[0].score - without[1].score);
+    }
+
+    #[test]
+    fn term_frequency_ignores_terms_only_in_later_documents() {
+        let index = fixtures::index_of(&[
+            ("/r/a.txt", "alpha alpha"),
+            ("/r/b.txt", "beta"),
+            ("/r/c.txt", "alpha beta beta"),
+        ])
+        .unwrap();
+        let (engine, _history) = fixtures::engine(index, &["/r"]);
+        let mut found = paths(&engine.search("alpha OR beta", 10));
+        found.sort();
+        assert_eq!(found, ["/r/a.txt", "/r/b.txt", "/r/c.txt"]);
+    }
+
+    #[test]
+    fn directory_order_modes() {
+        let index = fixtures::index_of(&[
+            ("/r/report/", ""),
+            ("/r/old/report_archive/", ""),
+            ("/r/report.txt", ""),
+            ("/r/notes/report_draft.md", ""),
+        ])
+        .unwrap();
+        let (mut engine, history) = fixtures::engine(index, &["/r"]);
+        let mut search = |order| {
+            let config = Config {
+                root_dirs: vec![PathBuf::from("/r")],
+                open_history_path: history.path().join("open_history.tsv"),
+                directory_order: order,
+                ..Config::default()
+            };
+            engine.configure(&config);
+            engine.search("report", 10)
+        };
+        let kinds = |results: &[SearchResult]| results.iter().map(|r| r.is_dir).collect::<Vec<_>>();
+
+        let mixed = search(DirectoryOrder::Mixed);
+        assert_eq!(mixed.len(), 4);
+        assert!(mixed.windows(2).all(|pair| pair[0].score >= pair[1].score));
+
+        assert_eq!(kinds(&search(DirectoryOrder::DirsFirst)), [true, true, false, false]);
+        assert_eq!(kinds(&search(DirectoryOrder::FilesFirst)), [false, false, true, true]);
+
+        let hidden = search(DirectoryOrder::HideDirs);
+        assert_eq!(hidden.len(), 2);
+        assert!(hidden.iter().all(|r| !r.is_dir));
+    }
+}
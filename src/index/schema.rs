@@ -1,10 +1,37 @@
 use tantivy::schema::*;
+use tantivy::tokenizer::{LowerCaser, SimpleTokenizer, TextAnalyzer};
+use tantivy::Index;
+
+/// Tokenizer for file names: every run of letters/digits is its own token,
+/// so `user-service.config.js` is `user` `service` `config` `js` and
+/// `my.long.file.name.txt` is `my` `long` `file` `name` `txt`. Unlike the
+/// default tokenizer it keeps long tokens (hashes, generated names).
+pub const FILE_NAME_TOKENIZER: &str = "filename";
+
+/// Register the custom tokenizers the schema refers to. Must be called on
+/// every `Index` handle before writing or searching.
+pub fn register_tokenizers(index: &Index) {
+    index.tokenizers().register(
+        FILE_NAME_TOKENIZER,
+        TextAnalyzer::builder(SimpleTokenizer::default())
+            .filter(LowerCaser)
+            .build(),
+    );
+}
 
 pub fn build_schema() -> Schema {
     let mut builder = Schema::builder();
 
-    // File name - tokenized for partial matching, stored for display
-    builder.add_text_field("file_name", TEXT | STORED);
+    // File name - split on `.`, `-`, `_` and spaces for partial matching,
+    // stored for display
+    let name_options = TextOptions::default()
+        .set_stored()
+        .set_indexing_options(
+            TextFieldIndexing::default()
+                .set_tokenizer(FILE_NAME_TOKENIZER)
+                .set_index_option(IndexRecordOption::WithFreqsAndPositions),
+        );
+    builder.add_text_field("file_name", name_options);
 
     // Full file path - stored for display, indexed as raw string
     let path_options = TextOptions::default()
@@ -19,9 +46,25 @@ pub fn build_schema() -> Schema {
     // File extension - indexed as single token for filtering
     builder.add_text_field("extension", STRING | STORED);
 
+    // MIME type sniffed from the content (e.g. "image/png") - single token
+    // for `type:` filters
+    builder.add_text_field("mime_type", STRING | STORED);
+
     // File content - tokenized full-text, NOT stored to save disk space
     builder.add_text_field("content", TEXT);
 
+    // Extracted metadata (e.g. Markdown frontmatter) - small, stored so we
+    // can tell when a hit came from it
+    builder.add_text_field("metadata", TEXT | STORED);
+
+    // Photo EXIF details (camera, capture time, GPS presence) - stored so we
+    // can tell when a hit came from it
+    builder.add_text_field("exif_text", TEXT | STORED);
+
+    // Music tags (artist, album, title, genre) - stored so we can tell when
+    // a hit came from them and show the matching tag
+    builder.add_text_field("tags", TEXT | STORED);
+
     // File size in bytes
     builder.add_u64_field("file_size", INDEXED | STORED | FAST);
 
@@ -34,9 +77,27 @@ pub fn build_schema() -> Schema {
     // Permissions string (e.g. "rwxr-xr-x")
     builder.add_text_field("permissions", STRING | STORED);
 
+    // Owning user and group names, single tokens for `owner:` filters;
+    // empty on platforms without Unix ownership
+    builder.add_text_field("owner", STRING | STORED);
+    builder.add_text_field("group", STRING | STORED);
+
     // Is directory flag
     builder.add_u64_field("is_dir", INDEXED | STORED);
 
+    // Hash of the file's bytes (hex), for grouping duplicates; only set
+    // when duplicate detection is on
+    builder.add_text_field("content_hash", STRING | STORED);
+
+    // Set when only the start of a large file's content was indexed
+    builder.add_u64_field("content_truncated", STORED);
+
+    // Media details (images, audio, video); only set when media indexing is on
+    builder.add_u64_field("width", STORED | FAST);
+    builder.add_u64_field("height", STORED | FAST);
+    builder.add_u64_field("duration", STORED | FAST);
+    builder.add_text_field("codec", STRING | STORED);
+
     builder.build()
 }
 
@@ -45,12 +106,24 @@ pub struct SchemaFields {
     pub file_name: Field,
     pub file_path: Field,
     pub extension: Field,
+    pub mime_type: Field,
     pub content: Field,
+    pub metadata: Field,
+    pub exif_text: Field,
+    pub tags: Field,
     pub file_size: Field,
     pub modified: Field,
     pub created: Field,
     pub permissions: Field,
+    pub owner: Field,
+    pub group: Field,
     pub is_dir: Field,
+    pub content_hash: Field,
+    pub content_truncated: Field,
+    pub width: Field,
+    pub height: Field,
+    pub duration: Field,
+    pub codec: Field,
 }
 
 impl SchemaFields {
@@ -59,12 +132,77 @@ impl SchemaFields {
             file_name: schema.get_field("file_name").unwrap(),
             file_path: schema.get_field("file_path").unwrap(),
             extension: schema.get_field("extension").unwrap(),
+            mime_type: schema.get_field("mime_type").unwrap(),
             content: schema.get_field("content").unwrap(),
+            metadata: schema.get_field("metadata").unwrap(),
+            exif_text: schema.get_field("exif_text").unwrap(),
+            tags: schema.get_field("tags").unwrap(),
             file_size: schema.get_field("file_size").unwrap(),
             modified: schema.get_field("modified").unwrap(),
             created: schema.get_field("created").unwrap(),
             permissions: schema.get_field("permissions").unwrap(),
+            owner: schema.get_field("owner").unwrap(),
+            group: schema.get_field("group").unwrap(),
             is_dir: schema.get_field("is_dir").unwrap(),
+            content_hash: schema.get_field("content_hash").unwrap(),
+            content_truncated: schema.get_field("content_truncated").unwrap(),
+            width: schema.get_field("width").unwrap(),
+            height: schema.get_field("height").unwrap(),
+            duration: schema.get_field("duration").unwrap(),
+            codec: schema.get_field("codec").unwrap(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::index::fixtures;
+    use crate::types::MatchType;
+
+    fn tokens(text: &str) -> Vec<String> {
+        let index = fixtures::empty_index();
+        let mut analyzer = index.tokenizers().get(FILE_NAME_TOKENIZER).unwrap();
+        let mut stream = analyzer.token_stream(text);
+        let mut tokens = Vec::new();
+        while stream.advance() {
+            tokens.push(stream.token().text.clone());
+        }
+        tokens
+    }
+
+    #[test]
+    fn file_names_split_on_dots_hyphens_underscores_and_spaces() {
+        assert_eq!(tokens("user-service.config.js"), ["user", "service", "config", "js"]);
+        assert_eq!(tokens("my.long.file.name.txt"), ["my", "long", "file", "name", "txt"]);
+        assert_eq!(tokens("Quarterly_Report 2024.PDF"), ["quarterly", "report", "2024", "pdf"]);
+        // Long generated names stay whole
+        let hash = "3f7a9c0e1b2d4f6a8c0e1b2d4f6a8c0e1b2d4f6a8c0e1b2d4f6a8c0e1b2d4f6a.bin";
+        assert_eq!(tokens(hash)[0].len(), 64);
+    }
+
+    #[test]
+    fn each_name_component_is_searchable() {
+        let index = fixtures::index_of(&[
+            ("/r/user-service.config.js", ""),
+            ("/r/my.long.file.name.txt", ""),
+        ])
+        .unwrap();
+        let (engine, _history) = fixtures::engine(index, &["/r"]);
+        let names = |query| {
+            engine
+                .search(query, 10)
+                .into_iter()
+                .filter(|r| matches!(r.match_type, MatchType::FileName))
+                .map(|r| r.file_name)
+                .collect::<Vec<_>>()
+        };
+        for query in ["user", "service", "config", "USER"] {
+            assert_eq!(names(query), ["user-service.config.js"], "{}", query);
+        }
+        for query in ["long", "file", "name", "long.file"] {
+            assert_eq!(names(query), ["my.long.file.name.txt"], "{}", query);
         }
+        assert!(names("serv").is_empty());
     }
 }
@@ -0,0 +1,84 @@
+use std::fs;
+use std::path::Path;
+
+use tantivy::collector::Count;
+use tantivy::query::TermQuery;
+use tantivy::schema::{Field, IndexRecordOption};
+use tantivy::{Index, Term};
+
+use super::schema::SchemaFields;
+
+/// A snapshot of the index for the settings panel.
+#[derive(Debug, Clone)]
+pub struct IndexHealth {
+    /// Live documents (files plus directories)
+    pub docs: u64,
+    pub dirs: u64,
+    /// Deleted documents still taking space until their segments are merged
+    pub deleted_docs: u64,
+    pub segments: usize,
+    /// Total size of the index directory in bytes
+    pub disk_bytes: u64,
+    /// Unix time of the last commit, from when `meta.json` was written
+    pub last_commit: Option<i64>,
+    /// Documents that have something in each of the optional text fields,
+    /// as (label, count)
+    pub field_docs: Vec<(&'static str, u64)>,
+}
+
+/// Gather the numbers from segment metadata and field norms; nothing is
+/// loaded from the doc store, so this stays cheap on big indexes.
+pub fn collect(index: &Index, index_path: &Path) -> tantivy::Result<IndexHealth> {
+    let searcher = index.reader()?.searcher();
+    let fields = SchemaFields::new(&index.schema());
+
+    let segments = searcher.segment_readers();
+    let docs = segments.iter().map(|s| u64::from(s.num_docs())).sum();
+    let deleted_docs = segments.iter().map(|s| u64::from(s.num_deleted_docs())).sum();
+    let dirs_query = TermQuery::new(
+        Term::from_field_u64(fields.is_dir, 1),
+        IndexRecordOption::Basic,
+    );
+    let dirs = searcher.search(&dirs_query, &Count)? as u64;
+
+    let text_fields: [(&'static str, Field); 4] = [
+        ("Content", fields.content),
+        ("Metadata", fields.metadata),
+        ("EXIF", fields.exif_text),
+        ("Music tags", fields.tags),
+    ];
+    let mut field_docs = Vec::new();
+    for (label, field) in text_fields {
+        let mut count = 0;
+        for segment in segments {
+            // A field norm of 0 means the document has no tokens in the field
+            let norms = segment.get_fieldnorms_reader(field)?;
+            let alive = segment.alive_bitset();
+            count += (0..segment.max_doc())
+                .filter(|&doc| alive.is_none_or(|a| a.is_alive(doc)))
+                .filter(|&doc| norms.fieldnorm_id(doc) > 0)
+                .count() as u64;
+        }
+        field_docs.push((label, count));
+    }
+
+    let entries: Vec<fs::Metadata> = fs::read_dir(index_path)
+        .map(|dir| dir.flatten().filter_map(|e| e.metadata().ok()).collect())
+        .unwrap_or_default();
+    let disk_bytes = entries.iter().filter(|m| m.is_file()).map(|m| m.len()).sum();
+    let last_commit = fs::metadata(index_path.join("meta.json"))
+        .and_then(|m| m.modified())
+        .ok()
+        .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+        .map(|d| d.as_secs() as i64);
+
+    Ok(IndexHealth {
+        docs,
+        dirs,
+        deleted_docs,
+        segments: segments.len(),
+        disk_bytes,
+        last_commit,
+        field_docs,
+    })
+}
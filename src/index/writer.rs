@@ -1,27 +1,48 @@
 use std::path::Path;
+use std::time::{Duration, Instant};
+use tantivy::query::Query;
 use tantivy::{doc, Index, IndexWriter as TantivyWriter};
 
 use super::schema::SchemaFields;
+use crate::indexer::content::TextContent;
+use crate::indexer::hash;
+use crate::indexer::media::MediaInfo;
 use crate::indexer::metadata::FileMetadata;
 
+/// The smallest heap tantivy accepts for a writer
+pub const MIN_HEAP_BYTES: usize = 15_000_000;
+
 pub struct IndexWriter {
     writer: TantivyWriter,
     fields: SchemaFields,
     docs_since_commit: u64,
     commit_interval: u64,
+    last_commit: Instant,
+    /// Commit this long after the last commit even short of `commit_interval`
+    /// documents; None to go by count only
+    commit_after: Option<Duration>,
 }
 
 impl IndexWriter {
-    pub fn new(index: &Index, commit_interval: u64) -> tantivy::Result<Self> {
+    /// A writer buffering documents in `heap_bytes` (at least
+    /// [`MIN_HEAP_BYTES`]) that commits every `commit_interval` documents,
+    /// or after `commit_after` if that comes first.
+    pub fn new(
+        index: &Index,
+        heap_bytes: usize,
+        commit_interval: u64,
+        commit_after: Option<Duration>,
+    ) -> tantivy::Result<Self> {
         let schema = index.schema();
         let fields = SchemaFields::new(&schema);
-        // Use 50MB heap for the writer
-        let writer = index.writer(50_000_000)?;
+        let writer = index.writer(heap_bytes.max(MIN_HEAP_BYTES))?;
         Ok(IndexWriter {
             writer,
             fields,
             docs_since_commit: 0,
             commit_interval,
+            last_commit: Instant::now(),
+            commit_after,
         })
     }
 
@@ -29,7 +50,10 @@ impl IndexWriter {
         &mut self,
         path: &Path,
         meta: &FileMetadata,
-        content: Option<&str>,
+        mime_type: Option<&str>,
+        content: Option<&TextContent>,
+        metadata: Option<&str>,
+        media: Option<&MediaInfo>,
     ) -> tantivy::Result<()> {
         let file_name = path
             .file_name()
@@ -38,7 +62,7 @@ impl IndexWriter {
         let file_path = path.to_string_lossy().to_string();
         let extension = path
             .extension()
-            .map(|e| e.to_string_lossy().to_string())
+            .map(|e| e.to_string_lossy().to_lowercase())
             .unwrap_or_default();
 
         let mut doc = doc!(
@@ -49,11 +73,49 @@ impl IndexWriter {
             self.fields.modified => meta.modified,
             self.fields.created => meta.created,
             self.fields.permissions => meta.permissions.clone(),
+            self.fields.owner => meta.owner.clone(),
+            self.fields.group => meta.group.clone(),
             self.fields.is_dir => if meta.is_dir { 1u64 } else { 0u64 },
         );
 
-        if let Some(text) = content {
-            doc.add_text(self.fields.content, text);
+        if let Some(hash) = meta.content_hash {
+            doc.add_text(self.fields.content_hash, hash::to_hex(hash));
+        }
+        if let Some(mime) = mime_type {
+            doc.add_text(self.fields.mime_type, mime);
+        }
+        if let Some(content) = content {
+            doc.add_text(self.fields.content, &content.text);
+            if content.truncated {
+                doc.add_u64(self.fields.content_truncated, 1);
+            }
+        }
+        if let Some(text) = metadata {
+            doc.add_text(self.fields.metadata, text);
+        }
+        if let Some(media) = media {
+            let text = media.to_text();
+            if !text.is_empty() {
+                doc.add_text(self.fields.metadata, text);
+            }
+            if let Some(w) = media.width {
+                doc.add_u64(self.fields.width, w);
+            }
+            if let Some(h) = media.height {
+                doc.add_u64(self.fields.height, h);
+            }
+            if let Some(d) = media.duration_secs {
+                doc.add_u64(self.fields.duration, d);
+            }
+            if let Some(codec) = &media.codec {
+                doc.add_text(self.fields.codec, codec);
+            }
+            if let Some(exif) = &media.exif {
+                doc.add_text(self.fields.exif_text, exif.to_text());
+            }
+            if let Some(tags) = &media.tags {
+                doc.add_text(self.fields.tags, tags.to_text());
+            }
         }
 
         self.writer.add_document(doc)?;
@@ -62,9 +124,15 @@ impl IndexWriter {
         Ok(())
     }
 
+    /// Commit once `commit_interval` documents were added, or once any were
+    /// and `commit_after` has passed: big, slow files would otherwise keep
+    /// progress (and what's searchable) stale for a long time.
     /// Returns true if a commit was performed
     pub fn maybe_commit(&mut self) -> tantivy::Result<bool> {
-        if self.docs_since_commit >= self.commit_interval {
+        let overdue = self
+            .commit_after
+            .is_some_and(|after| self.docs_since_commit > 0 && self.last_commit.elapsed() >= after);
+        if self.docs_since_commit >= self.commit_interval || overdue {
             self.commit()?;
             Ok(true)
         } else {
@@ -75,6 +143,7 @@ impl IndexWriter {
     pub fn commit(&mut self) -> tantivy::Result<()> {
         self.writer.commit()?;
         self.docs_since_commit = 0;
+        self.last_commit = Instant::now();
         Ok(())
     }
 
@@ -82,4 +151,16 @@ impl IndexWriter {
     pub fn delete_term(&mut self, term: tantivy::Term) {
         self.writer.delete_term(term);
     }
+
+    /// Delete every document in the index (for a rebuild from scratch)
+    pub fn delete_all(&mut self) -> tantivy::Result<()> {
+        self.writer.delete_all_documents()?;
+        Ok(())
+    }
+
+    /// Delete all documents matching a query (e.g. everything under a removed folder)
+    pub fn delete_query(&mut self, query: Box<dyn Query>) -> tantivy::Result<()> {
+        self.writer.delete_query(query)?;
+        Ok(())
+    }
 }
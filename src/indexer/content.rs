@@ -1,6 +1,14 @@
 use std::fs;
-use std::io::Read;
+use std::io::{self, Read};
 use std::path::Path;
+use std::process::{Command, Stdio};
+use std::sync::OnceLock;
+
+use chardetng::{EncodingDetector, Iso2022JpDetection, Utf8Detection};
+use encoding_rs::{UTF_16BE, UTF_16LE};
+
+use crate::config::Config;
+use crate::indexer::media;
 
 /// Known text file extensions that we should index content for
 const TEXT_EXTENSIONS: &[&str] = &[
@@ -24,12 +32,40 @@ const TEXT_EXTENSIONS: &[&str] = &[
     "dockerfile", "makefile", "cmake", "meson",
 ];
 
+/// The user's changes to [`TEXT_EXTENSIONS`]: `extra_text_extensions` and
+/// `exclude_text_extensions` from the config. Entries may have a leading dot
+/// and any case.
+#[derive(Clone, Copy, Default)]
+pub struct TextExtensions<'a> {
+    pub extra: &'a [String],
+    pub excluded: &'a [String],
+}
+
+impl<'a> TextExtensions<'a> {
+    pub fn from_config(config: &'a Config) -> Self {
+        TextExtensions {
+            extra: &config.extra_text_extensions,
+            excluded: &config.exclude_text_extensions,
+        }
+    }
+}
+
+fn lists_extension(list: &[String], ext: &str) -> bool {
+    list.iter()
+        .any(|listed| listed.trim().trim_start_matches('.').eq_ignore_ascii_case(ext))
+}
+
 /// Check if a file should have its content indexed
-pub fn is_text_file(path: &Path) -> bool {
+pub fn is_text_file(path: &Path, extensions: TextExtensions) -> bool {
     // Check extension first (fast path)
     if let Some(ext) = path.extension() {
         let ext_lower = ext.to_string_lossy().to_lowercase();
-        if TEXT_EXTENSIONS.contains(&ext_lower.as_str()) {
+        if lists_extension(extensions.excluded, &ext_lower) {
+            return false;
+        }
+        if TEXT_EXTENSIONS.contains(&ext_lower.as_str())
+            || lists_extension(extensions.extra, &ext_lower)
+        {
             return true;
         }
     }
@@ -37,6 +73,9 @@ pub fn is_text_file(path: &Path) -> bool {
     // Check for extensionless known files
     if let Some(name) = path.file_name() {
         let name = name.to_string_lossy().to_lowercase();
+        if lists_extension(extensions.excluded, &name) {
+            return false;
+        }
         if matches!(
             name.as_str(),
             "makefile" | "dockerfile" | "gemfile" | "rakefile" | "procfile"
@@ -49,37 +88,249 @@ pub fn is_text_file(path: &Path) -> bool {
     false
 }
 
+/// How much of the start of a file the binary and UTF-16 checks look at
+const SNIFF_LEN: usize = 8192;
+
 /// Check if file content appears to be binary (has null bytes in first 8KB)
-fn is_binary_content(path: &Path) -> bool {
-    let mut file = match fs::File::open(path) {
-        Ok(f) => f,
-        Err(_) => return true, // treat errors as binary
-    };
-
-    let mut buf = [0u8; 8192];
-    let bytes_read = match file.read(&mut buf) {
-        Ok(n) => n,
-        Err(_) => return true,
-    };
-
-    buf[..bytes_read].contains(&0)
+fn is_binary_content(head: &[u8]) -> bool {
+    head.contains(&0)
 }
 
-/// Read file content for indexing, with size limit
-pub fn read_content(path: &Path, max_size: u64) -> Option<String> {
+/// Text read from a file for indexing
+pub struct TextContent {
+    pub text: String,
+    /// The file is over the size limit and only its start was read
+    pub truncated: bool,
+}
+
+/// Read file content for indexing, with size limit. Files over `max_size`
+/// are skipped, or with `truncate_large` have their first `max_size` bytes
+/// read, so memory use stays bounded either way. `Ok(None)` for files with
+/// no text to index; an error means a text file couldn't be read.
+pub fn read_content(
+    path: &Path,
+    max_size: u64,
+    truncate_large: bool,
+    extensions: TextExtensions,
+) -> io::Result<Option<TextContent>> {
     // Check size first
-    let meta = fs::metadata(path).ok()?;
-    if meta.len() > max_size || meta.len() == 0 {
+    let meta = fs::metadata(path)?;
+    let truncated = meta.len() > max_size;
+    if (truncated && !truncate_large) || meta.len() == 0 {
+        return Ok(None);
+    }
+
+    if !is_text_file(path, extensions) {
+        return Ok(None);
+    }
+
+    let mut bytes = Vec::new();
+    fs::File::open(path)?.take(max_size).read_to_end(&mut bytes)?;
+    if bytes.is_empty() {
+        return Ok(None);
+    }
+    Ok(decode_text(&bytes, truncated).map(|text| TextContent { text, truncated }))
+}
+
+/// Content of a whole file whose bytes were already read (for hashing), so
+/// it isn't read a second time
+pub fn content_from_bytes(path: &Path, bytes: &[u8], extensions: TextExtensions) -> Option<TextContent> {
+    if bytes.is_empty() || !is_text_file(path, extensions) {
+        return None;
+    }
+    let text = decode_text(bytes, false)?;
+    Some(TextContent { text, truncated: false })
+}
+
+/// Image formats `tesseract` can read
+const OCR_EXTENSIONS: &[&str] = &["png", "jpg", "jpeg", "gif", "webp", "bmp", "tif", "tiff"];
+
+/// Whether `tesseract` can be run, checked once per process
+fn tesseract_installed() -> bool {
+    static INSTALLED: OnceLock<bool> = OnceLock::new();
+    *INSTALLED.get_or_init(|| {
+        Command::new("tesseract")
+            .arg("--version")
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status()
+            .is_ok_and(|status| status.success())
+    })
+}
+
+/// Text recognized in an image of `size` bytes with `tesseract`, when
+/// `ocr_images` is on and the image is within the `ocr_max_bytes` and
+/// `ocr_max_pixels` caps. None without tesseract, if it fails, or if it
+/// finds no text.
+pub fn ocr_image(path: &Path, size: u64, config: &Config) -> Option<TextContent> {
+    if !config.ocr_images || size == 0 || size > config.ocr_max_bytes {
+        return None;
+    }
+    let ext = path.extension()?.to_string_lossy().to_lowercase();
+    if !OCR_EXTENSIONS.contains(&ext.as_str()) {
+        return None;
+    }
+    let (width, height) = media::image_dimensions(path)?;
+    if width as u64 * height as u64 > config.ocr_max_pixels || !tesseract_installed() {
+        return None;
+    }
+
+    let output = Command::new("tesseract")
+        .arg(path)
+        .arg("stdout")
+        .stdin(Stdio::null())
+        .stderr(Stdio::null())
+        .output()
+        .ok()?;
+    if !output.status.success() {
         return None;
     }
+    let text = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    (!text.is_empty()).then_some(TextContent { text, truncated: false })
+}
 
-    if !is_text_file(path) {
+/// Decode a text file to a String whatever its encoding: UTF-8, UTF-16
+/// (with or without a byte order mark), or else a legacy encoding guessed
+/// from the bytes (Windows-1252, Shift-JIS, GBK, KOI8-R, ...). Binary content
+/// gives None. `truncated` bytes may end in the middle of a character.
+fn decode_text(bytes: &[u8], truncated: bool) -> Option<String> {
+    // UTF-16 is full of null bytes, so it has to be recognized before the binary check
+    match bytes {
+        [0xFF, 0xFE, rest @ ..] => return Some(decode_utf16(rest, false)),
+        [0xFE, 0xFF, rest @ ..] => return Some(decode_utf16(rest, true)),
+        [0xEF, 0xBB, 0xBF, rest @ ..] => return Some(decode_8bit(rest, truncated)),
+        _ => {}
+    }
+    let head = &bytes[..bytes.len().min(SNIFF_LEN)];
+    if let Some(big_endian) = utf16_without_bom(head) {
+        return Some(decode_utf16(bytes, big_endian));
+    }
+    if is_binary_content(head) {
         return None;
     }
+    Some(decode_8bit(bytes, truncated))
+}
 
-    if is_binary_content(path) {
+/// UTF-8 if the bytes are valid UTF-8, else the legacy encoding chardetng
+/// thinks most likely
+fn decode_8bit(bytes: &[u8], truncated: bool) -> String {
+    match std::str::from_utf8(bytes) {
+        Ok(text) => text.to_string(),
+        // Valid UTF-8 cut off partway through its last character
+        Err(e) if truncated && e.error_len().is_none() => {
+            String::from_utf8_lossy(&bytes[..e.valid_up_to()]).into_owned()
+        }
+        Err(_) => {
+            // ISO-2022-JP is all ASCII bytes, so it's valid UTF-8 and never gets here
+            let mut detector = EncodingDetector::new(Iso2022JpDetection::Deny);
+            detector.feed(bytes, !truncated);
+            let encoding = detector.guess(None, Utf8Detection::Deny);
+            encoding.decode_without_bom_handling(bytes).0.into_owned()
+        }
+    }
+}
+
+fn decode_utf16(bytes: &[u8], big_endian: bool) -> String {
+    let encoding = if big_endian { UTF_16BE } else { UTF_16LE };
+    encoding.decode_without_bom_handling(bytes).0.into_owned()
+}
+
+/// Mostly-ASCII UTF-16 has a null in every other byte and hardly any in the
+/// others. Returns whether it's big-endian (nulls first), or None if the
+/// bytes don't look like UTF-16.
+fn utf16_without_bom(head: &[u8]) -> Option<bool> {
+    if head.len() < 4 {
         return None;
     }
+    let pairs = head.len() / 2;
+    let zeros_at = |offset: usize| head.iter().skip(offset).step_by(2).filter(|&&b| b == 0).count();
+    let (even, odd) = (zeros_at(0), zeros_at(1));
+    let mostly = |n: usize| n * 10 >= pairs * 4;
+    let hardly = |n: usize| n * 20 <= pairs;
+    if mostly(odd) && hardly(even) {
+        Some(false)
+    } else if mostly(even) && hardly(odd) {
+        Some(true)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn utf16(text: &str, big_endian: bool, bom: bool) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        let units = bom.then_some(0xFEFF).into_iter().chain(text.encode_utf16());
+        for unit in units {
+            if big_endian {
+                bytes.extend(unit.to_be_bytes());
+            } else {
+                bytes.extend(unit.to_le_bytes());
+            }
+        }
+        bytes
+    }
+
+    #[test]
+    fn utf8_with_and_without_bom() {
+        assert_eq!(decode_text("zażółć gęślą".as_bytes(), false).unwrap(), "zażółć gęślą");
+        let mut bytes = vec![0xEF, 0xBB, 0xBF];
+        bytes.extend("naïve café".as_bytes());
+        assert_eq!(decode_text(&bytes, false).unwrap(), "naïve café");
+    }
+
+    #[test]
+    fn utf16_with_a_bom() {
+        let text = "Grüße, 世界";
+        assert_eq!(decode_text(&utf16(text, false, true), false).unwrap(), text);
+        assert_eq!(decode_text(&utf16(text, true, true), false).unwrap(), text);
+    }
+
+    #[test]
+    fn utf16_without_a_bom_is_recognized_by_its_nulls() {
+        let text = "Plain ASCII notes saved by an old Windows editor";
+        assert_eq!(decode_text(&utf16(text, false, false), false).unwrap(), text);
+        assert_eq!(decode_text(&utf16(text, true, false), false).unwrap(), text);
+    }
+
+    #[test]
+    fn invalid_utf8_falls_back_to_a_guessed_encoding() {
+        // "café" in Latin-1, then curly quotes, an en dash and the euro sign
+        // from the 0x80-0x9F range Windows-1252 adds
+        let bytes = b"caf\xE9 \x93quoted\x94 \x96 5\x80";
+        assert_eq!(decode_text(bytes, false).unwrap(), "café \u{201C}quoted\u{201D} \u{2013} 5€");
 
-    fs::read_to_string(path).ok()
+        let text = "検索エンジンの設定ファイルです。日本語のテキストを正しく読み込みます。";
+        let (shift_jis, _, _) = encoding_rs::SHIFT_JIS.encode(text);
+        assert_eq!(decode_text(&shift_jis, false).unwrap(), text);
+
+        let text = "Привет, это заметка в старой кодировке для поиска по содержимому";
+        let (koi8, _, _) = encoding_rs::KOI8_R.encode(text);
+        assert_eq!(decode_text(&koi8, false).unwrap(), text);
+    }
+
+    #[test]
+    fn truncation_inside_a_character_keeps_utf8() {
+        let bytes = "ąę".as_bytes();
+        let cut = &bytes[..bytes.len() - 1];
+        assert_eq!(decode_text(cut, true).unwrap(), "ą");
+        // A whole file ending like that isn't UTF-8 at all
+        assert_eq!(decode_text(cut, false).unwrap(), "Ä…Ä");
+    }
+
+    #[test]
+    fn binary_content_gives_none() {
+        assert!(decode_text(b"\x7FELF\x02\x01\x01\x00\x00\x00\x00\x00\x00\x00\x00\x00", false).is_none());
+        let mut png = vec![0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A];
+        png.extend([0, 0, 0, 0x0D, b'I', b'H', b'D', b'R', 0, 0, 1, 0, 0, 0, 1, 0, 8, 6, 0, 0, 0]);
+        assert!(decode_text(&png, false).is_none());
+    }
+
+    #[test]
+    fn short_input_is_not_taken_for_utf16() {
+        assert_eq!(decode_text(b"a", false).unwrap(), "a");
+        assert_eq!(decode_text(b"ab\0", false), None);
+    }
 }
@@ -1,26 +1,162 @@
-use std::collections::HashMap;
-use std::sync::mpsc::Sender;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Mutex, PoisonError};
 use std::thread;
+use std::time::{Duration, Instant};
 
+use rayon::iter::{ParallelBridge, ParallelIterator};
+use tantivy::directory::error::LockError;
 use tantivy::schema::Value;
 
 use crate::config::Config;
+use crate::index::query;
 use crate::index::schema::SchemaFields;
 use crate::index::writer::IndexWriter;
-use crate::indexer::content;
+use crate::indexer::content::{self, TextContent, TextExtensions};
+use crate::indexer::frontmatter;
+use crate::indexer::hash;
+use crate::indexer::media::{self, MediaInfo};
 use crate::indexer::metadata::FileMetadata;
+use crate::indexer::mime;
+use crate::indexer::progress::{extend_estimate, ProgressSink};
+use crate::indexer::tags;
+use crate::indexer::volumes;
 use crate::indexer::walker;
+use crate::indexer::watcher::{self, Watcher};
 use crate::types::{IndexProgress, IndexStats, IndexStatus};
 
+/// Messages to the indexer thread
+pub enum IndexerCommand {
+    /// Indexed folders changed; run a pass with the new config. Documents
+    /// under removed roots are purged like deleted files.
+    Reindex(Box<Config>),
+    /// Files or folders changed on disk (from the filesystem watcher)
+    FilesChanged(Vec<PathBuf>),
+    /// The watcher lost track of changes; run a full incremental pass
+    Rescan,
+    /// Drop every document and index all roots again from zero
+    Rebuild,
+}
+
+/// How often to run an incremental pass when the watcher couldn't be started
+const RESCAN_INTERVAL: Duration = Duration::from_secs(5 * 60);
+
+/// Index in the background, then keep the index up to date. The returned
+/// sender re-runs indexing with a new config; only one indexer may write to
+/// the index at a time, so reuse it rather than starting another.
 pub fn start_indexing(
     index: tantivy::Index,
     config: Config,
-    progress_tx: Sender<IndexProgress>,
-    ctx: eframe::egui::Context,
-) -> thread::JoinHandle<()> {
-    thread::spawn(move || {
-        run_indexing(index, config, progress_tx, ctx);
-    })
+    progress: impl ProgressSink + 'static,
+) -> Sender<IndexerCommand> {
+    let (tx, rx) = mpsc::channel();
+    thread::spawn(move || keep_current(&index, config, &progress, rx));
+    tx
+}
+
+/// Indexer thread: an initial pass, then apply watcher batches and UI
+/// commands as they come. Roots that are offline (e.g. an unplugged external
+/// drive) are polled and re-indexed as soon as they're mounted again.
+///
+/// The watcher sends on a channel of its own, so only the app holds a sender
+/// for `commands` and the thread ends once the app drops it. Without a
+/// watcher, changes are picked up by a pass every [`RESCAN_INTERVAL`].
+fn keep_current(
+    index: &tantivy::Index,
+    mut config: Config,
+    progress: &dyn ProgressSink,
+    commands: Receiver<IndexerCommand>,
+) {
+    let (watcher_tx, changes) = mpsc::channel();
+    // Watch before the first pass so nothing changed during it is missed
+    let mut watcher = start_watcher(&config, &watcher_tx);
+    let done = run_indexing(index, &config, progress);
+    report_done(progress, done, watcher.is_some());
+
+    let (_, mut offline) = volumes::partition_roots(&config.root_dirs, &config.mounted_roots_path);
+    let mut next_poll = Instant::now() + volumes::POLL_INTERVAL;
+    let mut next_rescan = Instant::now() + RESCAN_INTERVAL;
+    loop {
+        // Can't wait on both channels at once; look at the watcher's about
+        // as often as it sends batches
+        let command = match commands.recv_timeout(watcher::DEBOUNCE) {
+            Ok(command) => Some(command),
+            Err(RecvTimeoutError::Timeout) => None,
+            // The app is shutting down
+            Err(RecvTimeoutError::Disconnected) => return,
+        };
+        let pending: Vec<_> = command.into_iter().chain(changes.try_iter()).collect();
+        for command in pending {
+            match command {
+                IndexerCommand::Reindex(new_config) => {
+                    config = *new_config;
+                    offline = volumes::partition_roots(&config.root_dirs, &config.mounted_roots_path).1;
+                    drop(watcher);
+                    watcher = start_watcher(&config, &watcher_tx);
+                    let done = run_indexing(index, &config, progress);
+                    report_done(progress, done, watcher.is_some());
+                }
+                IndexerCommand::FilesChanged(paths) => {
+                    apply_changes(index, &config, paths, progress);
+                }
+                IndexerCommand::Rescan => {
+                    let done = run_indexing(index, &config, progress);
+                    report_done(progress, done, watcher.is_some());
+                }
+                IndexerCommand::Rebuild => {
+                    let done = rebuild(index, &config, progress);
+                    report_done(progress, done, watcher.is_some());
+                }
+            }
+        }
+
+        if Instant::now() < next_poll {
+            continue;
+        }
+        next_poll = Instant::now() + volumes::POLL_INTERVAL;
+        let (_, now_offline) = volumes::partition_roots(&config.root_dirs, &config.mounted_roots_path);
+        let remounted = offline.iter().any(|root| !now_offline.contains(root));
+        offline = now_offline;
+        if remounted {
+            // The remounted root has no watches yet
+            drop(watcher);
+            watcher = start_watcher(&config, &watcher_tx);
+            let done = run_indexing(index, &config, progress);
+            report_done(progress, done, watcher.is_some());
+        } else if watcher.is_none() && Instant::now() >= next_rescan {
+            next_rescan = Instant::now() + RESCAN_INTERVAL;
+            run_indexing(index, &config, progress);
+        }
+    }
+}
+
+fn start_watcher(config: &Config, tx: &Sender<IndexerCommand>) -> Option<Watcher> {
+    let (roots, _) = volumes::partition_roots(&config.root_dirs, &config.mounted_roots_path);
+    // Deep enough for every root; changes past a root's own limit are
+    // dropped when they're applied
+    let max_depth = roots
+        .iter()
+        .try_fold(0, |deepest, root| config.max_depth_for(root).map(|d| deepest.max(d)));
+    // The index lives in the data dir, which may well be under a root
+    watcher::watch(
+        &roots,
+        std::slice::from_ref(&config.index_path),
+        &config.skip_dirs,
+        max_depth,
+        tx.clone(),
+    )
+}
+
+/// Once a pass is done and changes are being watched, say so
+fn report_done(progress: &dyn ProgressSink, done: IndexProgress, watching: bool) {
+    if let (true, IndexStatus::Ready(stats)) = (watching, &done.status) {
+        progress.report(IndexProgress {
+            status: IndexStatus::Watching(stats.clone()),
+            ..done
+        });
+    }
 }
 
 /// Load existing indexed files as a map of (path → modified_timestamp).
@@ -40,7 +176,8 @@ fn load_existing_index(index: &tantivy::Index) -> HashMap<String, i64> {
             Some(s) => s,
             None => continue,
         };
-        for doc_id in 0..segment_reader.num_docs() {
+        // Ids of deleted documents stay taken until their segment is merged
+        for doc_id in segment_reader.doc_ids_alive() {
             if let Ok(doc) = store.get::<tantivy::TantivyDocument>(doc_id) {
                 let path = doc
                     .get_first(fields.file_path)
@@ -58,146 +195,215 @@ fn load_existing_index(index: &tantivy::Index) -> HashMap<String, i64> {
     existing
 }
 
-fn run_indexing(
-    index: tantivy::Index,
-    config: Config,
-    progress_tx: Sender<IndexProgress>,
-    ctx: eframe::egui::Context,
-) {
+/// Bring the index in line with the files under `config.root_dirs`: add new
+/// files, re-index modified ones and delete documents whose files are gone.
+///
+/// Runs synchronously and reports through `progress`; the final update is
+/// `Ready` with the add/update/delete counts, or `Error`, and is also returned.
+pub fn run_indexing(index: &tantivy::Index, config: &Config, progress: &dyn ProgressSink) -> IndexProgress {
     // ── Load existing index state ──
-    let _ = progress_tx.send(IndexProgress {
+    progress.report(IndexProgress {
         files_indexed: 0,
         estimated_total: 0,
+        files_per_sec: 0.0,
+        eta_seconds: None,
         status: IndexStatus::Counting,
     });
-    ctx.request_repaint();
 
-    let mut existing = load_existing_index(&index);
+    let existing = load_existing_index(index);
     let had_existing = !existing.is_empty();
     let existing_count = existing.len() as u64;
 
     // If index already has data, show it as ready immediately so search works
     // while we do an incremental update in the background
     if had_existing {
-        let _ = progress_tx.send(IndexProgress {
+        progress.report(IndexProgress {
             files_indexed: existing_count,
             estimated_total: existing_count,
+            files_per_sec: 0.0,
+            eta_seconds: None,
             status: IndexStatus::Ready(None),
         });
-        ctx.request_repaint();
     }
 
+    // Unmounted drives are skipped; their documents stay in the index
+    let (roots, offline_roots) = volumes::partition_roots(&config.root_dirs, &config.mounted_roots_path);
+    let walk_roots: Vec<_> = roots
+        .iter()
+        .map(|root| (root.clone(), config.max_depth_for(root)))
+        .collect();
+
     // ── Phase 1: Quick file count scan ──
-    let estimated_total = quick_count(&config.root_dirs, &progress_tx, &ctx, had_existing);
+    let mut estimated_total = quick_count(
+        &walk_roots,
+        &config.index_extensions,
+        &config.skip_dirs,
+        config.follow_symlinks,
+        progress,
+        had_existing,
+    );
 
-    let mut writer = match IndexWriter::new(&index, config.commit_interval) {
+    let mut writer = match open_writer(index, config) {
         Ok(w) => w,
         Err(e) => {
-            let _ = progress_tx.send(IndexProgress {
+            let failed = IndexProgress {
                 files_indexed: existing_count,
                 estimated_total: existing_count,
-                status: IndexStatus::Error(e.to_string()),
-            });
-            ctx.request_repaint();
-            return;
+                files_per_sec: 0.0,
+                eta_seconds: None,
+                status: IndexStatus::Error(writer_error(&e)),
+            };
+            progress.report(failed.clone());
+            return failed;
         }
     };
 
-    // Create a channel for the walker to send paths
-    let (path_tx, path_rx) = std::sync::mpsc::channel();
+    let pool = match rayon::ThreadPoolBuilder::new()
+        .num_threads(config.threads.max(1))
+        .build()
+    {
+        Ok(p) => p,
+        Err(e) => {
+            let failed = IndexProgress {
+                files_indexed: existing_count,
+                estimated_total: existing_count,
+                files_per_sec: 0.0,
+                eta_seconds: None,
+                status: IndexStatus::Error(e.to_string()),
+            };
+            progress.report(failed.clone());
+            return failed;
+        }
+    };
 
-    let roots = config.root_dirs.clone();
-    let walker_handle = thread::spawn(move || {
-        walker::walk_paths(&roots, path_tx);
-    });
+    // Walker → reader pool (metadata, content and media read in parallel) →
+    // this thread, the only one writing to the index
+    let (path_tx, path_rx) = mpsc::channel();
+    // Bounded, so readers can't pile up file contents faster than they're written
+    let (scanned_tx, scanned_rx) = mpsc::sync_channel::<Scanned>(256);
 
+    let fields = SchemaFields::new(&index.schema());
+    let existing_shared = Mutex::new(existing);
     let mut files_scanned: u64 = 0;
+    // Taken as unchanged by `claim_unchanged`, without going through a reader
+    let claimed = AtomicU64::new(0);
     let mut files_added: u64 = 0;
     let mut files_updated: u64 = 0;
     let mut need_commit = false;
+    let mut throughput = Throughput::new();
+    // Only its failures are filled in before the end
+    let mut stats = IndexStats::default();
 
-    for path in path_rx {
-        files_scanned += 1;
-
-        let path_str = path.to_string_lossy().to_string();
-
-        // Check if this file is already indexed with the same modified time
-        let meta = match FileMetadata::from_path(&path) {
-            Some(m) => m,
-            None => {
-                existing.remove(&path_str);
-                continue;
-            }
-        };
+    thread::scope(|scope| {
+        let existing = &existing_shared;
+        let pool = &pool;
+        let walk_roots = &walk_roots;
+        let claimed = &claimed;
+        scope.spawn(move || {
+            // Folders whose listing is as the last pass left it, with `quick_rescan`
+            let mut unchanged_dirs = HashSet::new();
+            walker::walk_entries(
+                walk_roots,
+                &config.index_extensions,
+                &config.skip_dirs,
+                config.follow_symlinks,
+                |entry| {
+                    if config.quick_rescan && claim_unchanged(&entry, &mut unchanged_dirs, existing) {
+                        claimed.fetch_add(1, Ordering::Relaxed);
+                        return true;
+                    }
+                    path_tx.send(entry.into_path()).is_ok()
+                },
+            );
+        });
+        scope.spawn(move || {
+            pool.install(|| {
+                path_rx
+                    .into_iter()
+                    .par_bridge()
+                    .for_each_with(scanned_tx, |tx, path| {
+                        let _ = tx.send(scan(path, existing, config));
+                    });
+            });
+        });
 
-        if let Some(&indexed_modified) = existing.get(&path_str) {
-            if indexed_modified == meta.modified {
-                // File unchanged — skip it
-                existing.remove(&path_str);
+        for scanned in scanned_rx {
+            files_scanned += 1;
 
+            let Scanned::Changed { file, replaces } = scanned else {
                 // Still send progress updates during scan
-                if files_scanned % 2000 == 0 {
-                    let _ = progress_tx.send(IndexProgress {
+                if files_scanned.is_multiple_of(2000) {
+                    estimated_total = extend_estimate(estimated_total, existing_count + files_added);
+                    let (files_per_sec, eta_seconds) = throughput.sample(files_scanned, claimed.load(Ordering::Relaxed), estimated_total);
+                    progress.report(IndexProgress {
                         files_indexed: existing_count + files_added,
-                        estimated_total: estimated_total.max(existing_count + files_added),
+                        estimated_total,
+                        files_per_sec,
+                        eta_seconds,
                         status: IndexStatus::Indexing,
                     });
-                    ctx.request_repaint();
                 }
                 continue;
+            };
+
+            if replaces {
+                // File modified — delete old version, re-add below
+                let term = tantivy::Term::from_field_text(fields.file_path, &file.path.to_string_lossy());
+                writer.delete_term(term);
+                files_updated += 1;
+            } else {
+                files_added += 1;
             }
-            // File modified — delete old version, will re-add below
-            let schema = index.schema();
-            let fields = SchemaFields::new(&schema);
-            let term = tantivy::Term::from_field_text(fields.file_path, &path_str);
-            writer.delete_term(term);
-            existing.remove(&path_str);
-            files_updated += 1;
-        } else {
-            files_added += 1;
-        }
 
-        let file_content = if !meta.is_dir {
-            content::read_content(&path, config.max_file_size)
-        } else {
-            None
-        };
+            if let Some(error) = &file.read_error {
+                stats.record_failure(&file.path, error);
+            }
+            if let Err(e) = file.add_to(&mut writer) {
+                stats.record_failure(&file.path, e);
+                continue;
+            }
 
-        if writer
-            .add_file(&path, &meta, file_content.as_deref())
-            .is_err()
-        {
-            continue;
-        }
+            need_commit = true;
 
-        need_commit = true;
+            // Periodic commit and progress update
+            if let Ok(true) = writer.maybe_commit() {
+                estimated_total = extend_estimate(estimated_total, existing_count + files_added);
+                let (files_per_sec, eta_seconds) = throughput.sample(files_scanned, claimed.load(Ordering::Relaxed), estimated_total);
+                progress.report(IndexProgress {
+                    files_indexed: existing_count + files_added,
+                    estimated_total,
+                    files_per_sec,
+                    eta_seconds,
+                    status: IndexStatus::Indexing,
+                });
+            }
 
-        // Periodic commit and progress update
-        if let Ok(true) = writer.maybe_commit() {
-            let _ = progress_tx.send(IndexProgress {
-                files_indexed: existing_count + files_added,
-                estimated_total: estimated_total.max(existing_count + files_added),
-                status: IndexStatus::Indexing,
-            });
-            ctx.request_repaint();
+            if (files_added + files_updated).is_multiple_of(500) {
+                estimated_total = extend_estimate(estimated_total, existing_count + files_added);
+                let (files_per_sec, eta_seconds) = throughput.sample(files_scanned, claimed.load(Ordering::Relaxed), estimated_total);
+                progress.report(IndexProgress {
+                    files_indexed: existing_count + files_added,
+                    estimated_total,
+                    files_per_sec,
+                    eta_seconds,
+                    status: IndexStatus::Indexing,
+                });
+            }
         }
+    });
 
-        if (files_added + files_updated) % 500 == 0 {
-            let _ = progress_tx.send(IndexProgress {
-                files_indexed: existing_count + files_added,
-                estimated_total: estimated_total.max(existing_count + files_added),
-                status: IndexStatus::Indexing,
-            });
-            ctx.request_repaint();
-        }
-    }
+    // Whatever no reader claimed wasn't found on disk
+    let mut existing = existing_shared.into_inner().unwrap_or_else(PoisonError::into_inner);
 
-    let _ = walker_handle.join();
+    // A drive unplugged mid-scan looks like mass deletion — keep documents
+    // from every root that is offline now, not just at the start
+    let mut offline_roots = offline_roots;
+    let now_offline = volumes::partition_roots(&roots, &config.mounted_roots_path).1;
+    offline_roots.extend(now_offline);
+    existing.retain(|path_str, _| !volumes::is_under_any(Path::new(path_str), &offline_roots));
 
     // ── Delete files that no longer exist on disk ──
     if !existing.is_empty() {
-        let schema = index.schema();
-        let fields = SchemaFields::new(&schema);
         for path_str in existing.keys() {
             let term = tantivy::Term::from_field_text(fields.file_path, path_str);
             writer.delete_term(term);
@@ -210,21 +416,24 @@ fn run_indexing(
 
     // Only commit if something actually changed
     if need_commit {
-        let _ = progress_tx.send(IndexProgress {
+        progress.report(IndexProgress {
             files_indexed: total_indexed,
             estimated_total: total_indexed,
+            files_per_sec: 0.0,
+            eta_seconds: None,
             status: IndexStatus::Committing,
         });
-        ctx.request_repaint();
 
         if let Err(e) = writer.commit() {
-            let _ = progress_tx.send(IndexProgress {
+            let failed = IndexProgress {
                 files_indexed: total_indexed,
                 estimated_total: total_indexed,
+                files_per_sec: 0.0,
+                eta_seconds: None,
                 status: IndexStatus::Error(e.to_string()),
-            });
-            ctx.request_repaint();
-            return;
+            };
+            progress.report(failed.clone());
+            return failed;
         }
     }
 
@@ -232,65 +441,385 @@ fn run_indexing(
         added: files_added,
         updated: files_updated,
         deleted,
+        ..stats
     };
-    let _ = progress_tx.send(IndexProgress {
+    let done = IndexProgress {
         files_indexed: total_indexed,
         estimated_total: total_indexed,
+        files_per_sec: 0.0,
+        eta_seconds: None,
         status: IndexStatus::Ready(if stats.has_changes() { Some(stats) } else { None }),
+    };
+    progress.report(done.clone());
+    done
+}
+
+/// Wipe the index and run a full pass into the empty index.
+///
+/// The wipe is committed before walking starts, so searches come back empty
+/// rather than stale until the first files of the new pass are committed.
+pub fn rebuild(index: &tantivy::Index, config: &Config, progress: &dyn ProgressSink) -> IndexProgress {
+    let wiped = open_writer(index, config).and_then(|mut writer| {
+        writer.delete_all()?;
+        writer.commit()
     });
-    ctx.request_repaint();
+    if let Err(e) = wiped {
+        let failed = IndexProgress {
+            files_indexed: 0,
+            estimated_total: 0,
+            files_per_sec: 0.0,
+            eta_seconds: None,
+            status: IndexStatus::Error(writer_error(&e)),
+        };
+        progress.report(failed.clone());
+        return failed;
+    }
+    run_indexing(index, config, progress)
+}
+
+/// A walked path, as looked at by a reader thread
+enum Scanned {
+    /// Indexed already with the same modified time, or unreadable
+    Unchanged,
+    /// New or modified; `replaces` is set if an older version is indexed
+    Changed { file: Box<PreparedFile>, replaces: bool },
+}
+
+/// With `quick_rescan`, claim what the last pass indexed in folders whose
+/// modification time hasn't moved since: nothing was added to, removed from
+/// or renamed in them, so their files are taken as unchanged without being
+/// looked at. A file edited in place is missed; the watcher catches those
+/// while the app runs. Returns whether `entry` was claimed, and doesn't need
+/// to go through [`scan`].
+fn claim_unchanged(
+    entry: &ignore::DirEntry,
+    unchanged_dirs: &mut HashSet<PathBuf>,
+    existing: &Mutex<HashMap<String, i64>>,
+) -> bool {
+    let path_str = entry.path().to_string_lossy();
+    if entry.file_type().is_some_and(|ft| ft.is_dir()) {
+        let Some(meta) = FileMetadata::from_path(entry.path()) else {
+            return false;
+        };
+        let mut existing = existing.lock().unwrap_or_else(PoisonError::into_inner);
+        if existing.get(path_str.as_ref()) != Some(&meta.modified) {
+            return false;
+        }
+        existing.remove(path_str.as_ref());
+        unchanged_dirs.insert(entry.path().to_path_buf());
+        return true;
+    }
+    // Files the last pass didn't index (a new extension allowed, say) still get scanned
+    entry.path().parent().is_some_and(|dir| unchanged_dirs.contains(dir))
+        && existing
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .remove(path_str.as_ref())
+            .is_some()
+}
+
+/// Decide whether `path` needs (re-)indexing and, if so, read it. Claims the
+/// path's entry in `existing`, so what's left at the end is gone from disk.
+fn scan(path: PathBuf, existing: &Mutex<HashMap<String, i64>>, config: &Config) -> Scanned {
+    let path_str = path.to_string_lossy().to_string();
+    let indexed_modified = existing
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .remove(&path_str);
+
+    // Check if this file is already indexed with the same modified time
+    let Some(meta) = FileMetadata::from_path(&path) else {
+        return Scanned::Unchanged;
+    };
+    if indexed_modified == Some(meta.modified) {
+        return Scanned::Unchanged;
+    }
+    Scanned::Changed {
+        file: Box::new(PreparedFile::read(path, meta, config)),
+        replaces: indexed_modified.is_some(),
+    }
+}
+
+/// Everything that goes into a file's document, read ahead of writing it
+struct PreparedFile {
+    path: PathBuf,
+    meta: FileMetadata,
+    mime_type: Option<&'static str>,
+    content: Option<TextContent>,
+    metadata_text: Option<String>,
+    media: Option<MediaInfo>,
+    /// Why the content couldn't be read
+    read_error: Option<String>,
+}
+
+impl PreparedFile {
+    fn read(path: PathBuf, mut meta: FileMetadata, config: &Config) -> Self {
+        let hashed = config.detect_duplicates && !meta.is_dir && meta.size <= config.max_file_size;
+        let file_content = if hashed {
+            // One read serves both the hash and the content
+            hash::read_for_hash(&path, config.max_file_size).map(|bytes| {
+                bytes.and_then(|bytes| {
+                    meta.content_hash = Some(hash::content_hash(&bytes));
+                    content::content_from_bytes(&path, &bytes, TextExtensions::from_config(config))
+                })
+            })
+        } else if !meta.is_dir {
+            content::read_content(
+                &path,
+                config.max_file_size,
+                config.truncate_large_files,
+                TextExtensions::from_config(config),
+            )
+        } else {
+            Ok(None)
+        };
+        // Still indexed by name and metadata
+        let (file_content, read_error) = match file_content {
+            Ok(file) => (file, None),
+            Err(e) => (None, Some(e.to_string())),
+        };
+
+        // Markdown frontmatter goes to the metadata field, not the body
+        let (metadata_text, content) = match file_content {
+            Some(file) if frontmatter::is_markdown(&path) => {
+                let (front, body) = frontmatter::split(&file.text);
+                let body = TextContent {
+                    text: body.to_string(),
+                    truncated: file.truncated,
+                };
+                (front, Some(body))
+            }
+            other => (None, other),
+        };
+
+        let mime_type = mime::detect(&path, meta.is_dir, content.is_some());
+
+        // After the MIME type is detected, so OCR'd images keep their own
+        let content = match content {
+            None if !meta.is_dir && read_error.is_none() => {
+                content::ocr_image(&path, meta.size, config)
+            }
+            other => other,
+        };
+
+        let mut media = if config.index_media_metadata && !meta.is_dir {
+            media::extract(&path)
+        } else {
+            None
+        };
+
+        // Tags sit in small blocks apart from the audio, so they're read
+        // whatever the file size and without media indexing
+        if !meta.is_dir && tags::is_tagged_audio(&path) {
+            if let Some(found) = tags::extract(&path) {
+                media.get_or_insert_with(MediaInfo::default).tags = Some(found);
+            }
+        }
+
+        PreparedFile {
+            path,
+            meta,
+            mime_type,
+            content,
+            metadata_text,
+            media,
+            read_error,
+        }
+    }
+
+    fn add_to(&self, writer: &mut IndexWriter) -> tantivy::Result<()> {
+        writer.add_file(
+            &self.path,
+            &self.meta,
+            self.mime_type,
+            self.content.as_ref(),
+            self.metadata_text.as_deref(),
+            self.media.as_ref(),
+        )
+    }
+}
+
+fn open_writer(index: &tantivy::Index, config: &Config) -> tantivy::Result<IndexWriter> {
+    let commit_after = (config.commit_interval_secs > 0)
+        .then(|| Duration::from_secs(config.commit_interval_secs));
+    IndexWriter::new(index, config.writer_heap_bytes, config.commit_interval, commit_after)
+}
+
+/// Why a writer couldn't be opened or used. tantivy's message for a busy
+/// lock doesn't say who holds it; with the app's own index lock that's
+/// another copy of the app that doesn't take it (an older version).
+fn writer_error(e: &tantivy::TantivyError) -> String {
+    match e {
+        tantivy::TantivyError::LockFailure(LockError::LockBusy, _) => {
+            "the index is locked by another drozoSearch instance".to_string()
+        }
+        e => e.to_string(),
+    }
+}
+
+/// Apply a batch of changes reported by the filesystem watcher: re-index
+/// files that exist, drop the ones that are gone. A removed folder takes its
+/// contents with it; a new or moved-in folder is indexed with its contents.
+fn apply_changes(
+    index: &tantivy::Index,
+    config: &Config,
+    paths: Vec<PathBuf>,
+    progress: &dyn ProgressSink,
+) {
+    let mut writer = match open_writer(index, config) {
+        Ok(w) => w,
+        Err(e) => {
+            eprintln!("drozosearch: can't apply file changes ({})", writer_error(&e));
+            return;
+        }
+    };
+    let searcher = index.reader().ok().map(|r| r.searcher());
+    let fields = SchemaFields::new(&index.schema());
+    let mut stats = IndexStats::default();
+
+    let is_indexed = |term: &tantivy::Term| {
+        searcher
+            .as_ref()
+            .is_some_and(|s| s.doc_freq(term).is_ok_and(|n| n > 0))
+    };
+    let reindex = |writer: &mut IndexWriter, stats: &mut IndexStats, path: &Path, meta: &FileMetadata| {
+        let term = tantivy::Term::from_field_text(fields.file_path, &path.to_string_lossy());
+        let known = is_indexed(&term);
+        writer.delete_term(term);
+        let file = PreparedFile::read(path.to_path_buf(), meta.clone(), config);
+        if let Some(error) = &file.read_error {
+            stats.record_failure(path, error);
+        }
+        match file.add_to(writer) {
+            Ok(()) if known => stats.updated += 1,
+            Ok(()) => stats.added += 1,
+            Err(e) => stats.record_failure(path, e),
+        }
+    };
+
+    for path in paths {
+        // The watcher may see changes deeper than the root's depth limit
+        let (depth_left, within_depth) = config.depth_below(&path);
+        match FileMetadata::from_path(&path) {
+            Some(meta)
+                if within_depth && walker::is_wanted_path(&path, meta.is_dir, &config.index_extensions) =>
+            {
+                reindex(&mut writer, &mut stats, &path, &meta);
+                if meta.is_dir {
+                    let children = walker::walk_builder(&path, &config.skip_dirs, None, depth_left)
+                        .build()
+                        .flatten()
+                        .skip(1);
+                    for entry in children.filter(|e| walker::is_wanted(e, &config.index_extensions)) {
+                        if let Some(meta) = FileMetadata::from_path(entry.path()) {
+                            reindex(&mut writer, &mut stats, entry.path(), &meta);
+                        }
+                    }
+                }
+            }
+            Some(_) => {}
+            None => {
+                let term = tantivy::Term::from_field_text(fields.file_path, &path.to_string_lossy());
+                if is_indexed(&term) {
+                    stats.deleted += 1;
+                }
+                writer.delete_term(term);
+                let _ = writer.delete_query(query::path_prefix_query(&path));
+            }
+        }
+    }
+
+    if let Err(e) = writer.commit() {
+        eprintln!("drozosearch: can't apply file changes ({})", e);
+        return;
+    }
+    let total = index
+        .reader()
+        .map(|r| r.searcher().num_docs())
+        .unwrap_or_default();
+    progress.report(IndexProgress {
+        files_indexed: total,
+        estimated_total: total,
+        files_per_sec: 0.0,
+        eta_seconds: None,
+        status: IndexStatus::Watching(stats.has_changes().then_some(stats)),
+    });
+}
+
+/// How long a rate is averaged over, so a burst of small files or one big
+/// one doesn't swing the ETA
+const RATE_WINDOW: Duration = Duration::from_secs(10);
+/// No rate is given before this much of the pass has gone by; the first
+/// files through are mostly ones already in the page cache
+const RATE_WARMUP: Duration = Duration::from_secs(3);
+
+/// Files per second through an indexing pass, over the last `RATE_WINDOW`
+struct Throughput {
+    started: Instant,
+    // (when, files done by then), oldest first
+    samples: VecDeque<(Instant, u64)>,
+}
+
+impl Throughput {
+    fn new() -> Self {
+        let started = Instant::now();
+        Throughput {
+            started,
+            samples: VecDeque::from([(started, 0)]),
+        }
+    }
+
+    /// Record that `done` of `total` files are through, besides `skipped`
+    /// ones taken as unchanged without being read. Returns the rate and the
+    /// seconds left at that rate, or (0, None) while it's too early to tell.
+    fn sample(&mut self, done: u64, skipped: u64, total: u64) -> (f64, Option<u64>) {
+        self.sample_at(Instant::now(), done, skipped, total)
+    }
+
+    fn sample_at(&mut self, now: Instant, done: u64, skipped: u64, total: u64) -> (f64, Option<u64>) {
+        self.samples.push_back((now, done));
+        // Keep the newest sample from before the window, so the rate covers all of it
+        while self.samples.get(1).is_some_and(|&(at, _)| now - at >= RATE_WINDOW) {
+            self.samples.pop_front();
+        }
+        let (since, done_then) = self.samples[0];
+        let secs = (now - since).as_secs_f64();
+        if now - self.started < RATE_WARMUP || secs < 1.0 || done <= done_then {
+            return (0.0, None);
+        }
+        let rate = (done - done_then) as f64 / secs;
+        let eta = (total.saturating_sub(skipped + done) as f64 / rate).ceil() as u64;
+        (rate, Some(eta))
+    }
 }
 
 /// Fast pre-scan: count files without reading metadata or content.
 /// Sends counting progress updates so the UI stays responsive.
 /// When `quiet` is true (incremental update), don't overwrite the Ready status.
 fn quick_count(
-    roots: &[std::path::PathBuf],
-    progress_tx: &Sender<IndexProgress>,
-    ctx: &eframe::egui::Context,
+    roots: &[(PathBuf, Option<usize>)],
+    allowed_extensions: &[String],
+    skip_dirs: &[String],
+    follow_symlinks: bool,
+    progress: &dyn ProgressSink,
     quiet: bool,
 ) -> u64 {
-    use ignore::WalkBuilder;
-
     let mut count: u64 = 0;
+    let visited = follow_symlinks.then(|| walker::Visited::with_roots(roots.iter().map(|(root, _)| root.as_path())));
 
-    for root in roots {
-        let walker = WalkBuilder::new(root)
-            .hidden(false)
-            .git_ignore(true)
-            .git_global(true)
-            .git_exclude(true)
-            .follow_links(false)
-            .max_depth(Some(20))
-            .filter_entry(|entry| {
-                if entry.file_type().map_or(false, |ft| ft.is_dir()) {
-                    if let Some(name) = entry.file_name().to_str() {
-                        let skip = [
-                            ".git", "node_modules", "target", ".cache", ".Trash",
-                            "__pycache__", ".tox", ".venv", "venv", ".env", "dist",
-                            "build", ".build", ".gradle", ".idea", ".vscode",
-                            "Library", ".Spotlight-V100", ".fseventsd",
-                        ];
-                        if skip.contains(&name) {
-                            return false;
-                        }
-                    }
-                }
-                true
-            })
-            .build();
-
-        for entry in walker {
-            if entry.is_ok() {
+    for (root, max_depth) in roots {
+        for entry in walker::walk_builder(root, skip_dirs, visited.as_ref(), *max_depth).build() {
+            if entry.is_ok_and(|e| walker::is_wanted(&e, allowed_extensions)) {
                 count += 1;
                 // Update UI every 5000 files during counting (only for fresh index)
-                if !quiet && count % 5000 == 0 {
-                    let _ = progress_tx.send(IndexProgress {
+                if !quiet && count.is_multiple_of(5000) {
+                    progress.report(IndexProgress {
                         files_indexed: 0,
                         estimated_total: count,
+                        files_per_sec: 0.0,
+                        eta_seconds: None,
                         status: IndexStatus::Counting,
                     });
-                    ctx.request_repaint();
                 }
             }
         }
@@ -298,3 +827,219 @@ fn quick_count(
 
     count
 }
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::time::SystemTime;
+
+    use super::*;
+    use crate::index::fixtures;
+
+    /// Keeps every update, so a test can look at what the indexer reported
+    #[derive(Default)]
+    struct RecordingSink(Mutex<Vec<IndexProgress>>);
+
+    impl ProgressSink for RecordingSink {
+        fn send(&self, progress: IndexProgress) {
+            self.0.lock().unwrap().push(progress);
+        }
+    }
+
+    fn test_config(root: &Path) -> Config {
+        Config {
+            root_dirs: vec![root.to_path_buf()],
+            threads: 2,
+            ..Config::default()
+        }
+    }
+
+    /// Adding or removing a file touches its folder; keep the folders' times
+    /// fixed so only the files themselves count as changed
+    fn pin_dir_times(dirs: &[&Path]) {
+        let pinned = SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+        for dir in dirs {
+            fs::File::open(dir).unwrap().set_modified(pinned).unwrap();
+        }
+    }
+
+    fn stats(done: &IndexProgress) -> (u64, u64, u64) {
+        match &done.status {
+            IndexStatus::Ready(Some(s)) => (s.added, s.updated, s.deleted),
+            IndexStatus::Ready(None) => (0, 0, 0),
+            other => panic!("indexing didn't finish: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn counts_added_updated_and_deleted_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        fs::write(root.join("a.txt"), "alpha").unwrap();
+        fs::write(root.join("b.txt"), "beta").unwrap();
+        fs::create_dir(root.join("sub")).unwrap();
+        fs::write(root.join("sub").join("c.rs"), "fn main() {}").unwrap();
+        let dirs = [root, &root.join("sub")];
+        pin_dir_times(&dirs);
+
+        let index = fixtures::empty_index();
+        let config = test_config(root);
+        let sink = RecordingSink::default();
+
+        // The root, three files and a folder
+        let done = run_indexing(&index, &config, &sink);
+        assert_eq!(stats(&done), (5, 0, 0));
+        assert_eq!(done.files_indexed, 5);
+        let reported = sink.0.lock().unwrap();
+        assert!(matches!(reported.first().unwrap().status, IndexStatus::Counting));
+        assert!(matches!(reported.last().unwrap().status, IndexStatus::Ready(Some(_))));
+        drop(reported);
+
+        // Nothing changed on disk
+        assert_eq!(stats(&run_indexing(&index, &config, &sink)), (0, 0, 0));
+
+        // Modification times are compared in whole seconds
+        let file = fs::OpenOptions::new().write(true).open(root.join("a.txt")).unwrap();
+        file.set_modified(SystemTime::now() + Duration::from_secs(60)).unwrap();
+        drop(file);
+        let done = run_indexing(&index, &config, &sink);
+        assert_eq!(stats(&done), (0, 1, 0));
+        assert_eq!(done.files_indexed, 5);
+
+        fs::remove_file(root.join("b.txt")).unwrap();
+        pin_dir_times(&dirs);
+        let done = run_indexing(&index, &config, &sink);
+        assert_eq!(stats(&done), (0, 0, 1));
+        assert_eq!(done.files_indexed, 4);
+
+        fs::write(root.join("d.md"), "# delta").unwrap();
+        pin_dir_times(&dirs);
+        let done = run_indexing(&index, &config, &sink);
+        assert_eq!(stats(&done), (1, 0, 0));
+        assert_eq!(done.files_indexed, 5);
+    }
+
+    #[test]
+    fn emptying_a_root_deletes_its_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        fs::write(root.join("a.txt"), "alpha").unwrap();
+        pin_dir_times(&[root]);
+        let index = fixtures::empty_index();
+        let config = test_config(root);
+        assert_eq!(stats(&run_indexing(&index, &config, &RecordingSink::default())), (2, 0, 0));
+
+        // An ordinary folder, not a drive's mount point left behind
+        fs::remove_file(root.join("a.txt")).unwrap();
+        pin_dir_times(&[root]);
+        let done = run_indexing(&index, &config, &RecordingSink::default());
+        assert_eq!(stats(&done), (0, 0, 1));
+        assert!(!is_indexed(&index, &root.join("a.txt")));
+    }
+
+    fn is_indexed(index: &tantivy::Index, path: &Path) -> bool {
+        let fields = SchemaFields::new(&index.schema());
+        let term = tantivy::Term::from_field_text(fields.file_path, &path.to_string_lossy());
+        index.reader().unwrap().searcher().doc_freq(&term).unwrap() > 0
+    }
+
+    /// The next update with a `Watching` status, or None on timeout
+    fn next_watching(progress: &Receiver<IndexProgress>) -> Option<IndexProgress> {
+        let deadline = Instant::now() + Duration::from_secs(10);
+        while let Some(left) = deadline.checked_duration_since(Instant::now()) {
+            match progress.recv_timeout(left) {
+                Ok(update) if matches!(update.status, IndexStatus::Watching(_)) => return Some(update),
+                Ok(_) => {}
+                Err(_) => return None,
+            }
+        }
+        None
+    }
+
+    #[test]
+    fn watched_changes_leave_ignored_files_out() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().canonicalize().unwrap();
+        fs::write(root.join(walker::IGNORE_FILENAME), "*.log\n").unwrap();
+        let (log, notes) = (root.join("debug.log"), root.join("notes.txt"));
+        fs::write(&log, "noise").unwrap();
+        let index = fixtures::empty_index();
+        let (tx, rx) = mpsc::channel();
+        let _commands = start_indexing(index.clone(), test_config(&root), tx);
+        assert!(next_watching(&rx).is_some(), "the first pass didn't finish");
+        assert!(!is_indexed(&index, &log));
+
+        fs::write(&log, "more noise").unwrap();
+        fs::write(&notes, "hello").unwrap();
+        while !is_indexed(&index, &notes) {
+            assert!(next_watching(&rx).is_some(), "notes.txt was never indexed");
+        }
+        assert!(!is_indexed(&index, &log));
+    }
+
+    #[test]
+    fn stops_once_the_app_drops_its_sender() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().canonicalize().unwrap();
+        let (tx, rx) = mpsc::channel();
+        let commands = start_indexing(fixtures::empty_index(), test_config(&root), tx);
+        assert!(rx.recv_timeout(Duration::from_secs(10)).is_ok(), "the first pass never started");
+
+        drop(commands);
+        // The thread drops its progress sender on the way out
+        let end = loop {
+            match rx.recv_timeout(Duration::from_secs(10)) {
+                Ok(_) => {}
+                Err(e) => break e,
+            }
+        };
+        assert_eq!(end, RecvTimeoutError::Disconnected, "the indexer thread kept running");
+    }
+
+    #[test]
+    fn eta_leaves_out_files_taken_as_unchanged() {
+        let mut throughput = Throughput::new();
+        let started = throughput.started;
+        assert_eq!(throughput.sample_at(started + Duration::from_secs(1), 20, 600, 1000), (0.0, None));
+        // 600 of 1000 claimed without a look, 200 read in 10s: 200 left at 20/s
+        let later = started + Duration::from_secs(10);
+        assert_eq!(throughput.sample_at(later, 200, 600, 1000), (20.0, Some(10)));
+    }
+
+    #[test]
+    fn extension_allowlist_applies_to_count_and_walk() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        fs::create_dir_all(root.join("src")).unwrap();
+        fs::create_dir_all(root.join("node_modules")).unwrap();
+        for name in ["main.rs", "README.md", "notes.txt", "src/lib.RS", "src/data.json", "node_modules/dep.rs"] {
+            fs::write(root.join(name), "x").unwrap();
+        }
+
+        let config = Config {
+            index_extensions: vec!["rs".to_string(), ".md".to_string()],
+            ..test_config(root)
+        };
+        let walk_roots = [(root.to_path_buf(), None)];
+        let counted = quick_count(
+            &walk_roots,
+            &config.index_extensions,
+            &config.skip_dirs,
+            false,
+            &RecordingSink::default(),
+            true,
+        );
+        let mut walked = Vec::new();
+        walker::walk_entries(&walk_roots, &config.index_extensions, &config.skip_dirs, false, |entry| {
+            walked.push(entry.into_path());
+            true
+        });
+        // The root, src/, main.rs, README.md and src/lib.RS
+        assert_eq!(counted, 5);
+        assert_eq!(walked.len(), 5);
+        assert!(!walked.iter().any(|p| p.ends_with("notes.txt") || p.ends_with("dep.rs")));
+
+        let done = run_indexing(&fixtures::empty_index(), &config, &RecordingSink::default());
+        assert_eq!(stats(&done), (5, 0, 0));
+    }
+}
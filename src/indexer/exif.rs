@@ -0,0 +1,189 @@
+use std::fs::File;
+use std::io::{Cursor, Read};
+use std::path::Path;
+
+use ::exif::{In, Tag, Value};
+
+/// How much of a file is read looking for EXIF. JPEG keeps it in an APP1
+/// segment near the start, which is at most 64 KiB.
+const READ_LIMIT: u64 = 256 * 1024;
+
+/// Camera details from a photo's EXIF block.
+#[derive(Debug, Clone, Default)]
+pub struct ExifInfo {
+    /// Make and model, e.g. `Canon EOS 5D Mark IV`
+    pub camera: Option<String>,
+    /// When the photo was taken, as `YYYY-MM-DD HH:MM:SS` camera-local time
+    pub taken: Option<String>,
+    pub has_gps: bool,
+}
+
+impl ExifInfo {
+    /// Searchable text for the `exif_text` field, e.g.
+    /// `camera: Canon EOS 5D` / `taken: 2023-05-01 14:22:10` / `gps: yes`.
+    pub fn to_text(&self) -> String {
+        let mut lines = Vec::new();
+        if let Some(camera) = &self.camera {
+            lines.push(format!("camera: {}", camera));
+        }
+        if let Some(taken) = &self.taken {
+            lines.push(format!("taken: {}", taken));
+        }
+        if self.has_gps {
+            lines.push("gps: yes".to_string());
+        }
+        lines.join("\n")
+    }
+
+    fn is_empty(&self) -> bool {
+        self.camera.is_none() && self.taken.is_none() && !self.has_gps
+    }
+}
+
+/// Read the EXIF block of a JPEG, TIFF-based file (TIFF and most camera
+/// raw formats), HEIF, PNG or WebP. Files without one, or with a damaged
+/// one, return None.
+pub fn extract(path: &Path) -> Option<ExifInfo> {
+    let mut head = Vec::new();
+    File::open(path).ok()?.take(READ_LIMIT).read_to_end(&mut head).ok()?;
+    let exif = ::exif::Reader::new().read_from_container(&mut Cursor::new(head)).ok()?;
+    let info = info_from(&exif);
+    (!info.is_empty()).then_some(info)
+}
+
+fn info_from(exif: &::exif::Exif) -> ExifInfo {
+    let ascii = |tag: Tag| {
+        let field = exif.get_field(tag, In::PRIMARY)?;
+        let Value::Ascii(values) = &field.value else {
+            return None;
+        };
+        let text = String::from_utf8_lossy(values.first()?);
+        let text = text.trim_matches(|c: char| c == '\0' || c.is_whitespace());
+        (!text.is_empty()).then(|| text.to_string())
+    };
+    let taken = ascii(Tag::DateTimeOriginal)
+        .or_else(|| ascii(Tag::DateTime))
+        .map(|t| format_date_time(&t));
+    let has_gps = exif.get_field(Tag::GPSLatitude, In::PRIMARY).is_some();
+
+    // Models usually repeat the make ("Canon" / "Canon EOS 5D"), not always ("NIKON" / "D750")
+    let camera = match (ascii(Tag::Make), ascii(Tag::Model)) {
+        (Some(make), Some(model)) if model.to_lowercase().starts_with(&make.to_lowercase()) => Some(model),
+        (Some(make), Some(model)) => Some(format!("{} {}", make, model)),
+        (make, model) => make.or(model),
+    };
+    ExifInfo { camera, taken, has_gps }
+}
+
+/// EXIF writes dates as `2023:05:01 14:22:10`
+fn format_date_time(value: &str) -> String {
+    match value.split_once(' ') {
+        Some((date, time)) => format!("{} {}", date.replace(':', "-"), time),
+        None => value.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ::exif::experimental::Writer;
+    use ::exif::{Field, Rational};
+
+    use super::*;
+
+    fn ascii(tag: Tag, text: &str) -> Field {
+        Field {
+            tag,
+            ifd_num: In::PRIMARY,
+            value: Value::Ascii(vec![text.as_bytes().to_vec()]),
+        }
+    }
+
+    /// A TIFF-format EXIF block holding `fields`
+    fn tiff(fields: &[Field], little_endian: bool) -> Vec<u8> {
+        let mut writer = Writer::new();
+        for field in fields {
+            writer.push_field(field);
+        }
+        let mut out = Cursor::new(Vec::new());
+        writer.write(&mut out, little_endian).unwrap();
+        out.into_inner()
+    }
+
+    /// A minimal JPEG with `tiff` in its APP1 segment
+    fn jpeg(tiff: &[u8]) -> Vec<u8> {
+        let mut bytes = vec![0xFF, 0xD8, 0xFF, 0xE1];
+        bytes.extend(((tiff.len() + 8) as u16).to_be_bytes());
+        bytes.extend(b"Exif\0\0");
+        bytes.extend(tiff);
+        bytes.extend([0xFF, 0xDA, 0x00, 0x02, 0xFF, 0xD9]);
+        bytes
+    }
+
+    fn extract_bytes(name: &str, bytes: &[u8]) -> Option<ExifInfo> {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(name);
+        std::fs::write(&path, bytes).unwrap();
+        extract(&path)
+    }
+
+    fn photo_fields() -> Vec<Field> {
+        vec![
+            ascii(Tag::Make, "Canon"),
+            ascii(Tag::Model, "Canon EOS 5D Mark IV"),
+            ascii(Tag::DateTimeOriginal, "2023:05:01 14:22:10"),
+            Field {
+                tag: Tag::GPSLatitude,
+                ifd_num: In::PRIMARY,
+                value: Value::Rational(vec![Rational::from((52, 1)), Rational::from((13, 1)), Rational::from((0, 1))]),
+            },
+        ]
+    }
+
+    #[test]
+    fn camera_date_and_gps_from_a_jpeg() {
+        for little_endian in [true, false] {
+            let info = extract_bytes("photo.jpg", &jpeg(&tiff(&photo_fields(), little_endian))).unwrap();
+            assert_eq!(info.camera.as_deref(), Some("Canon EOS 5D Mark IV"));
+            assert_eq!(info.taken.as_deref(), Some("2023-05-01 14:22:10"));
+            assert!(info.has_gps);
+            assert_eq!(
+                info.to_text(),
+                "camera: Canon EOS 5D Mark IV\ntaken: 2023-05-01 14:22:10\ngps: yes"
+            );
+        }
+    }
+
+    #[test]
+    fn tiff_files_and_make_model_joining() {
+        let fields = [
+            ascii(Tag::Make, "NIKON"),
+            ascii(Tag::Model, "D750"),
+            ascii(Tag::DateTime, "2020:01:02 03:04:05"),
+        ];
+        let info = extract_bytes("scan.tif", &tiff(&fields, true)).unwrap();
+        assert_eq!(info.camera.as_deref(), Some("NIKON D750"));
+        assert_eq!(info.taken.as_deref(), Some("2020-01-02 03:04:05"));
+        assert!(!info.has_gps);
+
+        // Blank values count as missing; nothing useful means no info
+        let fields = [ascii(Tag::Make, "  "), ascii(Tag::Software, "editor")];
+        assert!(extract_bytes("blank.tif", &tiff(&fields, false)).is_none());
+    }
+
+    #[test]
+    fn damaged_and_foreign_files_give_none() {
+        let good = jpeg(&tiff(&photo_fields(), true));
+        // Every truncation of a valid file, and a few corruptions of it
+        for len in 0..good.len() {
+            let _ = extract_bytes("cut.jpg", &good[..len]);
+        }
+        for (i, byte) in [(4, 0xFF), (5, 0x02), (12, b'X'), (16, 0xFF), (20, 0xFF)] {
+            let mut bad = good.clone();
+            bad[i] = byte;
+            let _ = extract_bytes("bad.jpg", &bad);
+        }
+        assert!(extract_bytes("text.jpg", b"not an image at all").is_none());
+        assert!(extract_bytes("empty.jpg", b"").is_none());
+        assert!(extract_bytes("loop.tif", b"II*\0\x08\0\0\0\x01\0\x0f\x01\x02\0\xff\xff\xff\xff\0\0\0\0").is_none());
+    }
+}
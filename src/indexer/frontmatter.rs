@@ -0,0 +1,176 @@
+use std::path::Path;
+
+/// Check if a file is a Markdown document that may carry YAML frontmatter
+pub fn is_markdown(path: &Path) -> bool {
+    path.extension()
+        .map(|e| e.to_string_lossy().to_lowercase())
+        .is_some_and(|e| matches!(e.as_str(), "md" | "markdown"))
+}
+
+/// Split a leading `---`-fenced YAML block off a Markdown document.
+///
+/// Returns the frontmatter flattened into searchable text (one `key: value`
+/// line per field, list items joined by spaces) and the remaining body.
+/// Only the simple subset of YAML that notes actually use is understood:
+/// scalars, inline `[a, b]` lists and `- item` block lists. Nested mappings
+/// (indented lines) are dropped, leaving their key with no value. Anything
+/// else is treated as malformed and the document is returned untouched, as
+/// it is when the block is never closed.
+pub fn split(text: &str) -> (Option<String>, &str) {
+    let trimmed = text.trim_start_matches('\u{feff}');
+    let Some(after_open) = trimmed
+        .strip_prefix("---\n")
+        .or_else(|| trimmed.strip_prefix("---\r\n"))
+    else {
+        return (None, text);
+    };
+
+    // Find the closing fence (`---` or `...` on its own line)
+    let mut offset = 0;
+    let mut block_end = None;
+    for line in after_open.split_inclusive('\n') {
+        let bare = line.trim_end();
+        if bare == "---" || bare == "..." {
+            block_end = Some((offset, offset + line.len()));
+            break;
+        }
+        offset += line.len();
+    }
+    let Some((block_len, body_start)) = block_end else {
+        return (None, text);
+    };
+
+    match parse_fields(&after_open[..block_len]) {
+        Some(fields) if !fields.is_empty() => {
+            let flattened = fields
+                .iter()
+                .map(|(key, values)| format!("{}: {}", key, values.join(" ")))
+                .collect::<Vec<_>>()
+                .join("\n");
+            (Some(flattened), &after_open[body_start..])
+        }
+        _ => (None, text),
+    }
+}
+
+/// Parse `key: value` lines into (key, values) pairs. Returns None if a line
+/// doesn't fit the supported subset.
+fn parse_fields(block: &str) -> Option<Vec<(String, Vec<String>)>> {
+    let mut fields: Vec<(String, Vec<String>)> = Vec::new();
+
+    for line in block.lines() {
+        let bare = line.trim();
+        if bare.is_empty() || bare.starts_with('#') {
+            continue;
+        }
+
+        // Block list item belonging to the previous key
+        if let Some(item) = bare.strip_prefix("- ") {
+            let (_, values) = fields.last_mut()?;
+            values.push(unquote(item).to_string());
+            continue;
+        }
+
+        // Nested mappings aren't supported; their lines are indented
+        if line.starts_with(char::is_whitespace) {
+            continue;
+        }
+
+        let (key, value) = bare.split_once(':')?;
+        let key = key.trim();
+        if key.is_empty() || key.contains(char::is_whitespace) {
+            return None;
+        }
+
+        let value = value.trim();
+        let values = match value.strip_prefix('[').and_then(|v| v.strip_suffix(']')) {
+            Some(list) => list
+                .split(',')
+                .map(|v| unquote(v.trim()).to_string())
+                .filter(|v| !v.is_empty())
+                .collect(),
+            None if value.is_empty() => Vec::new(),
+            None => vec![unquote(value).to_string()],
+        };
+        fields.push((key.to_string(), values));
+    }
+
+    Some(fields)
+}
+
+fn unquote(s: &str) -> &str {
+    s.strip_prefix('"')
+        .and_then(|s| s.strip_suffix('"'))
+        .or_else(|| s.strip_prefix('\'').and_then(|s| s.strip_suffix('\'')))
+        .unwrap_or(s)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+    use crate::config::Config;
+    use crate::index::fixtures;
+    use crate::indexer::coordinator;
+    use crate::types::MatchType;
+
+    #[test]
+    fn fields_are_flattened_and_split_off_the_body() {
+        let note = "---\ntitle: \"Weekly plan\"\ntags: [recipes, 'vegan']\naliases:\n  - plan\n  - menu\n---\n# Monday\n";
+        let (front, body) = split(note);
+        assert_eq!(front.as_deref(), Some("title: Weekly plan\ntags: recipes vegan\naliases: plan menu"));
+        assert_eq!(body, "# Monday\n");
+
+        // A byte order mark, Windows line ends and a `...` fence
+        let (front, body) = split("\u{feff}---\r\ntags: work\r\n...\r\nbody");
+        assert_eq!(front.as_deref(), Some("tags: work"));
+        assert_eq!(body, "body");
+    }
+
+    #[test]
+    fn nested_mappings_are_dropped() {
+        let note = "---\nauthor:\n  name: Ann\n  email: ann@example.com\ntags: [a]\n---\nbody";
+        let (front, body) = split(note);
+        assert_eq!(front.as_deref(), Some("author: \ntags: a"));
+        assert_eq!(body, "body");
+    }
+
+    #[test]
+    fn unterminated_or_malformed_blocks_leave_the_document_alone() {
+        for note in [
+            "# No frontmatter\n---\ntags: a\n---\n",
+            "---\ntags: [a, b]\nno closing fence",
+            "---\njust a line of prose\n---\nbody",
+            "---\n- an item before any key\n---\nbody",
+            "---\ntwo words: value\n---\nbody",
+            "---\n# only a comment\n---\nbody",
+        ] {
+            assert_eq!(split(note), (None, note), "{:?}", note);
+        }
+    }
+
+    #[test]
+    fn a_frontmatter_tag_is_a_metadata_match() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        let note = root.join("dinner.md");
+        fs::write(&note, "---\ntags: [weeknight, lentils]\n---\nSoup for four.\n").unwrap();
+
+        let index = fixtures::empty_index();
+        let config = Config {
+            root_dirs: vec![root.to_path_buf()],
+            ..Config::default()
+        };
+        coordinator::run_indexing(&index, &config, &std::sync::mpsc::channel().0);
+        let (engine, _history) = fixtures::engine(index, &[root]);
+
+        let results = engine.search("lentils", 10);
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].file_path, note);
+        assert!(matches!(results[0].match_type, MatchType::Metadata));
+        // The body is still content
+        let results = engine.search("soup", 10);
+        assert!(matches!(results[0].match_type, MatchType::Content));
+    }
+}
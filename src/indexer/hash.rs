@@ -0,0 +1,57 @@
+use std::fs::File;
+use std::io::{self, Read};
+use std::path::Path;
+
+/// 128-bit XXH3 of `bytes`. Not cryptographic, but fast and stable across
+/// builds and platforms, which matters because it's stored in the index.
+/// Duplicates are compared by hash and size together.
+pub fn content_hash(bytes: &[u8]) -> u128 {
+    xxhash_rust::xxh3::xxh3_128(bytes)
+}
+
+/// Read at most `max_size` bytes of a file for hashing. Empty files give
+/// None: they're all alike and not worth reporting as duplicates.
+pub fn read_for_hash(path: &Path, max_size: u64) -> io::Result<Option<Vec<u8>>> {
+    let mut bytes = Vec::new();
+    File::open(path)?.take(max_size).read_to_end(&mut bytes)?;
+    Ok((!bytes.is_empty()).then_some(bytes))
+}
+
+/// The hash as stored in the `content_hash` field. 32 hex digits, so hashes
+/// stored by older versions (16 digits) never equal a new one.
+pub fn to_hex(hash: u128) -> String {
+    format!("{:032x}", hash)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hashes_are_stable() {
+        // Stored in indexes, so they must not change between builds
+        assert_eq!(to_hex(content_hash(b"")), "99aa06d3014798d86001c324468d497f");
+        assert_eq!(to_hex(content_hash(b"hello world")), "df8d09e93f874900a99b8775cc15b6c7");
+    }
+
+    #[test]
+    fn different_contents_hash_differently() {
+        let a = content_hash(b"same length A");
+        let b = content_hash(b"same length B");
+        assert_ne!(a, b);
+        assert_eq!(a, content_hash(b"same length A"));
+        assert_eq!(to_hex(1).len(), 32);
+    }
+
+    #[test]
+    fn reading_stops_at_the_limit_and_skips_empty_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("f.bin");
+        std::fs::write(&path, b"0123456789").unwrap();
+        assert_eq!(read_for_hash(&path, 4).unwrap().unwrap(), b"0123");
+        assert_eq!(read_for_hash(&path, 100).unwrap().unwrap().len(), 10);
+        std::fs::write(&path, b"").unwrap();
+        assert!(read_for_hash(&path, 100).unwrap().is_none());
+        assert!(read_for_hash(&dir.path().join("missing"), 100).is_err());
+    }
+}
@@ -0,0 +1,218 @@
+use std::path::Path;
+use std::process::Command;
+
+use crate::indexer::exif::{self, ExifInfo};
+use crate::indexer::tags::AudioTags;
+use crate::types::FileCategory;
+
+/// Technical details of an image, audio or video file.
+#[derive(Debug, Clone, Default)]
+pub struct MediaInfo {
+    pub width: Option<u64>,
+    pub height: Option<u64>,
+    pub duration_secs: Option<u64>,
+    pub codec: Option<String>,
+    /// Camera, capture time and GPS presence of photos
+    pub exif: Option<ExifInfo>,
+    /// Artist, album, title and genre of music files. Read even when media
+    /// indexing is off, see [`crate::indexer::tags`].
+    pub tags: Option<AudioTags>,
+}
+
+impl MediaInfo {
+    /// Searchable text for the metadata field, e.g.
+    /// `dimensions: 1920x1080` / `duration: 3:25` / `codec: aac`.
+    pub fn to_text(&self) -> String {
+        let mut lines = Vec::new();
+        if let (Some(w), Some(h)) = (self.width, self.height) {
+            lines.push(format!("dimensions: {}x{}", w, h));
+        }
+        if let Some(secs) = self.duration_secs {
+            lines.push(format!("duration: {}", format_duration(secs)));
+        }
+        if let Some(codec) = &self.codec {
+            lines.push(format!("codec: {}", codec));
+        }
+        lines.join("\n")
+    }
+
+    fn is_empty(&self) -> bool {
+        self.width.is_none()
+            && self.duration_secs.is_none()
+            && self.codec.is_none()
+            && self.exif.is_none()
+    }
+}
+
+/// Compact one-line summary for display, e.g. `1920×1080 · 3:25 · h264`.
+pub fn summary(
+    width: Option<u64>,
+    height: Option<u64>,
+    duration_secs: Option<u64>,
+    codec: Option<&str>,
+) -> Option<String> {
+    let mut parts = Vec::new();
+    if let (Some(w), Some(h)) = (width, height) {
+        parts.push(format!("{}×{}", w, h));
+    }
+    if let Some(secs) = duration_secs {
+        parts.push(format_duration(secs));
+    }
+    if let Some(codec) = codec {
+        parts.push(codec.to_string());
+    }
+    (!parts.is_empty()).then(|| parts.join(" · "))
+}
+
+/// Read media details for a file, if it is an image, audio or video file.
+///
+/// Images only have their header decoded, plus their EXIF block. Audio and video go through
+/// `ffprobe` when it is installed; without it they get no details. Corrupt
+/// or unsupported files return None rather than failing the index pass.
+pub fn extract(path: &Path) -> Option<MediaInfo> {
+    let info = match FileCategory::of(path, false) {
+        FileCategory::Image => image_info(path)?,
+        FileCategory::Audio => ffprobe_info(path, true)?,
+        FileCategory::Video => ffprobe_info(path, false)?,
+        _ => return None,
+    };
+    (!info.is_empty()).then_some(info)
+}
+
+/// Width and height of an image, read from its header
+pub fn image_dimensions(path: &Path) -> Option<(u32, u32)> {
+    image::ImageReader::open(path)
+        .ok()?
+        .with_guessed_format()
+        .ok()?
+        .into_dimensions()
+        .ok()
+}
+
+fn image_info(path: &Path) -> Option<MediaInfo> {
+    let dimensions = image_dimensions(path);
+    Some(MediaInfo {
+        width: dimensions.map(|(w, _)| w as u64),
+        height: dimensions.map(|(_, h)| h as u64),
+        exif: exif::extract(path),
+        ..Default::default()
+    })
+}
+
+/// For audio files the embedded cover art (a one-frame video stream) is
+/// ignored, so the codec and dimensions describe the sound.
+fn ffprobe_info(path: &Path, audio: bool) -> Option<MediaInfo> {
+    let output = Command::new("ffprobe")
+        .args(["-v", "error", "-show_entries"])
+        .arg("format=duration:stream=codec_type,codec_name,width,height")
+        .args(["-of", "default=noprint_wrappers=1"])
+        .arg(path)
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+
+    // One `key=value` per line; stream entries come first, then the format
+    let text = String::from_utf8_lossy(&output.stdout);
+    let mut info = MediaInfo::default();
+    let mut stream_codec: Option<String> = None;
+    let mut video_codec: Option<String> = None;
+    let mut audio_codec: Option<String> = None;
+    for line in text.lines() {
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+        match key {
+            "codec_name" => stream_codec = Some(value.to_string()),
+            "codec_type" if value == "video" && video_codec.is_none() => {
+                video_codec = stream_codec.take();
+            }
+            "codec_type" if value == "audio" && audio_codec.is_none() => {
+                audio_codec = stream_codec.take();
+            }
+            "width" if info.width.is_none() => info.width = value.parse().ok(),
+            "height" if info.height.is_none() => info.height = value.parse().ok(),
+            "duration" => {
+                info.duration_secs = value.parse::<f64>().ok().map(|d| d.round() as u64)
+            }
+            _ => {}
+        }
+    }
+    if audio {
+        info.width = None;
+        info.height = None;
+        info.codec = audio_codec;
+    } else {
+        // Prefer the video codec; fall back to the audio one
+        info.codec = video_codec.or(audio_codec);
+    }
+    Some(info)
+}
+
+fn format_duration(secs: u64) -> String {
+    let (h, m, s) = (secs / 3600, secs / 60 % 60, secs % 60);
+    if h > 0 {
+        format!("{}:{:02}:{:02}", h, m, s)
+    } else {
+        format!("{}:{:02}", m, s)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+    use crate::index::fixtures::{self, TestFile};
+    use crate::types::MatchType;
+
+    #[test]
+    fn image_dimensions_become_searchable() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("screenshot.png");
+        image::RgbImage::new(64, 48).save(&path).unwrap();
+
+        let info = extract(&path).unwrap();
+        assert_eq!((info.width, info.height), (Some(64), Some(48)));
+        assert_eq!(info.to_text(), "dimensions: 64x48");
+
+        let file = TestFile {
+            size: fs::metadata(&path).unwrap().len(),
+            modified: 1_700_000_000,
+            media: Some(info),
+            ..fixtures::file(&path.to_string_lossy())
+        };
+        let index = fixtures::index_of_files(&[file]).unwrap();
+        let (engine, _history) = fixtures::engine(index, &[dir.path()]);
+
+        let results = engine.search("64x48", 10);
+        assert_eq!(results.len(), 1);
+        assert!(matches!(results[0].match_type, MatchType::Metadata));
+        assert_eq!(engine.search("width:64 height:48", 10).len(), 1);
+        assert!(engine.search("width:>100", 10).is_empty());
+    }
+
+    #[test]
+    fn corrupt_images_are_skipped_without_panicking() {
+        let dir = tempfile::tempdir().unwrap();
+        let valid = dir.path().join("valid.png");
+        image::RgbImage::new(8, 8).save(&valid).unwrap();
+        let png = fs::read(&valid).unwrap();
+
+        let cases: [(&str, &[u8]); 5] = [
+            ("empty.png", b""),
+            ("garbage.jpg", b"\xff\xd8\xff\xe1\x00\x10Exif\x00\x00MM\x00*\xff\xff\xff\xff"),
+            ("truncated.png", &png[..20]),
+            ("text.gif", b"this is not a picture at all"),
+            ("zeros.tiff", &[0; 512]),
+        ];
+        for (name, bytes) in cases {
+            let path = dir.path().join(name);
+            fs::write(&path, bytes).unwrap();
+            let info = extract(&path);
+            assert!(info.is_none_or(|i| i.width.is_none()), "{} got dimensions", name);
+        }
+        assert!(extract(&dir.path().join("missing.png")).is_none());
+    }
+}
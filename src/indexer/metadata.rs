@@ -1,5 +1,11 @@
+#[cfg(unix)]
+use std::collections::HashMap;
+#[cfg(unix)]
+use std::ffi::CStr;
 use std::fs;
 use std::path::Path;
+#[cfg(unix)]
+use std::sync::{Mutex, OnceLock, PoisonError};
 
 #[derive(Debug, Clone)]
 pub struct FileMetadata {
@@ -7,7 +13,14 @@ pub struct FileMetadata {
     pub modified: i64,
     pub created: i64,
     pub permissions: String,
+    /// Owning user and group names (the numeric id when it has no name);
+    /// empty where files have no Unix owner
+    pub owner: String,
+    pub group: String,
     pub is_dir: bool,
+    /// Hash of the file's bytes, for finding duplicates. Filled in by the
+    /// indexer when `detect_duplicates` is on, never by `from_path`.
+    pub content_hash: Option<u128>,
 }
 
 impl FileMetadata {
@@ -29,13 +42,17 @@ impl FileMetadata {
             .unwrap_or(0);
 
         let permissions = format_permissions(&meta);
+        let (owner, group) = ownership(&meta);
 
         Some(FileMetadata {
             size: meta.len(),
             modified,
             created,
             permissions,
+            owner,
+            group,
             is_dir: meta.is_dir(),
+            content_hash: None,
         })
     }
 }
@@ -64,3 +81,87 @@ fn format_permissions(meta: &fs::Metadata) -> String {
         "readwrite".to_string()
     }
 }
+
+#[cfg(unix)]
+fn ownership(meta: &fs::Metadata) -> (String, String) {
+    use std::os::unix::fs::MetadataExt;
+    static USERS: OnceLock<Mutex<HashMap<u32, String>>> = OnceLock::new();
+    static GROUPS: OnceLock<Mutex<HashMap<u32, String>>> = OnceLock::new();
+    (
+        cached_name(&USERS, meta.uid(), user_name),
+        cached_name(&GROUPS, meta.gid(), group_name),
+    )
+}
+
+#[cfg(not(unix))]
+fn ownership(_meta: &fs::Metadata) -> (String, String) {
+    (String::new(), String::new())
+}
+
+/// The name for a user or group id, looked up once per id: a tree usually
+/// has only a few owners, and a lookup may read `/etc/passwd` or ask a
+/// directory service
+#[cfg(unix)]
+fn cached_name(
+    cache: &OnceLock<Mutex<HashMap<u32, String>>>,
+    id: u32,
+    lookup: fn(u32) -> Option<String>,
+) -> String {
+    let mut names = cache
+        .get_or_init(Default::default)
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner);
+    names
+        .entry(id)
+        .or_insert_with(|| lookup(id).unwrap_or_else(|| id.to_string()))
+        .clone()
+}
+
+/// Buffer sizes to try for the `get*_r` lookups before giving up
+#[cfg(unix)]
+const LOOKUP_BUFFER_SIZES: [usize; 4] = [1024, 4096, 16384, 65536];
+
+#[cfg(unix)]
+fn user_name(uid: u32) -> Option<String> {
+    for size in LOOKUP_BUFFER_SIZES {
+        let mut buf = vec![0 as libc::c_char; size];
+        // SAFETY: all-zero is a valid `passwd` (null pointers, zero ids)
+        let mut entry: libc::passwd = unsafe { std::mem::zeroed() };
+        let mut found = std::ptr::null_mut();
+        // SAFETY: the pointers are valid for the call and `buf.len()` is
+        // the buffer's real size; `entry` points into `buf` afterwards
+        let rc = unsafe { libc::getpwuid_r(uid, &mut entry, buf.as_mut_ptr(), buf.len(), &mut found) };
+        if rc == libc::ERANGE {
+            continue;
+        }
+        if rc != 0 || found.is_null() || entry.pw_name.is_null() {
+            return None;
+        }
+        // SAFETY: a non-null `pw_name` is a NUL-terminated string in `buf`
+        let name = unsafe { CStr::from_ptr(entry.pw_name) };
+        return Some(name.to_string_lossy().into_owned());
+    }
+    None
+}
+
+#[cfg(unix)]
+fn group_name(gid: u32) -> Option<String> {
+    for size in LOOKUP_BUFFER_SIZES {
+        let mut buf = vec![0 as libc::c_char; size];
+        // SAFETY: all-zero is a valid `group` (null pointers, zero id)
+        let mut entry: libc::group = unsafe { std::mem::zeroed() };
+        let mut found = std::ptr::null_mut();
+        // SAFETY: as in `user_name`
+        let rc = unsafe { libc::getgrgid_r(gid, &mut entry, buf.as_mut_ptr(), buf.len(), &mut found) };
+        if rc == libc::ERANGE {
+            continue;
+        }
+        if rc != 0 || found.is_null() || entry.gr_name.is_null() {
+            return None;
+        }
+        // SAFETY: a non-null `gr_name` is a NUL-terminated string in `buf`
+        let name = unsafe { CStr::from_ptr(entry.gr_name) };
+        return Some(name.to_string_lossy().into_owned());
+    }
+    None
+}
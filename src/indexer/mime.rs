@@ -0,0 +1,187 @@
+use std::fs;
+use std::io::Read;
+use std::path::Path;
+
+/// Bytes read from the start of a file to recognize its format; `infer`
+/// looks this far into zip files to tell office documents apart
+const SNIFF_LEN: u64 = 8192;
+
+/// MIME type of a file: sniffed from its first bytes, or guessed from the
+/// extension when the bytes aren't recognized. Files whose content was read
+/// as text (`is_text`) aren't opened again; their extension decides.
+/// Directories have none.
+pub fn detect(path: &Path, is_dir: bool, is_text: bool) -> Option<&'static str> {
+    if is_dir {
+        return None;
+    }
+    if is_text {
+        return Some(from_extension(path).unwrap_or("text/plain"));
+    }
+    let sniffed = sniff_file(path);
+    match (sniffed, from_extension(path)) {
+        // Office documents, jars, epubs... are zip files; the extension says which
+        (Some("application/zip"), Some(by_ext)) => Some(by_ext),
+        (Some(sniffed), _) => Some(sniffed),
+        (None, Some(by_ext)) => Some(by_ext),
+        (None, None) => Some("application/octet-stream"),
+    }
+}
+
+fn sniff_file(path: &Path) -> Option<&'static str> {
+    let mut head = Vec::new();
+    fs::File::open(path).ok()?.take(SNIFF_LEN).read_to_end(&mut head).ok()?;
+    sniff(&head)
+}
+
+/// Recognize a file format by its magic bytes. Formats the extension table
+/// knows get its name for them (`audio/flac` rather than `infer`'s
+/// `audio/x-flac`), so a format is called the same however it was detected.
+pub fn sniff(bytes: &[u8]) -> Option<&'static str> {
+    infer::get(bytes).map(|kind| for_extension(kind.extension()).unwrap_or(kind.mime_type()))
+}
+
+/// Guess a MIME type from the file extension.
+pub fn from_extension(path: &Path) -> Option<&'static str> {
+    for_extension(&path.extension()?.to_str()?.to_lowercase())
+}
+
+/// MIME type for a lowercase extension
+fn for_extension(ext: &str) -> Option<&'static str> {
+    let mime = match ext {
+        // Text and code
+        "txt" | "log" => "text/plain",
+        "md" | "markdown" => "text/markdown",
+        "html" | "htm" => "text/html",
+        "css" => "text/css",
+        "csv" => "text/csv",
+        "tsv" => "text/tab-separated-values",
+        "js" | "mjs" | "jsx" => "text/javascript",
+        "ts" | "tsx" => "text/x-typescript",
+        "rs" => "text/x-rust",
+        "py" => "text/x-python",
+        "go" => "text/x-go",
+        "c" | "h" => "text/x-c",
+        "cpp" | "hpp" | "cc" | "cxx" => "text/x-c++",
+        "java" => "text/x-java",
+        "rb" => "text/x-ruby",
+        "sh" | "bash" | "zsh" => "text/x-shellscript",
+        "json" => "application/json",
+        "xml" => "application/xml",
+        "toml" => "application/toml",
+        "yaml" | "yml" => "application/yaml",
+        "sql" => "application/sql",
+        // Images
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "webp" => "image/webp",
+        "bmp" => "image/bmp",
+        "ico" => "image/x-icon",
+        "svg" => "image/svg+xml",
+        "tif" | "tiff" => "image/tiff",
+        "heic" => "image/heic",
+        "avif" => "image/avif",
+        // Audio and video
+        "mp3" => "audio/mpeg",
+        "flac" => "audio/flac",
+        "ogg" | "oga" => "audio/ogg",
+        "wav" => "audio/wav",
+        "aac" => "audio/aac",
+        "m4a" => "audio/mp4",
+        "mp4" | "m4v" => "video/mp4",
+        "mov" => "video/quicktime",
+        "mkv" => "video/x-matroska",
+        "webm" => "video/webm",
+        "avi" => "video/x-msvideo",
+        // Documents
+        "pdf" => "application/pdf",
+        "doc" => "application/msword",
+        "docx" => "application/vnd.openxmlformats-officedocument.wordprocessingml.document",
+        "xlsx" => "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet",
+        "pptx" => "application/vnd.openxmlformats-officedocument.presentationml.presentation",
+        "odt" => "application/vnd.oasis.opendocument.text",
+        "epub" => "application/epub+zip",
+        // Archives and binaries
+        "zip" => "application/zip",
+        "jar" => "application/java-archive",
+        "tar" => "application/x-tar",
+        "gz" => "application/gzip",
+        "bz2" => "application/x-bzip2",
+        "xz" => "application/x-xz",
+        "7z" => "application/x-7z-compressed",
+        "rar" => "application/vnd.rar",
+        "exe" | "dll" => "application/vnd.microsoft.portable-executable",
+        "so" => "application/x-sharedlib",
+        "wasm" => "application/wasm",
+        _ => return None,
+    };
+    Some(mime)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn formats_are_recognized_by_their_bytes() {
+        assert_eq!(sniff(b"\x89PNG\r\n\x1a\n\0\0\0\x0dIHDR"), Some("image/png"));
+        assert_eq!(sniff(b"\xff\xd8\xff\xe0\0\x10JFIF\0"), Some("image/jpeg"));
+        assert_eq!(sniff(b"%PDF-1.7\n"), Some("application/pdf"));
+        let mut elf = b"\x7fELF\x02\x01\x01\0".to_vec();
+        elf.resize(64, 0);
+        assert_eq!(sniff(&elf), Some("application/x-executable"));
+        assert_eq!(sniff(b"fLaC\0\0\0\x22"), Some("audio/flac"));
+        assert_eq!(sniff(b"\0\0\0\x18ftypmp42\0\0\0\0"), Some("video/mp4"));
+        assert_eq!(sniff(b"RIFF\0\0\0\0WAVEfmt "), Some("audio/wav"));
+        assert_eq!(sniff(b"plain words"), None);
+    }
+
+    #[test]
+    fn truncated_and_random_bytes_are_handled() {
+        for magic in [&b"\x89PNG\r\n\x1a\n"[..], b"PK\x03\x04", b"RIFF\0\0\0\0WEBP", b"\0\0\0\x18ftyp"] {
+            for len in 0..magic.len() {
+                let _ = sniff(&magic[..len]);
+            }
+        }
+        // A simple LCG, so the bytes are the same on every run
+        let mut state = 0x2545_f491_4f6c_dd1du64;
+        for len in 0..512 {
+            let bytes: Vec<u8> = (0..len)
+                .map(|_| {
+                    state = state.wrapping_mul(6_364_136_223_846_793_005).wrapping_add(1);
+                    (state >> 56) as u8
+                })
+                .collect();
+            let _ = sniff(&bytes);
+        }
+    }
+
+    #[test]
+    fn extension_decides_for_text_zip_and_unknown_bytes() {
+        let dir = tempfile::tempdir().unwrap();
+        let write = |name: &str, bytes: &[u8]| {
+            let path = dir.path().join(name);
+            std::fs::write(&path, bytes).unwrap();
+            path
+        };
+
+        let png_named_txt = write("image.txt", b"\x89PNG\r\n\x1a\n\0\0\0\x0dIHDR");
+        assert_eq!(detect(&png_named_txt, false, false), Some("image/png"));
+        assert_eq!(detect(&png_named_txt, false, true), Some("text/plain"));
+        assert_eq!(detect(&write("notes.weird", b"x"), false, true), Some("text/plain"));
+
+        // A bare zip header with an office extension is taken as that document
+        let zip = b"PK\x03\x04\x14\0\0\0\0\0";
+        assert_eq!(detect(&write("archive.zip", zip), false, false), Some("application/zip"));
+        assert_eq!(
+            detect(&write("report.docx", zip), false, false),
+            Some("application/vnd.openxmlformats-officedocument.wordprocessingml.document")
+        );
+
+        assert_eq!(detect(&write("blob.dat", b"\x01\x02\x03"), false, false), Some("application/octet-stream"));
+        assert_eq!(detect(&write("song.mp3", b"\x01\x02\x03"), false, false), Some("audio/mpeg"));
+        assert_eq!(detect(&write("empty.bin", b""), false, false), Some("application/octet-stream"));
+        assert_eq!(detect(&dir.path().join("missing.png"), false, false), Some("image/png"));
+        assert_eq!(detect(dir.path(), true, false), None);
+    }
+}
@@ -1,4 +1,13 @@
 pub mod walker;
 pub mod content;
+pub mod exif;
+pub mod hash;
+pub mod frontmatter;
+pub mod media;
+pub mod mime;
 pub mod metadata;
+pub mod tags;
 pub mod coordinator;
+pub mod progress;
+pub mod volumes;
+pub mod watcher;
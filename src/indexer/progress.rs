@@ -0,0 +1,110 @@
+use std::sync::mpsc::Sender;
+
+use crate::types::IndexProgress;
+
+/// Where the indexer reports its progress.
+///
+/// The GUI uses [`UiProgress`], which forwards updates over a channel and
+/// wakes the egui event loop. Anything else (tests, benches, a headless run)
+/// can use a plain `Sender<IndexProgress>`, or its own sink that records or
+/// drops updates.
+pub trait ProgressSink: Send {
+    /// Deliver a progress update.
+    fn send(&self, progress: IndexProgress);
+
+    /// Ask whoever is displaying progress to redraw.
+    fn repaint(&self) {}
+
+    /// Send an update and request a redraw.
+    fn report(&self, progress: IndexProgress) {
+        self.send(progress);
+        self.repaint();
+    }
+}
+
+impl ProgressSink for Sender<IndexProgress> {
+    fn send(&self, progress: IndexProgress) {
+        let _ = Sender::send(self, progress);
+    }
+}
+
+/// Keep the estimate ahead of the files actually indexed.
+///
+/// The pre-scan count can fall short (a huge directory filling up while we
+/// walk, entries the count skipped), which would otherwise pin progress at
+/// 100% for the rest of the run. Once the count catches up, the estimate is
+/// pushed 5% past it so the total only ever grows.
+pub fn extend_estimate(estimate: u64, indexed: u64) -> u64 {
+    if indexed < estimate {
+        estimate
+    } else {
+        indexed + indexed / 20 + 1
+    }
+}
+
+/// Share of the pass to show as done, given the share shown so far.
+///
+/// Held just below 1 until the indexer reports Ready, and never moves
+/// backwards when the estimate is extended mid-pass.
+pub fn progress_fraction(shown: f32, indexed: u64, estimated_total: u64) -> f32 {
+    if estimated_total == 0 {
+        return shown;
+    }
+    let frac = indexed as f32 / estimated_total as f32;
+    shown.max(frac.clamp(0.0, 0.99))
+}
+
+/// Progress sink for the GUI: a channel polled by the app plus a repaint
+/// request so updates show up even when the window is idle.
+pub struct UiProgress {
+    tx: Sender<IndexProgress>,
+    ctx: eframe::egui::Context,
+}
+
+impl UiProgress {
+    pub fn new(tx: Sender<IndexProgress>, ctx: eframe::egui::Context) -> Self {
+        UiProgress { tx, ctx }
+    }
+}
+
+impl ProgressSink for UiProgress {
+    fn send(&self, progress: IndexProgress) {
+        let _ = self.tx.send(progress);
+    }
+
+    fn repaint(&self) {
+        self.ctx.request_repaint();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn estimate_grows_once_the_count_passes_it() {
+        assert_eq!(extend_estimate(1000, 400), 1000);
+        // Caught up: 5% headroom past what's indexed
+        assert_eq!(extend_estimate(1000, 1000), 1051);
+        assert_eq!(extend_estimate(1000, 4000), 4201);
+        // The estimate only ever grows, and stays ahead of the count
+        let mut estimate = 100;
+        for indexed in (0..10_000).step_by(250) {
+            let next = extend_estimate(estimate, indexed);
+            assert!(next >= estimate);
+            assert!(next > indexed);
+            estimate = next;
+        }
+    }
+
+    #[test]
+    fn fraction_stays_below_one_when_the_count_exceeds_the_estimate() {
+        // More files than the pre-scan counted
+        assert_eq!(progress_fraction(0.5, 1500, 1000), 0.99);
+        // The extended estimate would read lower; what's shown doesn't go back
+        let extended = extend_estimate(1000, 1500);
+        assert_eq!(progress_fraction(0.99, 1500, extended), 0.99);
+        assert_eq!(progress_fraction(0.2, 500, 1000), 0.5);
+        assert_eq!(progress_fraction(0.3, 0, 0), 0.3);
+    }
+}
@@ -0,0 +1,204 @@
+use std::path::Path;
+
+use lofty::config::ParseOptions;
+use lofty::prelude::*;
+use lofty::probe::Probe;
+use lofty::tag::Tag;
+
+/// Artist, album, title and genre embedded in a music file.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AudioTags {
+    pub title: Option<String>,
+    pub artist: Option<String>,
+    pub album: Option<String>,
+    pub genre: Option<String>,
+}
+
+impl AudioTags {
+    /// Searchable text for the `tags` field, one `name: value` line per tag,
+    /// e.g. `artist: Miles Davis` / `album: Kind of Blue`.
+    pub fn to_text(&self) -> String {
+        [
+            ("artist", &self.artist),
+            ("album", &self.album),
+            ("title", &self.title),
+            ("genre", &self.genre),
+        ]
+        .into_iter()
+        .filter_map(|(name, value)| value.as_ref().map(|v| format!("{}: {}", name, v)))
+        .collect::<Vec<_>>()
+        .join("\n")
+    }
+
+    fn is_empty(&self) -> bool {
+        self.title.is_none() && self.artist.is_none() && self.album.is_none() && self.genre.is_none()
+    }
+
+    /// Fill the tags that are still missing from `tag`
+    fn fill_from(&mut self, tag: &Tag) {
+        for (slot, value) in [
+            (&mut self.title, tag.title()),
+            (&mut self.artist, tag.artist()),
+            (&mut self.album, tag.album()),
+            (&mut self.genre, tag.genre()),
+        ] {
+            let Some(value) = value else {
+                continue;
+            };
+            let value = value.trim_matches(|c: char| c == '\0' || c.is_whitespace());
+            if slot.is_none() && !value.is_empty() {
+                *slot = Some(value.to_string());
+            }
+        }
+    }
+}
+
+/// Whether `path` is a music file whose tags [`extract`] can read
+pub fn is_tagged_audio(path: &Path) -> bool {
+    let ext = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or("")
+        .to_lowercase();
+    matches!(ext.as_str(), "mp3" | "flac" | "m4a")
+}
+
+/// Read the tags of an MP3 (ID3v2, then ID3v1), FLAC (Vorbis comments) or
+/// M4A file. The format is recognized by content, not by extension. Cover
+/// art and audio properties are skipped, so this is cheap whatever the file
+/// size. Untagged or damaged files return None.
+pub fn extract(path: &Path) -> Option<AudioTags> {
+    let options = ParseOptions::new().read_properties(false).read_cover_art(false);
+    let file = Probe::open(path)
+        .ok()?
+        .options(options)
+        .guess_file_type()
+        .ok()?
+        .read()
+        .ok()?;
+    let mut tags = AudioTags::default();
+    // The format's main tag first; others (an old ID3v1 after an ID3v2) fill gaps
+    let primary = file.primary_tag();
+    for tag in primary.into_iter().chain(file.tags()) {
+        tags.fill_from(tag);
+    }
+    (!tags.is_empty()).then_some(tags)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use lofty::config::WriteOptions;
+    use lofty::tag::TagType;
+
+    use super::*;
+
+    /// Three silent MPEG-1 Layer III frames (128 kbit/s, 44.1 kHz)
+    fn mp3() -> Vec<u8> {
+        let mut frame = vec![0xFF, 0xFB, 0x90, 0x64];
+        frame.resize(417, 0);
+        frame.repeat(3)
+    }
+
+    /// A FLAC stream with a STREAMINFO block (44.1 kHz, stereo, 16-bit) and padding
+    fn flac() -> Vec<u8> {
+        let mut bytes = b"fLaC\0\0\0\x22".to_vec();
+        bytes.extend([0x10, 0x00, 0x10, 0x00, 0, 0, 0, 0, 0, 0]);
+        bytes.extend([0x0A, 0xC4, 0x42, 0xF0, 0, 0, 0, 0]);
+        bytes.extend([0; 16]);
+        bytes.extend(b"\x81\0\0\x10");
+        bytes.extend([0; 16]);
+        bytes
+    }
+
+    fn write_file(dir: &Path, name: &str, bytes: &[u8]) -> PathBuf {
+        let path = dir.join(name);
+        std::fs::write(&path, bytes).unwrap();
+        path
+    }
+
+    fn save_tag(path: &Path, tag_type: TagType, fill: impl FnOnce(&mut Tag)) {
+        let mut tag = Tag::new(tag_type);
+        fill(&mut tag);
+        tag.save_to_path(path, WriteOptions::default()).unwrap();
+    }
+
+    #[test]
+    fn id3v2_tags_of_an_mp3() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = write_file(dir.path(), "track.mp3", &mp3());
+        save_tag(&path, TagType::Id3v2, |tag| {
+            tag.set_artist("Miles Davis".into());
+            tag.set_album("Kind of Blue".into());
+            tag.set_title("So What".into());
+            tag.set_genre("Jazz".into());
+        });
+        let tags = extract(&path).unwrap();
+        assert_eq!(
+            tags.to_text(),
+            "artist: Miles Davis\nalbum: Kind of Blue\ntitle: So What\ngenre: Jazz"
+        );
+    }
+
+    #[test]
+    fn id3v1_fills_what_id3v2_lacks() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = write_file(dir.path(), "track.mp3", &mp3());
+        save_tag(&path, TagType::Id3v1, |tag| {
+            tag.set_artist("Old Artist".into());
+            tag.set_album("Old Album".into());
+        });
+        save_tag(&path, TagType::Id3v2, |tag| tag.set_artist("New Artist".into()));
+        let tags = extract(&path).unwrap();
+        assert_eq!(tags.artist.as_deref(), Some("New Artist"));
+        assert_eq!(tags.album.as_deref(), Some("Old Album"));
+        assert_eq!(tags.title, None);
+    }
+
+    #[test]
+    fn vorbis_comments_of_a_flac_and_content_over_extension() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = write_file(dir.path(), "track.flac", &flac());
+        save_tag(&path, TagType::VorbisComments, |tag| {
+            tag.set_artist("Nina Simone".into());
+            tag.set_title("  Sinnerman \0".into());
+        });
+        let tags = extract(&path).unwrap();
+        assert_eq!(tags.artist.as_deref(), Some("Nina Simone"));
+        assert_eq!(tags.title.as_deref(), Some("Sinnerman"));
+
+        // Misnamed files are read by what they contain
+        let renamed = dir.path().join("track.mp3");
+        std::fs::rename(&path, &renamed).unwrap();
+        assert_eq!(extract(&renamed), Some(tags));
+    }
+
+    #[test]
+    fn untagged_damaged_and_foreign_files_give_none() {
+        let dir = tempfile::tempdir().unwrap();
+        assert_eq!(extract(&write_file(dir.path(), "plain.mp3", &mp3())), None);
+        assert_eq!(extract(&write_file(dir.path(), "text.mp3", b"not audio")), None);
+        assert_eq!(extract(&write_file(dir.path(), "empty.flac", b"")), None);
+        assert_eq!(extract(&dir.path().join("missing.mp3")), None);
+
+        let path = write_file(dir.path(), "good.mp3", &mp3());
+        save_tag(&path, TagType::Id3v2, |tag| tag.set_title("Title".into()));
+        let good = std::fs::read(&path).unwrap();
+        // Truncations of a tagged file, and garbage behind each known header
+        for len in (0..good.len()).step_by(7) {
+            let _ = extract(&write_file(dir.path(), "cut.mp3", &good[..len]));
+        }
+        let mut state = 0x9e37_79b9_7f4a_7c15u64;
+        for header in [&b"ID3\x04\0\0"[..], b"fLaC", b"\0\0\0\x18ftypM4A ", b"TAG"] {
+            for len in [4, 32, 300] {
+                let mut bytes = header.to_vec();
+                bytes.extend((0..len).map(|_| {
+                    state = state.wrapping_mul(6_364_136_223_846_793_005).wrapping_add(1);
+                    (state >> 56) as u8
+                }));
+                let _ = extract(&write_file(dir.path(), "junk.m4a", &bytes));
+            }
+        }
+    }
+}
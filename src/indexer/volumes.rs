@@ -0,0 +1,121 @@
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// How often to check whether offline roots (unplugged drives) came back
+pub const POLL_INTERVAL: std::time::Duration = std::time::Duration::from_secs(10);
+
+/// Roots last seen as the mount point of a volume of their own, such as a
+/// drive mounted at `/mnt/archive`, kept one per line in a small file so an
+/// unplugged drive is still recognized after a restart.
+struct MountedRoots {
+    file: PathBuf,
+    roots: HashSet<PathBuf>,
+}
+
+impl MountedRoots {
+    /// Load the record from `file`; a missing file records no roots.
+    fn load(file: &Path) -> Self {
+        let roots = fs::read_to_string(file)
+            .unwrap_or_default()
+            .lines()
+            .filter(|line| !line.is_empty())
+            .map(PathBuf::from)
+            .collect();
+        MountedRoots {
+            file: file.to_path_buf(),
+            roots,
+        }
+    }
+
+    fn is_available(&mut self, root: &Path) -> bool {
+        let Ok(mut entries) = fs::read_dir(root) else {
+            return false;
+        };
+        if is_mount_point(root) {
+            if self.roots.insert(root.to_path_buf()) {
+                self.save();
+            }
+            return true;
+        }
+        !self.roots.contains(root) || entries.next().is_some()
+    }
+
+    fn save(&self) {
+        let text: String = self
+            .roots
+            .iter()
+            .map(|root| format!("{}\n", root.display()))
+            .collect();
+        if let Err(e) = fs::write(&self.file, text) {
+            eprintln!("drozosearch: can't save mounted roots to {} ({})", self.file.display(), e);
+        }
+    }
+}
+
+/// Whether `root` is on another volume than its parent folder
+#[cfg(unix)]
+fn is_mount_point(root: &Path) -> bool {
+    use std::os::unix::fs::MetadataExt;
+    let Some(parent) = root.parent() else {
+        return true;
+    };
+    match (fs::metadata(root), fs::metadata(parent)) {
+        (Ok(root), Ok(parent)) => root.dev() != parent.dev(),
+        _ => false,
+    }
+}
+
+/// Whether `root` is on another volume than its parent folder; only a
+/// drive's own root (`E:\`) is known to be
+#[cfg(not(unix))]
+fn is_mount_point(root: &Path) -> bool {
+    root.parent().is_none()
+}
+
+/// Check whether a root directory is currently reachable.
+///
+/// A root on an unmounted external drive either disappears (macOS `/Volumes`,
+/// Linux `/media`) or is left behind as an empty mount point (`/mnt/...`).
+/// Both count as unavailable, so an empty mount point is never used as
+/// evidence that its indexed files were deleted. Which roots are mount
+/// points is recorded in `mounts` whenever they're seen mounted; any other
+/// empty root was just emptied.
+pub fn is_available(root: &Path, mounts: &Path) -> bool {
+    MountedRoots::load(mounts).is_available(root)
+}
+
+/// Split roots into (available, unavailable); see [`is_available`].
+pub fn partition_roots(roots: &[PathBuf], mounts: &Path) -> (Vec<PathBuf>, Vec<PathBuf>) {
+    let mut record = MountedRoots::load(mounts);
+    roots.iter().cloned().partition(|root| record.is_available(root))
+}
+
+/// Check if `path` lives under any of the given roots
+pub fn is_under_any(path: &Path, roots: &[PathBuf]) -> bool {
+    roots.iter().any(|root| path.starts_with(root))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_recorded_mount_points_are_offline_when_empty() {
+        let dir = tempfile::tempdir().unwrap();
+        let mounts = dir.path().join("mounted_roots.txt");
+        let (emptied, drive) = (dir.path().join("emptied"), dir.path().join("drive"));
+        fs::create_dir(&emptied).unwrap();
+        fs::create_dir(&drive).unwrap();
+
+        assert!(is_available(&emptied, &mounts));
+        assert!(!is_available(&dir.path().join("gone"), &mounts));
+
+        // Seen mounted by an earlier run, now just the folder it was mounted on
+        fs::write(&mounts, format!("{}\n", drive.display())).unwrap();
+        assert!(!is_available(&drive, &mounts));
+        assert!(is_available(&emptied, &mounts));
+        fs::write(drive.join("notes.txt"), "x").unwrap();
+        assert!(is_available(&drive, &mounts));
+    }
+}
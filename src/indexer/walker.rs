@@ -1,10 +1,13 @@
+use std::collections::HashSet;
 use std::path::{Path, PathBuf};
-use std::sync::mpsc::Sender;
+use std::sync::{Arc, Mutex, PoisonError};
 
-use ignore::WalkBuilder;
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use ignore::{DirEntry, Match, WalkBuilder};
 
-/// Directories to always skip
-const SKIP_DIRS: &[&str] = &[
+/// Directory names skipped unless the user configures otherwise; the
+/// default for `Config::skip_dirs`
+pub const DEFAULT_SKIP_DIRS: &[&str] = &[
     ".git",
     "node_modules",
     "target",
@@ -26,43 +29,286 @@ const SKIP_DIRS: &[&str] = &[
     ".fseventsd",
 ];
 
-/// Walk the filesystem from the given roots, sending discovered paths to the channel
-pub fn walk_paths(roots: &[PathBuf], tx: Sender<PathBuf>) {
-    for root in roots {
-        walk_single_root(root, &tx);
+/// Walk the filesystem from the given roots, each to its own depth limit,
+/// handing discovered entries to `visit` (a folder before what's in it)
+/// until it returns false.
+/// With a non-empty `allowed_extensions`, only directories and files with one
+/// of those extensions are visited. Directories named in `skip_dirs` are not
+/// entered. With `follow_symlinks`, each file is visited once however many
+/// routes lead to it, across all the roots.
+pub fn walk_entries(
+    roots: &[(PathBuf, Option<usize>)],
+    allowed_extensions: &[String],
+    skip_dirs: &[String],
+    follow_symlinks: bool,
+    mut visit: impl FnMut(DirEntry) -> bool,
+) {
+    let visited = follow_symlinks.then(|| Visited::with_roots(roots.iter().map(|(root, _)| root.as_path())));
+    for (root, max_depth) in roots {
+        let walked = walk_single_root(
+            root,
+            *max_depth,
+            allowed_extensions,
+            skip_dirs,
+            visited.as_ref(),
+            &mut visit,
+        );
+        if !walked {
+            return;
+        }
+    }
+}
+
+/// Returns false if `visit` stopped the walk
+fn walk_single_root(
+    root: &Path,
+    max_depth: Option<usize>,
+    allowed_extensions: &[String],
+    skip_dirs: &[String],
+    visited: Option<&Visited>,
+    visit: &mut impl FnMut(DirEntry) -> bool,
+) -> bool {
+    for entry in walk_builder(root, skip_dirs, visited, max_depth).build() {
+        let entry = match entry {
+            Ok(e) => e,
+            Err(_) => continue, // skip permission errors etc
+        };
+        if !is_wanted(&entry, allowed_extensions) {
+            continue;
+        }
+
+        if !visit(entry) {
+            return false;
+        }
     }
+    true
 }
 
-fn walk_single_root(root: &Path, tx: &Sender<PathBuf>) {
-    let walker = WalkBuilder::new(root)
+/// Canonical paths a walk has been to. Shared between the walks of all the
+/// roots in a pass, so a file or folder reachable through several symlinks
+/// (or from two roots) is visited once.
+#[derive(Clone, Default)]
+pub struct Visited(Arc<Mutex<HashSet<PathBuf>>>);
+
+impl Visited {
+    /// Start with the roots marked, so a symlink to one of them isn't
+    /// followed; the root's own walk covers it
+    pub fn with_roots<'a>(roots: impl IntoIterator<Item = &'a Path>) -> Self {
+        let visited = Visited::default();
+        for root in roots {
+            visited.first_visit(root);
+        }
+        visited
+    }
+
+    /// Whether this is the first route to `path`'s target. Paths that can't
+    /// be resolved are let through; the walker reports broken links itself.
+    fn first_visit(&self, path: &Path) -> bool {
+        match path.canonicalize() {
+            Ok(target) => self.0.lock().unwrap_or_else(PoisonError::into_inner).insert(target),
+            Err(_) => true,
+        }
+    }
+}
+
+/// Ignore file with gitignore syntax that works outside git repos too
+pub const IGNORE_FILENAME: &str = ".drozoignore";
+
+/// Walker settings shared by the indexing walk and the pre-scan count, so
+/// both see the same set of files. The watcher checks changed paths against
+/// the same rules with [`is_skipped_dir`] and [`is_ignored`].
+///
+/// What gets excluded, strongest rule first:
+/// 1. `skip_dirs`: folders with these names are never entered, even if an
+///    ignore file whitelists them
+/// 2. `.drozoignore`
+/// 3. `.ignore`
+/// 4. `.gitignore`, the global gitignore and `.git/info/exclude` (inside git
+///    repos only)
+///
+/// A `!pattern` in a stronger file re-includes what a weaker one ignored, and
+/// a file in a deeper directory beats one of the same kind further up.
+///
+/// Symlinks are followed when `visited` is given, and anything it has seen
+/// already is skipped (a folder with everything below it). Symlink loops are
+/// caught by the walker itself. `max_depth` counts levels below `root`.
+pub fn walk_builder(
+    root: &Path,
+    skip_dirs: &[String],
+    visited: Option<&Visited>,
+    max_depth: Option<usize>,
+) -> WalkBuilder {
+    let skip_dirs = skip_dirs.to_vec();
+    let visited = visited.cloned();
+    let mut builder = WalkBuilder::new(root);
+    builder
         .hidden(false) // include hidden files
         .git_ignore(true) // respect .gitignore
         .git_global(true)
         .git_exclude(true)
-        .follow_links(false) // avoid symlink loops
-        .max_depth(Some(20)) // don't go too deep
-        .filter_entry(|entry| {
+        .ignore(true) // respect .ignore
+        .add_custom_ignore_filename(IGNORE_FILENAME)
+        .follow_links(visited.is_some())
+        .max_depth(max_depth)
+        .filter_entry(move |entry| {
             // Skip known heavy directories
-            if entry.file_type().map_or(false, |ft| ft.is_dir()) {
-                if let Some(name) = entry.file_name().to_str() {
-                    if SKIP_DIRS.contains(&name) {
-                        return false;
-                    }
+            if entry.file_type().is_some_and(|ft| ft.is_dir())
+                && is_skipped_dir(entry.file_name(), &skip_dirs)
+            {
+                return false;
+            }
+            visited.as_ref().is_none_or(|v| v.first_visit(entry.path()))
+        });
+    builder
+}
+
+/// Whether a walk from `root` would leave `path` out because of an ignore
+/// file: the path itself or a folder on the way to it is ignored. For paths
+/// that didn't come from a walk (the watcher's); the ignore files are read on
+/// every call, so an edit to one counts from the next change.
+pub fn is_ignored(root: &Path, path: &Path, is_dir: bool) -> bool {
+    let (Ok(relative), Some(parent)) = (path.strip_prefix(root), path.parent()) else {
+        return false;
+    };
+    let ignores = IgnoreFiles::read(parent);
+    let mut below = root.to_path_buf();
+    let mut parts = relative.iter().peekable();
+    while let Some(part) = parts.next() {
+        below.push(part);
+        if ignores.ignore(&below, is_dir || parts.peek().is_some()) {
+            return true;
+        }
+    }
+    false
+}
+
+/// The ignore files of one folder
+struct DirIgnores {
+    dir: PathBuf,
+    custom: Gitignore,
+    ignore: Gitignore,
+    git: Gitignore,
+    git_exclude: Gitignore,
+    /// Top of a git repo
+    has_git: bool,
+}
+
+/// The ignore files of a folder and every folder above it, nearest first,
+/// weighed against each other the way the walker does
+struct IgnoreFiles {
+    dirs: Vec<DirIgnores>,
+    git_global: Gitignore,
+}
+
+impl IgnoreFiles {
+    fn read(dir: &Path) -> Self {
+        let dirs = dir
+            .ancestors()
+            .map(|dir| {
+                let mut git_exclude = GitignoreBuilder::new(dir);
+                git_exclude.add(dir.join(".git/info/exclude"));
+                DirIgnores {
+                    dir: dir.to_path_buf(),
+                    custom: Gitignore::new(dir.join(IGNORE_FILENAME)).0,
+                    ignore: Gitignore::new(dir.join(".ignore")).0,
+                    git: Gitignore::new(dir.join(".gitignore")).0,
+                    git_exclude: git_exclude.build().unwrap_or_else(|_| Gitignore::empty()),
+                    has_git: dir.join(".git").exists(),
+                }
+            })
+            .collect();
+        IgnoreFiles {
+            dirs,
+            git_global: Gitignore::global().0,
+        }
+    }
+
+    /// Whether `path` is ignored by the files in the folders above it. Each
+    /// kind goes by its nearest match; git's only inside a repo, and not from
+    /// above its top.
+    fn ignore(&self, path: &Path, is_dir: bool) -> bool {
+        let above: Vec<_> = self
+            .dirs
+            .iter()
+            .filter(|d| path.parent().is_some_and(|parent| parent.starts_with(&d.dir)))
+            .collect();
+        let in_repo = above.iter().any(|d| d.has_git);
+        let (mut custom, mut ignore, mut git, mut git_exclude) =
+            (Match::None, Match::None, Match::None, Match::None);
+        let mut past_repo = false;
+        for d in above {
+            if custom.is_none() {
+                custom = d.custom.matched(path, is_dir);
+            }
+            if ignore.is_none() {
+                ignore = d.ignore.matched(path, is_dir);
+            }
+            if in_repo && !past_repo {
+                if git.is_none() {
+                    git = d.git.matched(path, is_dir);
+                }
+                if git_exclude.is_none() {
+                    git_exclude = d.git_exclude.matched(path, is_dir);
                 }
             }
-            true
+            past_repo |= d.has_git;
+        }
+        let git_global = if in_repo {
+            self.git_global.matched(path, is_dir)
+        } else {
+            Match::None
+        };
+        custom
+            .or(ignore)
+            .or(git)
+            .or(git_exclude)
+            .or(git_global)
+            .is_ignore()
+    }
+}
+
+/// Whether a directory with this name is in the `skip_dirs` list
+pub fn is_skipped_dir(name: &std::ffi::OsStr, skip_dirs: &[String]) -> bool {
+    name.to_str()
+        .is_some_and(|name| skip_dirs.iter().any(|skip| skip == name))
+}
+
+/// Check an entry against the extension allowlist. Directories always pass,
+/// and an empty list allows everything.
+pub fn is_wanted(entry: &DirEntry, allowed_extensions: &[String]) -> bool {
+    let is_dir = entry.file_type().is_some_and(|ft| ft.is_dir());
+    is_wanted_path(entry.path(), is_dir, allowed_extensions)
+}
+
+/// [`is_wanted`] for a path that didn't come from the walker
+pub fn is_wanted_path(path: &Path, is_dir: bool, allowed_extensions: &[String]) -> bool {
+    if allowed_extensions.is_empty() || is_dir {
+        return true;
+    }
+    path
+        .extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|ext| {
+            allowed_extensions
+                .iter()
+                .any(|allowed| allowed.trim_start_matches('.').eq_ignore_ascii_case(ext))
         })
-        .build();
+}
 
-    for entry in walker {
-        let entry = match entry {
-            Ok(e) => e,
-            Err(_) => continue, // skip permission errors etc
-        };
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-        let path = entry.into_path();
-        if tx.send(path).is_err() {
-            return; // receiver dropped, stop walking
-        }
+    #[test]
+    fn allowlist_keeps_listed_extensions_and_all_directories() {
+        let allowed = vec!["rs".to_string(), ".MD".to_string()];
+        assert!(is_wanted_path(Path::new("/r/main.rs"), false, &allowed));
+        assert!(is_wanted_path(Path::new("/r/README.md"), false, &allowed));
+        assert!(is_wanted_path(Path::new("/r/LIB.RS"), false, &allowed));
+        assert!(!is_wanted_path(Path::new("/r/notes.txt"), false, &allowed));
+        assert!(!is_wanted_path(Path::new("/r/Makefile"), false, &allowed));
+        assert!(is_wanted_path(Path::new("/r/src.txt"), true, &allowed));
+        // No list: everything
+        assert!(is_wanted_path(Path::new("/r/notes.txt"), false, &[]));
     }
 }
@@ -0,0 +1,257 @@
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::Sender;
+use std::time::Duration;
+
+use notify_debouncer_mini::notify::{self, Event, EventHandler, RecommendedWatcher, RecursiveMode, WatcherKind};
+use notify_debouncer_mini::{new_debouncer_opt, Config, DebounceEventResult, Debouncer};
+
+use crate::indexer::coordinator::IndexerCommand;
+use crate::indexer::walker;
+
+/// Changes are sent once a path has been quiet this long, or every this
+/// long while it keeps changing (e.g. a growing log file)
+pub const DEBOUNCE: Duration = Duration::from_millis(500);
+
+/// Watches the indexed folders and sends changed paths to the indexer as
+/// [`IndexerCommand::FilesChanged`]. Stops when dropped.
+pub struct Watcher {
+    _debouncer: Debouncer<ChangesOnly>,
+}
+
+/// The platform's watcher, passing on only events that change something.
+/// Files being opened and read are reported too, and the indexer reads what
+/// it indexes, so with those every batch would set off the next.
+struct ChangesOnly(RecommendedWatcher);
+
+impl notify::Watcher for ChangesOnly {
+    fn new<F: EventHandler>(mut handler: F, config: notify::Config) -> notify::Result<Self> {
+        let handler = move |event: notify::Result<Event>| {
+            if !event.as_ref().is_ok_and(|event| event.kind.is_access()) {
+                handler.handle_event(event);
+            }
+        };
+        RecommendedWatcher::new(handler, config).map(ChangesOnly)
+    }
+
+    fn watch(&mut self, path: &Path, recursive_mode: RecursiveMode) -> notify::Result<()> {
+        self.0.watch(path, recursive_mode)
+    }
+
+    fn unwatch(&mut self, path: &Path) -> notify::Result<()> {
+        self.0.unwatch(path)
+    }
+
+    fn kind() -> WatcherKind {
+        RecommendedWatcher::kind()
+    }
+}
+
+/// Which changed paths the indexer cares about
+struct Filter {
+    roots: Vec<PathBuf>,
+    excluded: Vec<PathBuf>,
+    skip_dirs: Vec<String>,
+    max_depth: Option<usize>,
+}
+
+impl Filter {
+    /// Same rules as the walker: under a root, not under `excluded`, no
+    /// skipped folder on the way, no deeper than `max_depth` and not left out
+    /// by an ignore file
+    fn wants(&self, path: &Path) -> bool {
+        if self.excluded.iter().any(|dir| path.starts_with(dir)) {
+            return false;
+        }
+        let Some((root, relative)) = self
+            .roots
+            .iter()
+            .filter_map(|root| Some((root, path.strip_prefix(root).ok()?)))
+            .min_by_key(|(_, relative)| relative.components().count())
+        else {
+            return false;
+        };
+        let depth = relative.components().count();
+        if self.max_depth.is_some_and(|max| depth > max) {
+            return false;
+        }
+        // Everything above the path is a folder; the path itself only counts
+        // if it (still) is one
+        let is_dir = path.is_dir();
+        let mut folders: Vec<_> = relative.parent().into_iter().flat_map(Path::iter).collect();
+        if is_dir {
+            folders.extend(relative.file_name());
+        }
+        !folders.into_iter().any(|name| walker::is_skipped_dir(name, &self.skip_dirs))
+            && !walker::is_ignored(root, path, is_dir)
+    }
+}
+
+/// Start watching `roots` down to `max_depth`, skipping the same `skip_dirs`
+/// and ignore files the walker does and anything under `excluded` (the index
+/// itself, which changes on every commit). Returns `None` if the platform's
+/// watcher can't be started; the indexer then falls back to a timed
+/// incremental pass.
+pub fn watch(
+    roots: &[PathBuf],
+    excluded: &[PathBuf],
+    skip_dirs: &[String],
+    max_depth: Option<usize>,
+    tx: Sender<IndexerCommand>,
+) -> Option<Watcher> {
+    let filter = Filter {
+        roots: roots.to_vec(),
+        excluded: excluded.to_vec(),
+        skip_dirs: skip_dirs.to_vec(),
+        max_depth,
+    };
+    let handler = move |result: DebounceEventResult| {
+        let command = match result {
+            Ok(events) => {
+                let paths: Vec<PathBuf> = events
+                    .into_iter()
+                    .map(|event| event.path)
+                    .filter(|path| filter.wants(path))
+                    .collect();
+                if paths.is_empty() {
+                    return;
+                }
+                IndexerCommand::FilesChanged(paths)
+            }
+            // Events may have been lost (e.g. the kernel queue overflowed)
+            Err(e) => {
+                eprintln!("drozosearch: file watcher error ({}), rescanning", e);
+                IndexerCommand::Rescan
+            }
+        };
+        // Nothing to do if the indexer is gone; the app is shutting down
+        let _ = tx.send(command);
+    };
+
+    let config = Config::default().with_timeout(DEBOUNCE);
+    let mut debouncer = match new_debouncer_opt::<_, ChangesOnly>(config, handler) {
+        Ok(debouncer) => debouncer,
+        Err(e) => {
+            eprintln!("drozosearch: can't watch for file changes ({})", e);
+            return None;
+        }
+    };
+    for root in roots {
+        // Symlinked folders aren't followed; the next index pass picks up
+        // their changes
+        if let Err(e) = debouncer.watcher().watch(root, RecursiveMode::Recursive) {
+            eprintln!(
+                "drozosearch: can't watch {} for changes, it won't update live ({})",
+                root.display(),
+                e
+            );
+        }
+    }
+    Some(Watcher { _debouncer: debouncer })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::mpsc::{self, Receiver};
+    use std::time::Instant;
+
+    use super::*;
+
+    fn filter(root: &Path) -> Filter {
+        Filter {
+            roots: vec![root.to_path_buf()],
+            excluded: vec![root.join("data/index")],
+            skip_dirs: vec!["node_modules".to_string()],
+            max_depth: Some(2),
+        }
+    }
+
+    /// Changed paths reported within a few seconds, or nothing on timeout
+    fn changed_paths(rx: &Receiver<IndexerCommand>, want: &Path) -> Vec<PathBuf> {
+        let deadline = Instant::now() + Duration::from_secs(10);
+        let mut seen = Vec::new();
+        while let Some(left) = deadline.checked_duration_since(Instant::now()) {
+            match rx.recv_timeout(left) {
+                Ok(IndexerCommand::FilesChanged(paths)) => {
+                    seen.extend(paths);
+                    if seen.iter().any(|path| path == want) {
+                        break;
+                    }
+                }
+                Ok(_) => {}
+                Err(_) => break,
+            }
+        }
+        seen
+    }
+
+    #[test]
+    fn filter_follows_the_walker_rules() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        std::fs::create_dir_all(root.join("node_modules")).unwrap();
+        let filter = filter(root);
+
+        assert!(filter.wants(&root.join("notes.txt")));
+        assert!(filter.wants(&root.join("docs/notes.txt")));
+        assert!(!filter.wants(&root.join("a/b/too-deep.txt")));
+        assert!(!filter.wants(&root.join("node_modules/lib.js")));
+        assert!(!filter.wants(&root.join("node_modules")));
+        // A file may be named like a skipped folder
+        assert!(filter.wants(&root.join("docs/node_modules")));
+        assert!(!filter.wants(&root.join("data/index/meta.json")));
+        assert!(!filter.wants(Path::new("/elsewhere/notes.txt")));
+    }
+
+    #[test]
+    fn filter_follows_the_ignore_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        std::fs::create_dir_all(root.join(".git")).unwrap();
+        std::fs::create_dir_all(root.join("docs")).unwrap();
+        std::fs::write(root.join(walker::IGNORE_FILENAME), "*.log\ndrafts/\n!keep.bak\n").unwrap();
+        std::fs::write(root.join(".gitignore"), "*.bak\n").unwrap();
+        std::fs::write(root.join("docs/.ignore"), "secret.txt\n").unwrap();
+        let filter = filter(root);
+
+        assert!(!filter.wants(&root.join("debug.log")));
+        assert!(!filter.wants(&root.join("drafts/plan.txt")));
+        assert!(!filter.wants(&root.join("old.bak")));
+        // A stronger ignore file re-includes it
+        assert!(filter.wants(&root.join("keep.bak")));
+        assert!(!filter.wants(&root.join("docs/secret.txt")));
+        assert!(filter.wants(&root.join("secret.txt")));
+        assert!(filter.wants(&root.join("docs/notes.txt")));
+    }
+
+    #[test]
+    fn reports_changes_under_the_roots() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().canonicalize().unwrap();
+        std::fs::create_dir_all(root.join("node_modules")).unwrap();
+        let (tx, rx) = mpsc::channel();
+        let watcher = watch(std::slice::from_ref(&root), &[], &["node_modules".to_string()], None, tx);
+        assert!(watcher.is_some());
+
+        std::fs::write(root.join("node_modules/lib.js"), "skipped").unwrap();
+        let created = root.join("notes.txt");
+        std::fs::write(&created, "hello").unwrap();
+        let seen = changed_paths(&rx, &created);
+        assert!(seen.contains(&created), "{:?}", seen);
+        assert!(!seen.iter().any(|path| path.starts_with(root.join("node_modules"))), "{:?}", seen);
+
+        std::fs::remove_file(&created).unwrap();
+        assert!(changed_paths(&rx, &created).contains(&created));
+    }
+
+    #[test]
+    fn stops_when_dropped() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().canonicalize().unwrap();
+        let (tx, rx) = mpsc::channel();
+        drop(watch(std::slice::from_ref(&root), &[], &[], None, tx));
+
+        std::fs::write(root.join("notes.txt"), "hello").unwrap();
+        // The handler (and with it the sender) is gone
+        assert!(rx.recv_timeout(Duration::from_secs(2)).is_err());
+    }
+}
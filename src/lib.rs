@@ -0,0 +1,11 @@
+//! Indexing and search core of drozoSearch, independent of the GUI.
+//! The `drozosearch` binary builds the desktop app on top of it; benches use
+//! it directly.
+
+pub mod api;
+pub mod config;
+pub mod index;
+pub mod indexer;
+pub mod opener;
+pub mod trash;
+pub mod types;
@@ -1,11 +1,17 @@
 mod app;
-mod config;
-mod index;
-mod indexer;
-mod types;
+mod cli;
+mod export;
+mod git;
+mod history;
+mod hotkey;
+mod theme;
+mod thumbnail;
 
 use eframe::egui;
 
+const DEFAULT_SIZE: [f32; 2] = [900.0, 600.0];
+const MIN_SIZE: [f32; 2] = [600.0, 400.0];
+
 fn load_icon() -> egui::IconData {
     let icon_bytes = include_bytes!("../assets/icon.png");
     let img = image::load_from_memory(icon_bytes)
@@ -20,14 +26,23 @@ fn load_icon() -> egui::IconData {
 }
 
 fn main() -> eframe::Result<()> {
-    let icon = load_icon();
+    // `--query` searches from the terminal without opening a window
+    match cli::parse_args(std::env::args()) {
+        Ok(Some(search)) => std::process::exit(cli::run(&search)),
+        Ok(None) => {}
+        // Usage errors exit with 2, like failed searches; --help with 0
+        Err(e) => e.exit(),
+    }
 
+    let icon = load_icon();
+    // The size (and position) of the last run is restored by eframe
+    let viewport = egui::ViewportBuilder::default()
+        .with_inner_size(DEFAULT_SIZE)
+        .with_min_inner_size(MIN_SIZE)
+        .with_title("drozoSearch")
+        .with_icon(icon);
     let options = eframe::NativeOptions {
-        viewport: egui::ViewportBuilder::default()
-            .with_inner_size([900.0, 600.0])
-            .with_min_inner_size([600.0, 400.0])
-            .with_title("drozoSearch")
-            .with_icon(icon),
+        viewport,
         ..Default::default()
     };
 
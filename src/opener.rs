@@ -0,0 +1,182 @@
+use std::ffi::OsString;
+use std::path::Path;
+use std::process::Command;
+
+use crate::config::Config;
+use crate::types::FileCategory;
+
+const PATH_PLACEHOLDER: &str = "{path}";
+
+/// Open a file with the command configured for its category, falling back to
+/// the OS default when none is set or the command fails to start.
+pub fn open(config: &Config, path: &Path, is_dir: bool) {
+    let category = FileCategory::of(path, is_dir);
+    if let Some(template) = config.open_commands.get(&category) {
+        match command_from_template(template, path).map(|mut cmd| cmd.spawn()) {
+            Some(Ok(_)) => return,
+            Some(Err(e)) => eprintln!("drozosearch: failed to run `{}`: {}", template, e),
+            None => eprintln!("drozosearch: empty open command for {:?}", category),
+        }
+    }
+    let _ = open::that(path);
+}
+
+/// Build the command for a template such as `open -a "Preview" {path}`.
+///
+/// The command is started directly, not through a shell, so the path is
+/// always passed as one argument no matter what characters it contains. A
+/// template without `{path}` gets the path appended as its last argument.
+pub fn command_from_template(template: &str, path: &Path) -> Option<Command> {
+    let mut words = split_words(template).into_iter();
+    let program = words.next()?;
+
+    let mut args: Vec<OsString> = words.map(|w| substitute(&w, path)).collect();
+    if !template.contains(PATH_PLACEHOLDER) {
+        args.push(path.as_os_str().to_owned());
+    }
+
+    let mut cmd = Command::new(substitute(&program, path));
+    cmd.args(args);
+    Some(cmd)
+}
+
+/// Start a terminal in `dir`: the configured `terminal_command` if there is
+/// one, else the platform's terminal. Waits for AppleScript on macOS, so
+/// call it off the UI thread. Returns false if no terminal could be started.
+pub fn open_terminal(config: &Config, dir: &Path) -> bool {
+    let template = config.terminal_command.trim();
+    if !template.is_empty() {
+        let mut words = split_words(template).into_iter();
+        if let Some(program) = words.next() {
+            let mut cmd = Command::new(substitute(&program, dir));
+            cmd.args(words.map(|w| substitute(&w, dir))).current_dir(dir);
+            match cmd.spawn() {
+                Ok(_) => return true,
+                Err(e) => eprintln!("drozosearch: failed to run `{}`: {}", template, e),
+            }
+        }
+    }
+    platform_terminal(dir)
+}
+
+#[cfg(target_os = "macos")]
+fn platform_terminal(dir: &Path) -> bool {
+    let quoted = dir.to_string_lossy().replace('\\', "\\\\").replace('"', "\\\"");
+    let iterm = Path::new("/Applications/iTerm.app").exists();
+    let script = if iterm {
+        format!(
+            r#"tell application "iTerm"
+    create window with default profile
+    tell current session of current window to write text "cd " & quoted form of "{}"
+    activate
+end tell"#,
+            quoted
+        )
+    } else {
+        format!(
+            r#"tell application "Terminal"
+    do script "cd " & quoted form of "{}"
+    activate
+end tell"#,
+            quoted
+        )
+    };
+    Command::new("osascript")
+        .arg("-e")
+        .arg(&script)
+        .status()
+        .is_ok_and(|s| s.success())
+}
+
+#[cfg(target_os = "windows")]
+fn platform_terminal(dir: &Path) -> bool {
+    // Windows Terminal where installed, the classic console otherwise
+    Command::new("wt.exe").arg("-d").arg(dir).spawn().is_ok()
+        || Command::new("cmd")
+            .args(["/C", "start", "cmd"])
+            .current_dir(dir)
+            .spawn()
+            .is_ok()
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+fn platform_terminal(dir: &Path) -> bool {
+    // $TERMINAL first, then the Debian alternative and common terminals; all
+    // of them start their shell in the working directory
+    let preferred = std::env::var("TERMINAL").ok().filter(|t| !t.trim().is_empty());
+    let candidates = preferred.into_iter().chain(
+        ["x-terminal-emulator", "gnome-terminal", "konsole", "xfce4-terminal", "xterm"]
+            .map(String::from),
+    );
+    for program in candidates {
+        if Command::new(&program).current_dir(dir).spawn().is_ok() {
+            return true;
+        }
+    }
+    false
+}
+
+fn substitute(word: &str, path: &Path) -> OsString {
+    if word == PATH_PLACEHOLDER {
+        // Keep non-UTF-8 paths intact when the path is a whole argument
+        path.as_os_str().to_owned()
+    } else {
+        word.replace(PATH_PLACEHOLDER, &path.to_string_lossy()).into()
+    }
+}
+
+/// Split a template into words. Double quotes group words on every platform.
+/// On Unix, single quotes group too and a backslash escapes the next
+/// character; on Windows both are literal so `C:\Program Files\...` works.
+fn split_words(template: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut current = String::new();
+    let mut in_word = false;
+    let mut quote: Option<char> = None;
+    let mut chars = template.chars();
+
+    while let Some(c) = chars.next() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some('"'), '\\') if cfg!(unix) => {
+                // Inside double quotes only `\"` and `\\` are escapes
+                match chars.clone().next() {
+                    Some(next @ ('"' | '\\')) => {
+                        current.push(next);
+                        chars.next();
+                    }
+                    _ => current.push('\\'),
+                }
+            }
+            (Some(_), c) => current.push(c),
+            (None, '"') => {
+                quote = Some('"');
+                in_word = true;
+            }
+            (None, '\'') if cfg!(unix) => {
+                quote = Some('\'');
+                in_word = true;
+            }
+            (None, '\\') if cfg!(unix) => {
+                if let Some(next) = chars.next() {
+                    current.push(next);
+                }
+                in_word = true;
+            }
+            (None, c) if c.is_whitespace() => {
+                if in_word {
+                    words.push(std::mem::take(&mut current));
+                    in_word = false;
+                }
+            }
+            (None, c) => {
+                current.push(c);
+                in_word = true;
+            }
+        }
+    }
+    if in_word {
+        words.push(current);
+    }
+    words
+}
@@ -0,0 +1,94 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use eframe::egui;
+use eframe::egui::ecolor::HsvaGamma;
+use eframe::egui::Color32;
+
+use drozosearch::config::Theme;
+
+/// Whether the frame being drawn uses the light palette. Colors are picked
+/// all over the UI code, often far from a `Ui`, so [`sync`] records it once
+/// per frame for [`gray`] and [`rgb`].
+static LIGHT: AtomicBool = AtomicBool::new(false);
+
+/// Install both palettes and pick the one `theme` asks for. With
+/// `Theme::System` egui switches between them as the OS preference changes.
+pub fn apply_theme(ctx: &egui::Context, theme: Theme) {
+    ctx.set_visuals_of(egui::Theme::Dark, dark_visuals());
+    ctx.set_visuals_of(egui::Theme::Light, light_visuals());
+    ctx.all_styles_mut(|style| style.spacing.item_spacing = egui::vec2(6.0, 1.0));
+    ctx.set_theme(match theme {
+        Theme::Dark => egui::ThemePreference::Dark,
+        Theme::Light => egui::ThemePreference::Light,
+        Theme::System => egui::ThemePreference::System,
+    });
+    sync(ctx);
+}
+
+/// Follow the palette egui resolved for this frame
+pub fn sync(ctx: &egui::Context) {
+    LIGHT.store(ctx.theme() == egui::Theme::Light, Ordering::Relaxed);
+}
+
+fn is_light() -> bool {
+    LIGHT.load(Ordering::Relaxed)
+}
+
+fn dark_visuals() -> egui::Visuals {
+    let mut visuals = egui::Visuals::dark();
+    visuals.window_shadow = egui::epaint::Shadow::NONE;
+    visuals.widgets.noninteractive.bg_fill = Color32::from_gray(22);
+    visuals.widgets.inactive.bg_fill = Color32::from_gray(32);
+    visuals.widgets.hovered.bg_fill = Color32::from_gray(42);
+    visuals.widgets.active.bg_fill = Color32::from_gray(50);
+    visuals.selection.bg_fill = Color32::from_rgb(35, 75, 130);
+    visuals.extreme_bg_color = Color32::from_gray(16);
+    visuals
+}
+
+/// The dark palette mirrored, so both themes have the same contrast steps
+fn light_visuals() -> egui::Visuals {
+    let mut visuals = egui::Visuals::light();
+    visuals.window_shadow = egui::epaint::Shadow::NONE;
+    visuals.widgets.noninteractive.bg_fill = Color32::from_gray(233);
+    visuals.widgets.inactive.bg_fill = Color32::from_gray(223);
+    visuals.widgets.hovered.bg_fill = Color32::from_gray(213);
+    visuals.widgets.active.bg_fill = Color32::from_gray(205);
+    visuals.selection.bg_fill = Color32::from_rgb(170, 200, 240);
+    visuals.extreme_bg_color = Color32::from_gray(239);
+    visuals
+}
+
+/// A gray given as its dark-theme level. In light mode it's mirrored
+/// (`255 - level`): dark backgrounds turn light and light text turns dark.
+pub fn gray(level: u8) -> Color32 {
+    if is_light() {
+        Color32::from_gray(255 - level)
+    } else {
+        Color32::from_gray(level)
+    }
+}
+
+/// A color given as its dark-theme value. In light mode, bright colors (text,
+/// status dots, badges) are darkened to stay readable on a light background,
+/// and dark tinted backgrounds become pale tints of the same hue.
+pub fn rgb(r: u8, g: u8, b: u8) -> Color32 {
+    let color = Color32::from_rgb(r, g, b);
+    if !is_light() {
+        return color;
+    }
+    let hsva = HsvaGamma::from(color);
+    let adjusted = if hsva.v >= 0.5 {
+        HsvaGamma {
+            v: hsva.v * 0.6,
+            ..hsva
+        }
+    } else {
+        HsvaGamma {
+            s: hsva.s * 0.4,
+            v: 1.0 - hsva.v * 0.3,
+            ..hsva
+        }
+    };
+    adjusted.into()
+}
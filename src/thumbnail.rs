@@ -0,0 +1,132 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::thread;
+
+use eframe::egui;
+
+/// Edge length thumbnails are decoded at, in pixels. Drawn at about half
+/// that, so they stay sharp on high-DPI screens.
+const THUMBNAIL_SIZE: u32 = 32;
+
+/// Thumbnails kept as textures at once; the oldest are dropped past this
+const MAX_CACHED: usize = 300;
+
+/// Images larger than this on disk aren't worth decoding for a row icon
+const MAX_FILE_SIZE: u64 = 64 * 1024 * 1024;
+
+/// Whether `path` looks like an image the `image` crate can decode
+pub fn is_thumbnailable(path: &Path) -> bool {
+    let ext = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or("")
+        .to_lowercase();
+    matches!(
+        ext.as_str(),
+        "png" | "jpg" | "jpeg" | "gif" | "webp" | "bmp" | "ico" | "tif" | "tiff"
+    )
+}
+
+/// Lazily decodes small image thumbnails on a background thread.
+///
+/// Works like the git status cache: the UI asks for the thumbnail of rows it
+/// is drawing, unknown paths are queued and decoded asynchronously, and the
+/// texture shows up on a later frame. Only rows on screen ask, and the cache
+/// is bounded, so a folder of thousands of photos never decodes them all.
+/// Files that fail to decode are remembered as `None`.
+pub struct ThumbnailCache {
+    request_tx: Sender<PathBuf>,
+    response_rx: Receiver<(PathBuf, Option<egui::ColorImage>)>,
+    textures: HashMap<PathBuf, Option<egui::TextureHandle>>,
+    // Cached paths, oldest first
+    order: VecDeque<PathBuf>,
+    pending: HashSet<PathBuf>,
+}
+
+impl ThumbnailCache {
+    pub fn new(ctx: egui::Context) -> Self {
+        let (request_tx, request_rx) = mpsc::channel::<PathBuf>();
+        let (response_tx, response_rx) = mpsc::channel();
+        thread::spawn(move || decode_thread(request_rx, response_tx, ctx));
+        ThumbnailCache {
+            request_tx,
+            response_rx,
+            textures: HashMap::new(),
+            order: VecDeque::new(),
+            pending: HashSet::new(),
+        }
+    }
+
+    /// Thumbnail of `path` if it's ready; queues it for decoding otherwise.
+    pub fn thumbnail(&mut self, ctx: &egui::Context, path: &Path) -> Option<egui::TextureHandle> {
+        while let Ok((p, image)) = self.response_rx.try_recv() {
+            self.pending.remove(&p);
+            let texture = image.map(|image| {
+                ctx.load_texture(
+                    format!("thumbnail:{}", p.display()),
+                    image,
+                    egui::TextureOptions::LINEAR,
+                )
+            });
+            self.insert(p, texture);
+        }
+
+        match self.textures.get(path) {
+            Some(texture) => texture.clone(),
+            None => {
+                if self.pending.insert(path.to_path_buf()) {
+                    let _ = self.request_tx.send(path.to_path_buf());
+                }
+                None
+            }
+        }
+    }
+
+    fn insert(&mut self, path: PathBuf, texture: Option<egui::TextureHandle>) {
+        if self.textures.insert(path.clone(), texture).is_none() {
+            self.order.push_back(path);
+        }
+        while self.order.len() > MAX_CACHED {
+            if let Some(oldest) = self.order.pop_front() {
+                // Dropping the last handle frees the texture
+                self.textures.remove(&oldest);
+            }
+        }
+    }
+}
+
+fn decode_thread(
+    rx: Receiver<PathBuf>,
+    tx: Sender<(PathBuf, Option<egui::ColorImage>)>,
+    ctx: egui::Context,
+) {
+    while let Ok(first) = rx.recv() {
+        // Newest requests first: after a fast scroll those are the rows on screen
+        let mut queue: Vec<PathBuf> = std::iter::once(first).chain(rx.try_iter()).collect();
+        while let Some(path) = queue.pop() {
+            let image = decode(&path);
+            if tx.send((path, image)).is_err() {
+                return;
+            }
+            ctx.request_repaint();
+            queue.extend(rx.try_iter());
+        }
+    }
+}
+
+fn decode(path: &Path) -> Option<egui::ColorImage> {
+    let size = std::fs::metadata(path).ok()?.len();
+    if size > MAX_FILE_SIZE {
+        return None;
+    }
+    let img = image::open(path)
+        .ok()?
+        .thumbnail(THUMBNAIL_SIZE, THUMBNAIL_SIZE)
+        .into_rgba8();
+    let (w, h) = img.dimensions();
+    Some(egui::ColorImage::from_rgba_unmultiplied(
+        [w as usize, h as usize],
+        &img.into_raw(),
+    ))
+}
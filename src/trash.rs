@@ -0,0 +1,89 @@
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Move a file or folder to the trash / recycle bin, where it can be
+/// restored from the usual way.
+pub fn move_to_trash(path: &Path) -> io::Result<()> {
+    if std::fs::symlink_metadata(path).is_err() {
+        return Err(io::Error::new(io::ErrorKind::NotFound, "file no longer exists"));
+    }
+    ::trash::delete(path).map_err(io::Error::other)
+}
+
+/// Delete a file, or a folder with everything in it, without the trash.
+pub fn delete_permanently(path: &Path) -> io::Result<()> {
+    let meta = std::fs::symlink_metadata(path)?;
+    if meta.is_dir() {
+        std::fs::remove_dir_all(path)
+    } else {
+        std::fs::remove_file(path)
+    }
+}
+
+/// What became of a batch of deletions
+pub struct DeleteOutcome {
+    pub permanently: bool,
+    pub deleted: Vec<PathBuf>,
+    pub failures: Vec<(PathBuf, io::Error)>,
+}
+
+/// Trash (or permanently delete) each of `paths`, carrying on past
+/// failures. Trashing can be slow (a large folder on another volume is
+/// copied), so the app runs this off the UI thread.
+pub fn delete_all(paths: Vec<PathBuf>, permanently: bool) -> DeleteOutcome {
+    let mut outcome = DeleteOutcome {
+        permanently,
+        deleted: Vec::new(),
+        failures: Vec::new(),
+    };
+    for path in paths {
+        let result = if permanently {
+            delete_permanently(&path)
+        } else {
+            move_to_trash(&path)
+        };
+        match result {
+            Ok(()) => outcome.deleted.push(path),
+            Err(e) => outcome.failures.push((path, e)),
+        }
+    }
+    outcome
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn permanent_deletion_removes_files_and_folders() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("a.txt");
+        let folder = dir.path().join("sub");
+        std::fs::write(&file, "a").unwrap();
+        std::fs::create_dir_all(folder.join("deeper")).unwrap();
+        std::fs::write(folder.join("deeper/b.txt"), "b").unwrap();
+
+        let outcome = delete_all(vec![file.clone(), folder.clone()], true);
+        assert_eq!(outcome.deleted, [file.clone(), folder.clone()]);
+        assert!(outcome.failures.is_empty());
+        assert!(!file.exists() && !folder.exists());
+    }
+
+    #[test]
+    fn missing_files_are_failures_not_errors_for_the_rest() {
+        let dir = tempfile::tempdir().unwrap();
+        let kept = dir.path().join("kept.txt");
+        let missing = dir.path().join("missing.txt");
+        std::fs::write(&kept, "k").unwrap();
+
+        let outcome = delete_all(vec![missing.clone(), kept.clone()], true);
+        assert_eq!(outcome.deleted, [kept]);
+        assert_eq!(outcome.failures.len(), 1);
+        assert_eq!(outcome.failures[0].0, missing);
+        assert_eq!(outcome.failures[0].1.kind(), io::ErrorKind::NotFound);
+
+        let outcome = delete_all(vec![missing], false);
+        assert!(outcome.deleted.is_empty());
+        assert_eq!(outcome.failures[0].1.kind(), io::ErrorKind::NotFound);
+    }
+}
@@ -1,6 +1,7 @@
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
 pub enum MatchType {
     FileName,
     Content,
@@ -17,36 +18,194 @@ impl std::fmt::Display for MatchType {
     }
 }
 
-#[derive(Debug, Clone)]
+/// One hit. Serializes (e.g. to JSON) with `file_path` as a string; paths
+/// that aren't valid UTF-8 are converted lossily rather than failing.
+#[derive(Debug, Clone, serde::Serialize)]
 pub struct SearchResult {
     pub file_name: String,
+    #[serde(serialize_with = "serialize_path_lossy")]
     pub file_path: PathBuf,
     pub match_type: MatchType,
     pub file_size: u64,
     pub modified: i64,
     pub score: f32,
+    /// Excerpt around the best content match, on a single line
     pub content_snippet: Option<String>,
+    /// Byte ranges of the matched words within `content_snippet`
+    pub snippet_highlights: Vec<std::ops::Range<usize>>,
     pub is_dir: bool,
+    /// Dimensions/duration/codec of media files, e.g. `1920×1080 · h264`
+    pub media_summary: Option<String>,
+    /// Content type sniffed at index time, e.g. `image/png`
+    pub mime_type: Option<String>,
+    /// Only the first `max_file_size` bytes of the content are indexed
+    pub content_truncated: bool,
+    /// Hash of the file's bytes, when duplicate detection is on
+    pub content_hash: Option<String>,
+    /// Owning user and group; `None` where files have no Unix owner
+    pub owner: Option<String>,
+    pub group: Option<String>,
+}
+
+impl SearchResult {
+    pub fn category(&self) -> FileCategory {
+        FileCategory::of(&self.file_path, self.is_dir)
+    }
+}
+
+fn serialize_path_lossy<S: serde::Serializer>(path: &Path, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&path.to_string_lossy())
+}
+
+/// Coarse file kind, following the groupings of the result list icons.
+/// Used to pick a per-category opener.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FileCategory {
+    Directory,
+    Code,
+    Data,
+    Document,
+    Pdf,
+    Image,
+    Audio,
+    Video,
+    Archive,
+    Binary,
+    Other,
+}
+
+/// Lowercase extensions of each category — the one place files are grouped
+/// by type, for openers, media probing and `category:` queries alike.
+/// Anything not listed is [`FileCategory::Other`].
+const CATEGORY_EXTENSIONS: &[(FileCategory, &[&str])] = &[
+    (
+        FileCategory::Code,
+        &[
+            "rs", "js", "jsx", "mjs", "ts", "tsx", "py", "go", "c", "h", "cpp", "hpp", "cc",
+            "cxx", "java", "kt", "kts", "rb", "swift", "sh", "bash", "zsh", "html", "htm", "css",
+            "scss", "sass", "vue", "svelte",
+        ],
+    ),
+    (
+        FileCategory::Data,
+        &["json", "yaml", "yml", "toml", "xml", "csv", "sql"],
+    ),
+    (
+        FileCategory::Document,
+        &["md", "markdown", "txt", "doc", "docx"],
+    ),
+    (FileCategory::Pdf, &["pdf"]),
+    (
+        FileCategory::Image,
+        &["png", "jpg", "jpeg", "gif", "svg", "webp", "bmp", "ico", "tif", "tiff"],
+    ),
+    (
+        FileCategory::Audio,
+        &["mp3", "wav", "flac", "ogg", "aac", "m4a"],
+    ),
+    (FileCategory::Video, &["mp4", "mkv", "avi", "mov", "webm"]),
+    (
+        FileCategory::Archive,
+        &["zip", "tar", "gz", "bz2", "xz", "7z", "rar"],
+    ),
+    (FileCategory::Binary, &["exe", "dll", "so", "dylib"]),
+];
+
+/// `category:` names and the categories each one covers
+const CATEGORY_NAMES: &[(&str, &[FileCategory])] = &[
+    ("code", &[FileCategory::Code]),
+    ("data", &[FileCategory::Data]),
+    ("documents", &[FileCategory::Document, FileCategory::Pdf]),
+    ("docs", &[FileCategory::Document, FileCategory::Pdf]),
+    ("pdf", &[FileCategory::Pdf]),
+    (
+        "media",
+        &[FileCategory::Image, FileCategory::Audio, FileCategory::Video],
+    ),
+    ("images", &[FileCategory::Image]),
+    ("audio", &[FileCategory::Audio]),
+    ("video", &[FileCategory::Video]),
+    ("archives", &[FileCategory::Archive]),
+    ("binaries", &[FileCategory::Binary]),
+];
+
+impl FileCategory {
+    pub fn of(path: &Path, is_dir: bool) -> Self {
+        if is_dir {
+            return FileCategory::Directory;
+        }
+        let ext = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .unwrap_or("")
+            .to_lowercase();
+
+        CATEGORY_EXTENSIONS
+            .iter()
+            .find(|(_, exts)| exts.contains(&ext.as_str()))
+            .map_or(FileCategory::Other, |(category, _)| *category)
+    }
+
+    /// The extensions that put a file in this category; none for
+    /// `Directory` and `Other`
+    pub fn extensions(self) -> &'static [&'static str] {
+        CATEGORY_EXTENSIONS
+            .iter()
+            .find(|(category, _)| *category == self)
+            .map_or(&[], |(_, exts)| exts)
+    }
+
+    /// The categories a `category:` name stands for (`media` is images,
+    /// audio and video), matched case-insensitively; a trailing `s` is
+    /// optional, so `category:archive` works too
+    pub fn named(name: &str) -> Option<&'static [FileCategory]> {
+        let name = name.to_lowercase();
+        CATEGORY_NAMES
+            .iter()
+            .find(|(n, _)| *n == name || n.strip_suffix('s') == Some(name.as_str()))
+            .map(|(_, categories)| *categories)
+    }
 }
 
 #[derive(Debug, Clone)]
 pub struct IndexProgress {
     pub files_indexed: u64,
     pub estimated_total: u64,
+    /// Files gone through per second over the last few seconds of a pass;
+    /// 0 outside `Indexing` and while it's too early to tell
+    pub files_per_sec: f64,
+    /// Seconds left at `files_per_sec`, when there's a rate to go by
+    pub eta_seconds: Option<u64>,
     pub status: IndexStatus,
 }
 
+/// At most this many failures are listed in [`IndexStats::failures`]; the
+/// rest are only counted
+pub const MAX_LISTED_FAILURES: usize = 1000;
+
 #[derive(Debug, Clone, Default)]
 pub struct IndexStats {
     pub added: u64,
     pub updated: u64,
     pub deleted: u64,
+    /// Files whose content couldn't be read, or that couldn't be indexed at all
+    pub failed: u64,
+    /// The first of them, with the reason
+    pub failures: Vec<(PathBuf, String)>,
 }
 
 impl IndexStats {
     pub fn has_changes(&self) -> bool {
         self.added > 0 || self.updated > 0 || self.deleted > 0
     }
+
+    pub fn record_failure(&mut self, path: &Path, error: impl std::fmt::Display) {
+        self.failed += 1;
+        if self.failures.len() < MAX_LISTED_FAILURES {
+            self.failures.push((path.to_path_buf(), error.to_string()));
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -56,7 +215,11 @@ pub enum IndexStatus {
     Indexing,
     Committing,
     Ready(Option<IndexStats>),
+    /// Ready, and changes on disk are applied as they happen
+    Watching(Option<IndexStats>),
     Error(String),
+    /// No folder is configured to index; waiting for the user to pick one
+    NoRoots,
 }
 
 impl std::fmt::Display for IndexStatus {
@@ -67,7 +230,9 @@ impl std::fmt::Display for IndexStatus {
             IndexStatus::Indexing => write!(f, "Indexing..."),
             IndexStatus::Committing => write!(f, "Committing..."),
             IndexStatus::Ready(_) => write!(f, "Ready"),
+            IndexStatus::Watching(_) => write!(f, "Watching for changes"),
             IndexStatus::Error(e) => write!(f, "Error: {}", e),
+            IndexStatus::NoRoots => write!(f, "No folder to index"),
         }
     }
 }